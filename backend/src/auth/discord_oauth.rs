@@ -85,6 +85,30 @@ pub async fn get_user_guilds(
         .await
 }
 
+#[derive(Debug, Deserialize)]
+pub struct DiscordMember {
+    pub roles: Vec<String>,
+}
+
+/// Fetch a guild member with the bot token. `None` if they are not in the guild.
+pub async fn get_guild_member(
+    http: &reqwest::Client,
+    bot_token: &str,
+    guild_id: &str,
+    user_id: &str,
+) -> Result<Option<DiscordMember>, reqwest::Error> {
+    let response = http
+        .get(format!("https://discord.com/api/guilds/{guild_id}/members/{user_id}"))
+        .header("Authorization", format!("Bot {bot_token}"))
+        .send()
+        .await?;
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    response.error_for_status()?.json::<DiscordMember>().await.map(Some)
+}
+
 /// Check if user has ADMINISTRATOR (0x8) or MANAGE_GUILD (0x20) permission
 pub fn has_guild_access(permissions: &str) -> bool {
     if let Ok(perms) = permissions.parse::<u64>() {
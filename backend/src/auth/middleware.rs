@@ -1,11 +1,20 @@
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
 use axum::{
-    extract::{FromRequestParts, State},
+    extract::{ConnectInfo, FromRequestParts, Path, State},
     http::{header, request::Parts},
 };
 use sqlx::SqlitePool;
 
+use crate::auth::discord_oauth::{self, has_guild_access};
 use crate::{error::AppError, state::AppState};
 
+/// How long a user's manageable-guild list is trusted before Discord is asked again.
+const GUILD_ACCESS_TTL: Duration = Duration::from_secs(300);
+
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct AuthUser {
     pub id: String,
@@ -57,6 +66,152 @@ impl FromRequestParts<AppState> for BotAuth {
     }
 }
 
+/// Authenticated user who manages the guild in the `{id}` path segment.
+///
+/// Rejects with `Forbidden` unless the user has ADMINISTRATOR or MANAGE_GUILD
+/// in that guild. Use this instead of `AuthUser` on every guild-scoped route.
+#[derive(Debug, Clone)]
+pub struct GuildAdmin {
+    pub user: AuthUser,
+    pub meta: RequestMeta,
+}
+
+/// Request details recorded alongside dashboard audit log entries.
+#[derive(Debug, Clone)]
+pub struct RequestMeta {
+    pub method: String,
+    pub path: String,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl RequestMeta {
+    fn from_parts(parts: &Parts, trusted_proxies: &[IpAddr]) -> Self {
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+
+        Self {
+            method: parts.method.to_string(),
+            path: parts.uri.path().to_string(),
+            ip_address: peer.map(|peer| client_ip(parts, peer, trusted_proxies).to_string()),
+            user_agent: parts
+                .headers
+                .get(header::USER_AGENT)
+                .and_then(|v| v.to_str().ok())
+                .map(String::from),
+        }
+    }
+}
+
+/// The client's address. `X-Forwarded-For` is only honoured when the peer
+/// is a trusted proxy; it is then read right to left, skipping further
+/// trusted hops, since anything left of them was supplied by the client.
+fn client_ip(parts: &Parts, peer: IpAddr, trusted_proxies: &[IpAddr]) -> IpAddr {
+    if !trusted_proxies.contains(&peer) {
+        return peer;
+    }
+
+    let forwarded: Vec<IpAddr> = parts
+        .headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .filter_map(|ip| ip.trim().parse().ok())
+        .collect();
+
+    forwarded
+        .iter()
+        .rev()
+        .find(|ip| !trusted_proxies.contains(ip))
+        .or(forwarded.first())
+        .copied()
+        .unwrap_or(peer)
+}
+
+impl FromRequestParts<AppState> for GuildAdmin {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let user = AuthUser::from_request_parts(parts, state).await?;
+
+        let Path(params) = Path::<HashMap<String, String>>::from_request_parts(parts, state)
+            .await
+            .map_err(|_| AppError::BadRequest("Missing guild ID in path".to_string()))?;
+        let guild_id = params
+            .get("id")
+            .cloned()
+            .ok_or_else(|| AppError::BadRequest("Missing guild ID in path".to_string()))?;
+
+        if !can_manage_guild(state, &user, &guild_id).await? {
+            return Err(AppError::Forbidden);
+        }
+
+        Ok(GuildAdmin {
+            user,
+            meta: RequestMeta::from_parts(parts, &state.config.trusted_proxies),
+        })
+    }
+}
+
+/// Cached result of a `/users/@me/guilds` lookup for one session.
+#[derive(Debug, Clone)]
+pub struct GuildAccessEntry {
+    guild_ids: Arc<HashSet<String>>,
+    fetched_at: Instant,
+}
+
+/// Per-session cache of manageable guild IDs, keyed by OAuth access token.
+pub type GuildAccessCache = tokio::sync::RwLock<HashMap<String, GuildAccessEntry>>;
+
+/// Whether the user has ADMINISTRATOR or MANAGE_GUILD in the given guild.
+pub async fn can_manage_guild(
+    state: &AppState,
+    user: &AuthUser,
+    guild_id: &str,
+) -> Result<bool, AppError> {
+    Ok(manageable_guild_ids(state, user).await?.contains(guild_id))
+}
+
+/// IDs of every guild the user can manage, served from the session cache
+/// when fresh and refreshed from Discord otherwise.
+pub async fn manageable_guild_ids(
+    state: &AppState,
+    user: &AuthUser,
+) -> Result<Arc<HashSet<String>>, AppError> {
+    if let Some(entry) = state.guild_access.read().await.get(&user.access_token)
+        && entry.fetched_at.elapsed() < GUILD_ACCESS_TTL
+    {
+        return Ok(entry.guild_ids.clone());
+    }
+
+    let guilds = discord_oauth::get_user_guilds(&state.http, &user.access_token)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch guilds: {e}")))?;
+
+    let guild_ids: Arc<HashSet<String>> = Arc::new(
+        guilds
+            .into_iter()
+            .filter(|g| g.permissions.as_deref().map(has_guild_access).unwrap_or(false))
+            .map(|g| g.id)
+            .collect(),
+    );
+
+    let mut cache = state.guild_access.write().await;
+    cache.retain(|_, entry| entry.fetched_at.elapsed() < GUILD_ACCESS_TTL);
+    cache.insert(
+        user.access_token.clone(),
+        GuildAccessEntry {
+            guild_ids: guild_ids.clone(),
+            fetched_at: Instant::now(),
+        },
+    );
+
+    Ok(guild_ids)
+}
+
 async fn get_session_user(db: &SqlitePool, session_id: &str) -> Result<Option<AuthUser>, AppError> {
     let row = sqlx::query_as::<_, SessionRow>(
         "SELECT user_id, username, avatar, access_token, expires_at FROM sessions WHERE id = ?",
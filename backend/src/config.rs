@@ -1,4 +1,5 @@
 use std::env;
+use std::net::IpAddr;
 
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -11,6 +12,10 @@ pub struct Config {
     pub client_secret: String,
     pub bot_api_key: String,
     pub mcp_port: u16,
+    /// Directory for transcripts archived with `directory` storage.
+    pub transcript_archive_dir: String,
+    /// Reverse proxies whose `X-Forwarded-For` header is believed.
+    pub trusted_proxies: Vec<IpAddr>,
 }
 
 impl Config {
@@ -34,6 +39,13 @@ impl Config {
                 .ok()
                 .and_then(|p| p.parse().ok())
                 .unwrap_or(3001),
+            transcript_archive_dir: env::var("TRANSCRIPT_ARCHIVE_DIR")
+                .unwrap_or_else(|_| "./data/transcripts".into()),
+            trusted_proxies: env::var("TRUSTED_PROXIES")
+                .unwrap_or_default()
+                .split(',')
+                .filter_map(|ip| ip.trim().parse().ok())
+                .collect(),
         }
     }
 }
@@ -0,0 +1,9 @@
+-- Dashboard audit trail: request metadata and structured config diffs
+ALTER TABLE dashboard_logs ADD COLUMN changes TEXT; -- JSON object { column: { before, after } }
+ALTER TABLE dashboard_logs ADD COLUMN method TEXT;
+ALTER TABLE dashboard_logs ADD COLUMN path TEXT;
+ALTER TABLE dashboard_logs ADD COLUMN ip_address TEXT;
+ALTER TABLE dashboard_logs ADD COLUMN user_agent TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_dashboard_logs_action ON dashboard_logs(guild_id, action, created_at);
+CREATE INDEX IF NOT EXISTS idx_dashboard_logs_user ON dashboard_logs(guild_id, user_id, created_at);
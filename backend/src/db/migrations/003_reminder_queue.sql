@@ -0,0 +1,17 @@
+-- Reminders become a delivery queue the bot claims from instead of rows the
+-- scheduler deletes. Recurring reminders are rescheduled on acknowledgement.
+ALTER TABLE reminders ADD COLUMN status TEXT NOT NULL DEFAULT 'pending'; -- pending, due, claimed, delivered, failed
+ALTER TABLE reminders ADD COLUMN recurrence TEXT; -- NULL (one-off), daily, weekly, cron
+ALTER TABLE reminders ADD COLUMN cron_expr TEXT; -- 5-field cron expression when recurrence = 'cron'
+ALTER TABLE reminders ADD COLUMN attempts INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE reminders ADD COLUMN lease_expires_at TEXT;
+ALTER TABLE reminders ADD COLUMN last_error TEXT;
+ALTER TABLE reminders ADD COLUMN delivered_at TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_reminders_status ON reminders(status, remind_at);
+
+-- The queue compares remind_at with datetime('now') as text, so bring rows
+-- stored in other formats (e.g. `YYYY-MM-DDTHH:MM:SSZ`) to SQLite's UTC
+-- format. Values SQLite cannot parse are left as they are.
+UPDATE reminders SET remind_at = COALESCE(datetime(remind_at), remind_at)
+WHERE remind_at != COALESCE(datetime(remind_at), remind_at);
@@ -0,0 +1,21 @@
+-- Expired temp punishments are kept as lifted records instead of deleted,
+-- and the unban/unmute the bot must perform is queued in an outbox.
+ALTER TABLE temp_punishments ADD COLUMN lifted_at TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_temp_punishments_active ON temp_punishments(lifted_at, expires_at);
+
+-- Bot outbox: actions the bot claims (over HTTP or its WebSocket) and acks
+CREATE TABLE IF NOT EXISTS pending_actions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    guild_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    action_type TEXT NOT NULL, -- 'unban' or 'unmute'
+    temp_punishment_id INTEGER REFERENCES temp_punishments(id) ON DELETE SET NULL,
+    status TEXT NOT NULL DEFAULT 'pending', -- pending, claimed, done, failed
+    attempts INTEGER NOT NULL DEFAULT 0,
+    lease_expires_at TEXT,
+    last_error TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    completed_at TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_pending_actions_status ON pending_actions(status, created_at);
@@ -0,0 +1,29 @@
+-- Per-guild XP. `users.xp` / `users.level` are kept for history but no longer
+-- written; every XP grant now targets a (guild, user) pair.
+CREATE TABLE IF NOT EXISTS member_levels (
+    guild_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    xp INTEGER NOT NULL DEFAULT 0,
+    level INTEGER NOT NULL DEFAULT 0,
+    last_xp_at TEXT, -- last message-driven grant, for the guild's xp_cooldown
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (guild_id, user_id)
+);
+CREATE INDEX IF NOT EXISTS idx_member_levels_rank ON member_levels(guild_id, xp DESC);
+
+-- Guild membership was never recorded, so carry each user's existing global
+-- XP only into guilds with evidence they are a member there (a ticket,
+-- warn, temp punishment, suggestion or reminder). Other guilds start empty
+-- and fill in as members earn XP there.
+INSERT OR IGNORE INTO member_levels (guild_id, user_id, xp, level)
+SELECT m.guild_id, u.id, u.xp, u.level
+FROM users u
+JOIN (
+    SELECT guild_id, user_id FROM tickets
+    UNION SELECT guild_id, target_user_id FROM warns
+    UNION SELECT guild_id, user_id FROM temp_punishments
+    UNION SELECT guild_id, user_id FROM suggestions
+    UNION SELECT guild_id, user_id FROM reminders
+) m ON m.user_id = u.id
+WHERE u.xp > 0 OR u.level > 0;
@@ -0,0 +1,12 @@
+-- Per-guild level curve. The default ('linear', step 100) matches what the
+-- bot's /bot/xp endpoint has always used, so existing levels stay put.
+ALTER TABLE guilds ADD COLUMN level_curve TEXT NOT NULL DEFAULT 'linear'; -- linear, quadratic, custom
+ALTER TABLE guilds ADD COLUMN level_curve_step INTEGER NOT NULL DEFAULT 100; -- linear: level N costs N * step XP
+ALTER TABLE guilds ADD COLUMN level_curve_table TEXT; -- custom: JSON array of total XP for level 1, 2, ...
+
+-- Recompute bookkeeping: levels are stale while levels_version < level_curve_version
+ALTER TABLE guilds ADD COLUMN level_curve_version INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE guilds ADD COLUMN levels_version INTEGER NOT NULL DEFAULT 0;
+
+-- Stored levels were written by two disagreeing formulas; fix them all once
+UPDATE guilds SET level_curve_version = 1;
@@ -0,0 +1,14 @@
+-- Roles granted or removed when members reach a level
+CREATE TABLE IF NOT EXISTS level_rewards (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    guild_id TEXT NOT NULL,
+    level INTEGER NOT NULL,
+    role_id TEXT NOT NULL,
+    action TEXT NOT NULL DEFAULT 'add', -- 'add' or 'remove'
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE(guild_id, level, role_id)
+);
+CREATE INDEX IF NOT EXISTS idx_level_rewards_guild ON level_rewards(guild_id, level);
+
+-- 'stack' keeps every earned reward role; 'replace' keeps only the highest
+ALTER TABLE guilds ADD COLUMN level_reward_mode TEXT NOT NULL DEFAULT 'stack';
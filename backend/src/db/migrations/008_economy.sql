@@ -0,0 +1,38 @@
+-- Economy: per-guild balances with daily streaks, and an append-only ledger
+-- of every balance change. `users.balance` / `users.last_daily` were never
+-- written and are kept only for history, like `users.xp`.
+CREATE TABLE IF NOT EXISTS member_balances (
+    guild_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    balance INTEGER NOT NULL DEFAULT 0,
+    last_daily TEXT, -- last /daily claim in this guild
+    daily_streak INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (guild_id, user_id)
+);
+CREATE INDEX IF NOT EXISTS idx_member_balances_rank ON member_balances(guild_id, balance DESC);
+
+CREATE TABLE IF NOT EXISTS transactions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id TEXT NOT NULL,
+    guild_id TEXT NOT NULL, -- guild whose balance changed
+    amount INTEGER NOT NULL, -- signed: positive credits, negative debits
+    balance_after INTEGER NOT NULL,
+    kind TEXT NOT NULL, -- daily, transfer_in, transfer_out, grant, take
+    counterparty_id TEXT, -- other user in a transfer
+    actor_id TEXT, -- dashboard user for grant/take
+    reason TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+CREATE INDEX IF NOT EXISTS idx_transactions_member ON transactions(guild_id, user_id, id DESC);
+
+CREATE TRIGGER IF NOT EXISTS transactions_no_update BEFORE UPDATE ON transactions
+BEGIN
+    SELECT RAISE(ABORT, 'transactions are append-only');
+END;
+
+CREATE TRIGGER IF NOT EXISTS transactions_no_delete BEFORE DELETE ON transactions
+BEGIN
+    SELECT RAISE(ABORT, 'transactions are append-only');
+END;
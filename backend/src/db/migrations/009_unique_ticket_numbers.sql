@@ -0,0 +1,32 @@
+-- Ticket numbers were read back from guilds.ticket_counter (or sent by the
+-- bot) non-atomically, so concurrent opens could share a number. Keep the
+-- earliest ticket for each (guild, number) and move later duplicates past the
+-- guild's highest number, in id order.
+CREATE TEMP TABLE ticket_renumber AS
+SELECT d.id,
+       (SELECT MAX(m.number) FROM tickets m WHERE m.guild_id = d.guild_id)
+           + ROW_NUMBER() OVER (PARTITION BY d.guild_id ORDER BY d.id) AS new_number
+FROM tickets d
+WHERE EXISTS (
+    SELECT 1 FROM tickets e
+    WHERE e.guild_id = d.guild_id AND e.number = d.number AND e.id < d.id
+);
+
+UPDATE tickets
+SET number = (SELECT r.new_number FROM ticket_renumber r WHERE r.id = tickets.id)
+WHERE id IN (SELECT id FROM ticket_renumber);
+
+UPDATE transcripts
+SET ticket_number = (SELECT t.number FROM tickets t WHERE t.id = transcripts.ticket_id)
+WHERE ticket_id IN (SELECT id FROM ticket_renumber);
+
+DROP TABLE ticket_renumber;
+
+-- The counter must never hand out a number that is already taken
+UPDATE guilds
+SET ticket_counter = MAX(
+    ticket_counter,
+    (SELECT COALESCE(MAX(t.number), 0) FROM tickets t WHERE t.guild_id = guilds.id)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_tickets_guild_number ON tickets(guild_id, number);
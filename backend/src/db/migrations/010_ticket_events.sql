@@ -0,0 +1,17 @@
+-- Ticket history: every status transition, claim and priority change
+CREATE TABLE IF NOT EXISTS ticket_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    ticket_id INTEGER NOT NULL REFERENCES tickets(id) ON DELETE CASCADE,
+    guild_id TEXT NOT NULL,
+    event_type TEXT NOT NULL, -- created, status, claim, priority
+    from_value TEXT,
+    to_value TEXT,
+    actor_id TEXT NOT NULL,
+    note TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+CREATE INDEX IF NOT EXISTS idx_ticket_events_ticket ON ticket_events(ticket_id, id);
+
+-- Statuses are now a fixed set: open, awaiting_user, on_hold, review, closed
+UPDATE tickets SET status = 'open'
+WHERE status NOT IN ('open', 'awaiting_user', 'on_hold', 'review', 'closed');
@@ -0,0 +1,22 @@
+-- Messages posted in ticket channels, streamed by the bot. Transcripts are
+-- rendered from these rows when a ticket closes.
+CREATE TABLE IF NOT EXISTS ticket_messages (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    ticket_id INTEGER NOT NULL REFERENCES tickets(id) ON DELETE CASCADE,
+    guild_id TEXT NOT NULL,
+    message_id TEXT NOT NULL UNIQUE, -- Discord message ID
+    author_id TEXT NOT NULL,
+    author_name TEXT NOT NULL,
+    author_avatar TEXT,
+    author_bot INTEGER NOT NULL DEFAULT 0,
+    content TEXT NOT NULL DEFAULT '',
+    attachments TEXT NOT NULL DEFAULT '[]', -- JSON array of {name, url, content_type, size}
+    embeds TEXT NOT NULL DEFAULT '[]', -- JSON array of Discord embed objects
+    edited_at TEXT,
+    deleted_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+CREATE INDEX IF NOT EXISTS idx_ticket_messages_ticket ON ticket_messages(ticket_id, created_at);
+
+-- Theme the stored HTML was rendered with
+ALTER TABLE transcripts ADD COLUMN theme TEXT NOT NULL DEFAULT 'dark';
@@ -0,0 +1,15 @@
+-- Signed, expiring links that let someone view a transcript without a
+-- dashboard login. The link's id is the JWT's `jti`.
+CREATE TABLE IF NOT EXISTS transcript_share_links (
+    id TEXT PRIMARY KEY NOT NULL, -- UUID
+    transcript_id TEXT NOT NULL REFERENCES transcripts(id) ON DELETE CASCADE,
+    guild_id TEXT NOT NULL,
+    created_by TEXT NOT NULL,
+    expires_at TEXT NOT NULL,
+    revoked_at TEXT,
+    revoked_by TEXT,
+    view_count INTEGER NOT NULL DEFAULT 0,
+    last_viewed_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+CREATE INDEX IF NOT EXISTS idx_transcript_share_links_transcript ON transcript_share_links(transcript_id);
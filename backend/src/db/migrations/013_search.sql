@@ -0,0 +1,60 @@
+-- Full-text search over ticket subjects, ticket messages and transcripts.
+
+-- Ticket subjects, kept in sync with `tickets` by triggers
+CREATE VIRTUAL TABLE IF NOT EXISTS tickets_fts USING fts5(
+    subject,
+    content = 'tickets',
+    content_rowid = 'id'
+);
+
+CREATE TRIGGER IF NOT EXISTS tickets_fts_ai AFTER INSERT ON tickets BEGIN
+    INSERT INTO tickets_fts (rowid, subject) VALUES (new.id, new.subject);
+END;
+CREATE TRIGGER IF NOT EXISTS tickets_fts_ad AFTER DELETE ON tickets BEGIN
+    INSERT INTO tickets_fts (tickets_fts, rowid, subject) VALUES ('delete', old.id, old.subject);
+END;
+CREATE TRIGGER IF NOT EXISTS tickets_fts_au AFTER UPDATE OF subject ON tickets BEGIN
+    INSERT INTO tickets_fts (tickets_fts, rowid, subject) VALUES ('delete', old.id, old.subject);
+    INSERT INTO tickets_fts (rowid, subject) VALUES (new.id, new.subject);
+END;
+
+INSERT INTO tickets_fts (tickets_fts) VALUES ('rebuild');
+
+-- Ticket message text and author names, kept in sync with `ticket_messages`
+CREATE VIRTUAL TABLE IF NOT EXISTS ticket_messages_fts USING fts5(
+    content,
+    author_name,
+    content = 'ticket_messages',
+    content_rowid = 'id'
+);
+
+CREATE TRIGGER IF NOT EXISTS ticket_messages_fts_ai AFTER INSERT ON ticket_messages BEGIN
+    INSERT INTO ticket_messages_fts (rowid, content, author_name)
+    VALUES (new.id, new.content, new.author_name);
+END;
+CREATE TRIGGER IF NOT EXISTS ticket_messages_fts_ad AFTER DELETE ON ticket_messages BEGIN
+    INSERT INTO ticket_messages_fts (ticket_messages_fts, rowid, content, author_name)
+    VALUES ('delete', old.id, old.content, old.author_name);
+END;
+CREATE TRIGGER IF NOT EXISTS ticket_messages_fts_au AFTER UPDATE OF content, author_name ON ticket_messages BEGIN
+    INSERT INTO ticket_messages_fts (ticket_messages_fts, rowid, content, author_name)
+    VALUES ('delete', old.id, old.content, old.author_name);
+    INSERT INTO ticket_messages_fts (rowid, content, author_name)
+    VALUES (new.id, new.content, new.author_name);
+END;
+
+INSERT INTO ticket_messages_fts (ticket_messages_fts) VALUES ('rebuild');
+
+-- Transcript text. Filled by the backend (the body is plain text, not the
+-- stored HTML); `transcripts.search_rowid` points at a transcript's row and
+-- stays NULL until it is indexed.
+CREATE VIRTUAL TABLE IF NOT EXISTS transcripts_fts USING fts5(subject, body);
+
+ALTER TABLE transcripts ADD COLUMN search_rowid INTEGER;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_transcripts_search_rowid ON transcripts(search_rowid);
+CREATE INDEX IF NOT EXISTS idx_transcripts_unindexed ON transcripts(created_at) WHERE search_rowid IS NULL;
+
+CREATE TRIGGER IF NOT EXISTS transcripts_fts_ad AFTER DELETE ON transcripts
+WHEN old.search_rowid IS NOT NULL BEGIN
+    DELETE FROM transcripts_fts WHERE rowid = old.search_rowid;
+END;
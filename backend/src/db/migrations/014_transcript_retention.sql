@@ -0,0 +1,15 @@
+-- Per-guild transcript retention. Transcripts older than the retention
+-- period are archived (body compressed with zstd) or deleted by the scheduler.
+ALTER TABLE guilds ADD COLUMN transcript_retention_days INTEGER; -- NULL keeps transcripts as they are forever
+ALTER TABLE guilds ADD COLUMN transcript_retention_action TEXT NOT NULL DEFAULT 'archive'; -- archive, delete
+ALTER TABLE guilds ADD COLUMN transcript_archive_storage TEXT NOT NULL DEFAULT 'inline'; -- inline, directory
+
+-- Where a transcript's HTML lives: 'html' in the html column, 'zstd' in
+-- html_zstd, 'file' in the zstd file at archive_path. Archived rows keep an
+-- empty html column.
+ALTER TABLE transcripts ADD COLUMN storage TEXT NOT NULL DEFAULT 'html';
+ALTER TABLE transcripts ADD COLUMN html_zstd BLOB;
+ALTER TABLE transcripts ADD COLUMN archive_path TEXT;
+ALTER TABLE transcripts ADD COLUMN archived_at TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_transcripts_guild_created ON transcripts(guild_id, created_at);
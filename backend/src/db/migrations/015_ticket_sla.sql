@@ -0,0 +1,32 @@
+-- SLA targets per guild and priority. Deadlines are copied onto each ticket
+-- when it opens or its priority changes, so later target edits don't move
+-- existing tickets' deadlines.
+CREATE TABLE IF NOT EXISTS ticket_sla_targets (
+    guild_id TEXT NOT NULL REFERENCES guilds(id) ON DELETE CASCADE,
+    priority TEXT NOT NULL, -- low, normal, high, urgent
+    first_response_minutes INTEGER, -- NULL: no first response target
+    resolution_minutes INTEGER, -- NULL: no resolution target
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (guild_id, priority)
+);
+
+ALTER TABLE tickets ADD COLUMN first_response_at TEXT;
+ALTER TABLE tickets ADD COLUMN first_response_by TEXT;
+ALTER TABLE tickets ADD COLUMN first_response_due_at TEXT;
+ALTER TABLE tickets ADD COLUMN resolution_due_at TEXT;
+
+-- Warnings and breaches already raised, so each is only sent once per deadline
+CREATE TABLE IF NOT EXISTS ticket_sla_alerts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    ticket_id INTEGER NOT NULL REFERENCES tickets(id) ON DELETE CASCADE,
+    guild_id TEXT NOT NULL,
+    target TEXT NOT NULL, -- first_response, resolution
+    level TEXT NOT NULL, -- warning, breach
+    due_at TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE (ticket_id, target, level, due_at)
+);
+
+-- Outbox actions about a ticket (SLA alerts) carry the ticket and details
+ALTER TABLE pending_actions ADD COLUMN ticket_id INTEGER REFERENCES tickets(id) ON DELETE CASCADE;
+ALTER TABLE pending_actions ADD COLUMN payload TEXT; -- JSON object
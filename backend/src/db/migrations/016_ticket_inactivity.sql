@@ -0,0 +1,16 @@
+-- Inactivity auto-close. After warn_hours without activity the bot posts a
+-- warning; close_hours after that warning, still without activity, the
+-- ticket is closed by the system. NULL turns the guild rule off.
+ALTER TABLE guilds ADD COLUMN ticket_inactivity_warn_hours INTEGER;
+ALTER TABLE guilds ADD COLUMN ticket_inactivity_close_hours INTEGER; -- NULL: warn only
+
+-- Per-category overrides: NULL uses the guild's rule, 0 turns it off
+ALTER TABLE ticket_categories ADD COLUMN inactivity_warn_hours INTEGER;
+ALTER TABLE ticket_categories ADD COLUMN inactivity_close_hours INTEGER;
+
+-- Pending inactivity warning; cleared whenever last_activity is bumped
+ALTER TABLE tickets ADD COLUMN inactivity_warned_at TEXT;
+-- 0 opts the ticket out of inactivity warnings and auto-close
+ALTER TABLE tickets ADD COLUMN auto_close INTEGER NOT NULL DEFAULT 1;
+
+CREATE INDEX IF NOT EXISTS idx_tickets_activity ON tickets(status, last_activity);
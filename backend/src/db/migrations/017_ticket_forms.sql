@@ -0,0 +1,25 @@
+-- Per-category ticket forms: the questions asked when opening a ticket
+CREATE TABLE IF NOT EXISTS ticket_form_questions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    category_id INTEGER NOT NULL REFERENCES ticket_categories(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    label TEXT NOT NULL,
+    style TEXT NOT NULL DEFAULT 'short', -- short, paragraph
+    required INTEGER NOT NULL DEFAULT 0,
+    min_length INTEGER,
+    max_length INTEGER,
+    placeholder TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE(category_id, position)
+);
+
+-- Answers given when a ticket was opened. The label is copied so answers
+-- still read right after the form is edited or the question removed.
+CREATE TABLE IF NOT EXISTS ticket_form_answers (
+    ticket_id INTEGER NOT NULL REFERENCES tickets(id) ON DELETE CASCADE,
+    question_id INTEGER REFERENCES ticket_form_questions(id) ON DELETE SET NULL,
+    position INTEGER NOT NULL,
+    label TEXT NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY (ticket_id, position)
+);
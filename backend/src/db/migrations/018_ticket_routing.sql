@@ -0,0 +1,17 @@
+-- Automatic ticket assignment: how new tickets pick a claimer from the
+-- guild's staff roster. manual, round_robin, least_open
+ALTER TABLE guilds ADD COLUMN ticket_routing TEXT NOT NULL DEFAULT 'manual';
+-- Per-category override; NULL uses the guild's policy
+ALTER TABLE ticket_categories ADD COLUMN routing TEXT;
+
+-- Staff that tickets can be assigned to
+CREATE TABLE IF NOT EXISTS ticket_staff (
+    guild_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    available INTEGER NOT NULL DEFAULT 1,
+    last_assigned_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (guild_id, user_id)
+);
+CREATE INDEX IF NOT EXISTS idx_tickets_claimed ON tickets(guild_id, claimed_by, status);
@@ -0,0 +1,32 @@
+-- Staff-only notes on tickets; never part of the transcript users see
+CREATE TABLE IF NOT EXISTS ticket_notes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    ticket_id INTEGER NOT NULL REFERENCES tickets(id) ON DELETE CASCADE,
+    guild_id TEXT NOT NULL,
+    author_id TEXT NOT NULL,
+    body TEXT NOT NULL,
+    pinned INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+CREATE INDEX IF NOT EXISTS idx_ticket_notes_ticket ON ticket_notes(ticket_id);
+
+-- Guild-defined tag vocabulary
+CREATE TABLE IF NOT EXISTS ticket_tags (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    guild_id TEXT NOT NULL,
+    name TEXT NOT NULL COLLATE NOCASE,
+    color TEXT, -- #rrggbb
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE(guild_id, name)
+);
+
+-- Tags attached to tickets
+CREATE TABLE IF NOT EXISTS ticket_tag_links (
+    ticket_id INTEGER NOT NULL REFERENCES tickets(id) ON DELETE CASCADE,
+    tag_id INTEGER NOT NULL REFERENCES ticket_tags(id) ON DELETE CASCADE,
+    added_by TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (ticket_id, tag_id)
+);
+CREATE INDEX IF NOT EXISTS idx_ticket_tag_links_tag ON ticket_tag_links(tag_id);
@@ -0,0 +1,11 @@
+-- Ticket opening policy, checked by the backend whenever the bot opens a
+-- ticket. NULL leaves a rule off.
+ALTER TABLE guilds ADD COLUMN ticket_max_open INTEGER;           -- active tickets per user
+ALTER TABLE guilds ADD COLUMN ticket_cooldown_minutes INTEGER;   -- wait after the user's last close
+ALTER TABLE guilds ADD COLUMN ticket_required_role TEXT;
+
+-- Per-category rules, applied on top of the guild's
+ALTER TABLE ticket_categories ADD COLUMN max_open INTEGER;       -- active tickets per user in the category
+ALTER TABLE ticket_categories ADD COLUMN required_role TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_tickets_user ON tickets(guild_id, user_id, status);
@@ -0,0 +1,22 @@
+-- Temporary blacklists: entries with expires_at are lifted by the scheduler.
+-- NULL keeps the entry until it is removed.
+ALTER TABLE ticket_blacklists ADD COLUMN expires_at TEXT;
+CREATE INDEX IF NOT EXISTS idx_ticket_blacklists_expiry ON ticket_blacklists(expires_at);
+
+-- Every add, removal and expiry, kept after the entry itself is gone
+CREATE TABLE IF NOT EXISTS ticket_blacklist_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    guild_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    action TEXT NOT NULL,          -- add, remove, expire
+    reason TEXT,
+    actor_id TEXT NOT NULL,
+    expires_at TEXT,               -- for adds: when the entry was set to lapse
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+CREATE INDEX IF NOT EXISTS idx_ticket_blacklist_events_user
+    ON ticket_blacklist_events(guild_id, user_id, created_at);
+
+-- Start the history from the entries that exist today
+INSERT INTO ticket_blacklist_events (guild_id, user_id, action, reason, actor_id, created_at)
+SELECT guild_id, user_id, 'add', reason, added_by, created_at FROM ticket_blacklists;
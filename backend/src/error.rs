@@ -2,6 +2,8 @@ use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use serde_json::json;
 
+use crate::models::TicketDenial;
+
 #[derive(Debug, thiserror::Error)]
 pub enum AppError {
     #[error("Not found: {0}")]
@@ -16,6 +18,10 @@ pub enum AppError {
     #[error("Forbidden")]
     Forbidden,
 
+    /// The ticket open policy refused; the reason goes back to the caller.
+    #[error("Ticket denied: {}", .0.message())]
+    TicketDenied(TicketDenial),
+
     #[error("Database error: {0}")]
     Database(#[from] sqlx::Error),
 
@@ -33,6 +39,10 @@ impl IntoResponse for AppError {
             AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
             AppError::Unauthorized => (StatusCode::UNAUTHORIZED, "Unauthorized".into()),
             AppError::Forbidden => (StatusCode::FORBIDDEN, "Forbidden".into()),
+            AppError::TicketDenied(denial) => {
+                let body = axum::Json(json!({ "error": denial.message(), "denial": denial }));
+                return (StatusCode::FORBIDDEN, body).into_response();
+            }
             AppError::Database(e) => {
                 tracing::error!("Database error: {e}");
                 (StatusCode::INTERNAL_SERVER_ERROR, "Database error".into())
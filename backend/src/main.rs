@@ -7,6 +7,7 @@ mod models;
 mod routes;
 mod services;
 mod state;
+mod timestamp;
 mod ws;
 
 use crate::config::Config;
@@ -48,7 +49,7 @@ async fn main() -> anyhow::Result<()> {
     // Start scheduler in background
     let scheduler_state = state.clone();
     tokio::spawn(async move {
-        services::scheduler::run_scheduler(&scheduler_state.db).await;
+        services::scheduler::run_scheduler(&scheduler_state).await;
     });
 
     // Build router
@@ -57,7 +58,11 @@ async fn main() -> anyhow::Result<()> {
     // Start web server
     let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{web_port}")).await?;
     tracing::info!("Backend listening on 0.0.0.0:{web_port}");
-    axum::serve(listener, app).await?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    )
+    .await?;
 
     Ok(())
 }
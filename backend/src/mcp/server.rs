@@ -111,6 +111,7 @@ impl McpServer {
                 tools::moderation::get_user_info(&self.state.db, arguments).await
             }
             "messages.search" => tools::messages::search(&self.state.db, arguments).await,
+            "transcripts.search" => tools::transcripts::search(&self.state.db, arguments).await,
             "guild.config" => tools::config::get_config(&self.state.db, arguments).await,
             "actions.send_message" => {
                 // This needs to go through the bot - return instruction
@@ -155,7 +156,7 @@ impl McpServer {
                     "type": "object",
                     "properties": {
                         "guild_id": { "type": "string", "description": "Discord guild ID" },
-                        "status": { "type": "string", "enum": ["open", "closed", "review"], "description": "Filter by status" }
+                        "status": { "type": "string", "enum": ["open", "awaiting_user", "on_hold", "review", "closed"], "description": "Filter by status" }
                     },
                     "required": ["guild_id"]
                 }),
@@ -197,7 +198,7 @@ impl McpServer {
             },
             ToolDefinition {
                 name: "moderation.get_user_info".into(),
-                description: "Get user profile info (XP, level, balance, warns)".into(),
+                description: "Get user profile info (plus guild XP, level, rank, balance and warns when guild_id is given)".into(),
                 input_schema: serde_json::json!({
                     "type": "object",
                     "properties": {
@@ -219,6 +220,25 @@ impl McpServer {
                     "required": ["channel_id"]
                 }),
             },
+            ToolDefinition {
+                name: "transcripts.search".into(),
+                description: "Full-text search over a guild's transcripts, ticket subjects and ticket messages, best matches first".into(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "guild_id": { "type": "string", "description": "Discord guild ID" },
+                        "query": { "type": "string", "description": "Words to find; end a word with * to match it as a prefix" },
+                        "type": { "type": "string", "enum": ["transcript", "ticket", "message"], "description": "Only return hits of this kind" },
+                        "user_id": { "type": "string", "description": "Ticket owner" },
+                        "category": { "type": "string" },
+                        "closed_by": { "type": "string", "description": "User ID who closed the ticket" },
+                        "from": { "type": "string", "description": "Earliest date (YYYY-MM-DD or RFC 3339)" },
+                        "to": { "type": "string", "description": "Latest date (YYYY-MM-DD or RFC 3339)" },
+                        "limit": { "type": "integer", "description": "Maximum results (default 10, max 50)" }
+                    },
+                    "required": ["guild_id", "query"]
+                }),
+            },
             ToolDefinition {
                 name: "guild.config".into(),
                 description: "Get guild configuration".into(),
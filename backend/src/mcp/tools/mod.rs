@@ -2,3 +2,4 @@ pub mod tickets;
 pub mod moderation;
 pub mod messages;
 pub mod config;
+pub mod transcripts;
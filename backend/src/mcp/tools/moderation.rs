@@ -2,6 +2,7 @@ use serde_json::Value;
 use sqlx::SqlitePool;
 
 use crate::models::warn::Warn;
+use crate::services::leveling;
 
 pub async fn get_warns(db: &SqlitePool, args: &Value) -> Result<Value, String> {
     let guild_id = args["guild_id"].as_str().ok_or("guild_id required")?;
@@ -30,7 +31,35 @@ pub async fn get_user_info(db: &SqlitePool, args: &Value) -> Result<Value, Strin
     .await
     .map_err(|e| e.to_string())?;
 
-    let warn_count = if let Some(guild_id) = args["guild_id"].as_str() {
+    let guild_id = args["guild_id"].as_str();
+
+    let (level, rank) = if let Some(guild_id) = guild_id {
+        let level = leveling::get_member(db, guild_id, user_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        let rank = leveling::get_rank(db, guild_id, user_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        (level, rank)
+    } else {
+        (None, None)
+    };
+
+    let balance = if let Some(guild_id) = guild_id {
+        sqlx::query_scalar::<_, i64>(
+            "SELECT balance FROM member_balances WHERE guild_id = ? AND user_id = ?",
+        )
+        .bind(guild_id)
+        .bind(user_id)
+        .fetch_optional(db)
+        .await
+        .map_err(|e| e.to_string())?
+        .unwrap_or(0)
+    } else {
+        0
+    };
+
+    let warn_count = if let Some(guild_id) = guild_id {
         sqlx::query_scalar::<_, i64>(
             "SELECT COUNT(*) FROM warns WHERE target_user_id = ? AND guild_id = ?",
         )
@@ -45,6 +74,9 @@ pub async fn get_user_info(db: &SqlitePool, args: &Value) -> Result<Value, Strin
 
     Ok(serde_json::json!({
         "user": user,
+        "level": level,
+        "rank": rank,
+        "balance": balance,
         "warn_count": warn_count
     }))
 }
@@ -1,11 +1,14 @@
 use serde_json::Value;
 use sqlx::SqlitePool;
 
-use crate::models::ticket::Ticket;
+use crate::models::ticket::{Ticket, TicketStatus};
+use crate::models::TranscriptNames;
+use crate::services::ticket;
 
 pub async fn list(db: &SqlitePool, args: &Value) -> Result<Value, String> {
     let guild_id = args["guild_id"].as_str().ok_or("guild_id required")?;
     let status = args["status"].as_str().unwrap_or("open");
+    let status = TicketStatus::parse(status).ok_or_else(|| format!("Invalid status '{status}'"))?;
 
     let tickets = sqlx::query_as::<_, Ticket>(
         "SELECT * FROM tickets WHERE guild_id = ? AND status = ? ORDER BY created_at DESC LIMIT 50",
@@ -36,14 +39,13 @@ pub async fn close(db: &SqlitePool, args: &Value) -> Result<Value, String> {
     let ticket_id = args["ticket_id"].as_i64().ok_or("ticket_id required")?;
     let closed_by = args["closed_by"].as_str().ok_or("closed_by required")?;
 
-    sqlx::query(
-        "UPDATE tickets SET status = 'closed', closed_by = ?, closed_at = datetime('now') WHERE id = ? AND status = 'open'",
-    )
-    .bind(closed_by)
-    .bind(ticket_id)
-    .execute(db)
-    .await
-    .map_err(|e| e.to_string())?;
+    let (ticket, transcript) = ticket::close_ticket(db, ticket_id, closed_by, &TranscriptNames::default())
+        .await
+        .map_err(|e| e.to_string())?;
 
-    Ok(serde_json::json!({ "status": "closed", "ticket_id": ticket_id }))
+    Ok(serde_json::json!({
+        "status": ticket.status,
+        "ticket_id": ticket_id,
+        "transcript_id": transcript.id,
+    }))
 }
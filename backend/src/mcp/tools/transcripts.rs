@@ -0,0 +1,31 @@
+use serde_json::Value;
+use sqlx::SqlitePool;
+
+use crate::models::{SearchFilters, SearchKind};
+use crate::services::search as search_service;
+
+pub async fn search(db: &SqlitePool, args: &Value) -> Result<Value, String> {
+    let guild_id = args["guild_id"].as_str().ok_or("guild_id required")?;
+    let query = args["query"].as_str().ok_or("query required")?;
+    let limit = args["limit"].as_i64().unwrap_or(10).clamp(1, 50);
+    let kind = match args["type"].as_str() {
+        Some(value) => Some(SearchKind::parse(value).ok_or_else(|| format!("Invalid type '{value}'"))?),
+        None => None,
+    };
+    let text = |key: &str| args[key].as_str().map(str::to_string);
+
+    let filters = SearchFilters {
+        kind,
+        user_id: text("user_id"),
+        category: text("category"),
+        closed_by: text("closed_by"),
+        from: text("from"),
+        to: text("to"),
+    };
+
+    let results = search_service::search(db, guild_id, query, &filters, limit, 0)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    serde_json::to_value(&results).map_err(|e| e.to_string())
+}
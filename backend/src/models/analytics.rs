@@ -0,0 +1,83 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Width of the time buckets in support analytics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnalyticsBucket {
+    #[default]
+    Day,
+    /// Weeks start on Monday.
+    Week,
+    Month,
+}
+
+impl AnalyticsBucket {
+    pub const ALL: [AnalyticsBucket; 3] = [AnalyticsBucket::Day, AnalyticsBucket::Week, AnalyticsBucket::Month];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AnalyticsBucket::Day => "day",
+            AnalyticsBucket::Week => "week",
+            AnalyticsBucket::Month => "month",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|b| b.as_str() == value)
+    }
+}
+
+/// Review and timing figures over a set of tickets. Times are in minutes
+/// from the ticket opening; unanswered and open tickets are left out of
+/// the medians.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SupportStats {
+    pub tickets: i64,
+    pub closed: i64,
+    pub rated: i64,
+    pub avg_rating: Option<f64>,
+    /// Review count per star rating, 1 through 5.
+    pub rating_distribution: BTreeMap<i64, i64>,
+    pub median_first_response_minutes: Option<f64>,
+    pub median_resolution_minutes: Option<f64>,
+}
+
+/// Support figures for one staff member. A ticket counts as handled by
+/// whoever claimed it and whoever closed it; `tickets` is the handled total.
+#[derive(Debug, Clone, Serialize)]
+pub struct StaffSupportStats {
+    pub user_id: String,
+    pub tickets_claimed: i64,
+    pub tickets_closed: i64,
+    #[serde(flatten)]
+    pub stats: SupportStats,
+}
+
+/// Support figures for one ticket category; `None` is uncategorized.
+#[derive(Debug, Clone, Serialize)]
+pub struct CategorySupportStats {
+    pub category: Option<String>,
+    #[serde(flatten)]
+    pub stats: SupportStats,
+}
+
+/// Support figures for tickets opened in one time bucket.
+#[derive(Debug, Clone, Serialize)]
+pub struct SupportBucketStats {
+    /// First day of the bucket (`YYYY-MM-DD`).
+    pub period: String,
+    #[serde(flatten)]
+    pub stats: SupportStats,
+}
+
+/// Response of the support-quality analytics.
+#[derive(Debug, Clone, Serialize)]
+pub struct SupportAnalytics {
+    pub bucket: AnalyticsBucket,
+    pub overall: SupportStats,
+    pub staff: Vec<StaffSupportStats>,
+    pub categories: Vec<CategorySupportStats>,
+    pub timeline: Vec<SupportBucketStats>,
+}
@@ -6,6 +6,12 @@ pub struct DashboardLog {
     pub user_id: String,
     pub action: String,
     pub details: String,
+    /// JSON object mapping changed columns to `{ "before", "after" }`.
+    pub changes: Option<String>,
+    pub method: Option<String>,
+    pub path: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
     pub created_at: String,
 }
 
@@ -26,6 +26,24 @@ pub struct GuildConfig {
     pub ticket_modal_placeholder: String,
     /// Stored as INTEGER 0/1 in SQLite.
     pub ticket_modal_required: i64,
+    /// Hours without activity before the bot warns; NULL disables auto-close.
+    pub ticket_inactivity_warn_hours: Option<i64>,
+    /// Hours after the warning before the ticket is closed; NULL only warns.
+    pub ticket_inactivity_close_hours: Option<i64>,
+    /// "manual", "round_robin", or "least_open".
+    pub ticket_routing: String,
+    /// Most active tickets a user may have at once; NULL is unlimited.
+    pub ticket_max_open: Option<i64>,
+    /// Minutes a user waits after their last ticket closed; NULL is none.
+    pub ticket_cooldown_minutes: Option<i64>,
+    /// Role a member needs to open tickets; NULL lets anyone.
+    pub ticket_required_role: Option<String>,
+    /// Days to keep transcripts as-is; NULL keeps them forever.
+    pub transcript_retention_days: Option<i64>,
+    /// "archive" or "delete", applied once the retention period is over.
+    pub transcript_retention_action: String,
+    /// Where archived transcripts go: "inline" or "directory".
+    pub transcript_archive_storage: String,
 
     // ── XP / Leveling ───────────────────────────────────────────────
     pub level_up_channel: Option<String>,
@@ -33,6 +51,14 @@ pub struct GuildConfig {
     pub xp_cooldown: i64,
     pub xp_min: i64,
     pub xp_max: i64,
+    /// "linear", "quadratic", or "custom".
+    pub level_curve: String,
+    /// XP per level step for the linear curve.
+    pub level_curve_step: i64,
+    /// JSON array of total XP per level for the custom curve.
+    pub level_curve_table: Option<String>,
+    /// "stack" keeps every earned reward role, "replace" only the highest.
+    pub level_reward_mode: String,
 
     // ── Welcome / Leave ─────────────────────────────────────────────
     pub welcome_channel: Option<String>,
@@ -116,6 +142,15 @@ pub struct UpdateGuildConfig {
     pub ticket_modal_label: Option<String>,
     pub ticket_modal_placeholder: Option<String>,
     pub ticket_modal_required: Option<i64>,
+    pub ticket_inactivity_warn_hours: Option<Option<i64>>,
+    pub ticket_inactivity_close_hours: Option<Option<i64>>,
+    pub ticket_routing: Option<String>,
+    pub ticket_max_open: Option<Option<i64>>,
+    pub ticket_cooldown_minutes: Option<Option<i64>>,
+    pub ticket_required_role: Option<Option<String>>,
+    pub transcript_retention_days: Option<Option<i64>>,
+    pub transcript_retention_action: Option<String>,
+    pub transcript_archive_storage: Option<String>,
 
     // XP / Leveling
     pub level_up_channel: Option<Option<String>>,
@@ -123,6 +158,10 @@ pub struct UpdateGuildConfig {
     pub xp_cooldown: Option<i64>,
     pub xp_min: Option<i64>,
     pub xp_max: Option<i64>,
+    pub level_curve: Option<String>,
+    pub level_curve_step: Option<i64>,
+    pub level_curve_table: Option<Option<String>>,
+    pub level_reward_mode: Option<String>,
 
     // Welcome / Leave
     pub welcome_channel: Option<Option<String>>,
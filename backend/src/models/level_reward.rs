@@ -0,0 +1,35 @@
+/// Row from the `level_rewards` table.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, sqlx::FromRow)]
+pub struct LevelReward {
+    pub id: i64,
+    pub guild_id: String,
+    /// Level at which the reward applies.
+    pub level: i64,
+    pub role_id: String,
+    /// "add" to grant the role, "remove" to take it away.
+    pub action: String,
+    pub created_at: String,
+}
+
+/// Payload for creating a new level reward.
+#[derive(Debug, serde::Deserialize)]
+pub struct CreateLevelReward {
+    pub level: i64,
+    pub role_id: String,
+    pub action: Option<String>,
+}
+
+/// Partial-update payload for a level reward.
+#[derive(Debug, serde::Deserialize)]
+pub struct UpdateLevelReward {
+    pub level: Option<i64>,
+    pub role_id: Option<String>,
+    pub action: Option<String>,
+}
+
+/// Role changes the bot should apply to a member after a level change.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct RoleChanges {
+    pub roles_to_add: Vec<String>,
+    pub roles_to_remove: Vec<String>,
+}
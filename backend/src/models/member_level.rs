@@ -0,0 +1,23 @@
+/// Row from the `member_levels` table: a user's XP within one guild.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, sqlx::FromRow)]
+pub struct MemberLevel {
+    pub guild_id: String,
+    /// Discord user ID.
+    pub user_id: String,
+    pub xp: i64,
+    pub level: i64,
+    /// Timestamp of the last message-driven XP grant (cooldown anchor).
+    pub last_xp_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// A member's position on a guild leaderboard.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, sqlx::FromRow)]
+pub struct LeaderboardEntry {
+    /// 1-based rank by XP within the guild; ties share a rank.
+    pub rank: i64,
+    pub user_id: String,
+    pub xp: i64,
+    pub level: i64,
+}
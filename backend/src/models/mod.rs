@@ -1,31 +1,53 @@
+pub mod analytics;
 pub mod auto_role;
 pub mod bot_config;
 pub mod dashboard_log;
 pub mod embed_template;
 pub mod giveaway;
 pub mod guild;
+pub mod level_reward;
+pub mod member_level;
+pub mod pending_action;
 pub mod reaction_role;
 pub mod reminder;
+pub mod search;
 pub mod session;
+pub mod sla;
 pub mod suggestion;
 pub mod temp_punishment;
 pub mod ticket;
+pub mod ticket_form;
+pub mod ticket_message;
+pub mod ticket_note;
+pub mod ticket_staff;
+pub mod transaction;
 pub mod transcript;
 pub mod user;
 pub mod warn;
 
+pub use analytics::*;
 pub use auto_role::*;
 pub use bot_config::*;
 pub use dashboard_log::*;
 pub use embed_template::*;
 pub use giveaway::*;
 pub use guild::*;
+pub use level_reward::*;
+pub use member_level::*;
+pub use pending_action::*;
 pub use reaction_role::*;
 pub use reminder::*;
+pub use search::*;
 pub use session::*;
+pub use sla::*;
 pub use suggestion::*;
 pub use temp_punishment::*;
 pub use ticket::*;
+pub use ticket_form::*;
+pub use ticket_message::*;
+pub use ticket_note::*;
+pub use ticket_staff::*;
+pub use transaction::*;
 pub use transcript::*;
 pub use user::*;
 pub use warn::*;
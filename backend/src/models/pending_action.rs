@@ -0,0 +1,26 @@
+/// Row from the `pending_actions` table: something the bot must do in
+/// Discord, such as lifting an expired temp punishment.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, sqlx::FromRow)]
+pub struct PendingAction {
+    pub id: i64,
+    pub guild_id: String,
+    pub user_id: String,
+    /// "unban" or "unmute" for temp punishments; "sla_warning", "sla_breach",
+    /// "inactivity_warning", "inactivity_close" or "ticket_reassigned" for
+    /// ticket notices.
+    pub action_type: String,
+    /// The temp punishment this action lifts, if any.
+    pub temp_punishment_id: Option<i64>,
+    /// "pending", "claimed", "done", or "failed".
+    pub status: String,
+    pub attempts: i64,
+    /// When the bot's claim lapses and the action becomes pending again.
+    pub lease_expires_at: Option<String>,
+    pub last_error: Option<String>,
+    pub created_at: String,
+    pub completed_at: Option<String>,
+    /// The ticket this action is about, if any.
+    pub ticket_id: Option<i64>,
+    /// JSON-encoded details for the bot.
+    pub payload: Option<String>,
+}
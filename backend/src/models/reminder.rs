@@ -9,6 +9,18 @@ pub struct Reminder {
     /// ISO-8601 timestamp when the reminder should fire.
     pub remind_at: String,
     pub created_at: String,
+    /// "pending", "due", "claimed", "delivered", or "failed".
+    pub status: String,
+    /// `None` for one-off reminders, otherwise "daily", "weekly", or "cron".
+    pub recurrence: Option<String>,
+    /// 5-field cron expression, set when `recurrence` is "cron".
+    pub cron_expr: Option<String>,
+    /// Delivery attempts for the current occurrence.
+    pub attempts: i64,
+    /// When the bot's claim lapses and the reminder becomes due again.
+    pub lease_expires_at: Option<String>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<String>,
 }
 
 /// Payload for creating a new reminder.
@@ -19,4 +31,6 @@ pub struct CreateReminder {
     pub channel_id: String,
     pub message: String,
     pub remind_at: String,
+    pub recurrence: Option<String>,
+    pub cron_expr: Option<String>,
 }
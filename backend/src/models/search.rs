@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+
+/// What a search hit matched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum SearchKind {
+    /// A closed ticket's transcript text.
+    Transcript,
+    /// A ticket subject.
+    Ticket,
+    /// A single message posted in a ticket channel.
+    Message,
+}
+
+impl SearchKind {
+    pub const ALL: [SearchKind; 3] = [SearchKind::Transcript, SearchKind::Ticket, SearchKind::Message];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SearchKind::Transcript => "transcript",
+            SearchKind::Ticket => "ticket",
+            SearchKind::Message => "message",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|k| k.as_str() == value)
+    }
+}
+
+/// Narrowing applied to a guild search. Dates accept the same forms as
+/// [`crate::timestamp::normalize`]; a bare `to` date covers the whole day.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SearchFilters {
+    /// Only hits of this kind; all kinds when unset.
+    pub kind: Option<SearchKind>,
+    /// Ticket owner.
+    pub user_id: Option<String>,
+    pub category: Option<String>,
+    pub closed_by: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+/// One search result.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct SearchHit {
+    pub kind: SearchKind,
+    pub ticket_id: i64,
+    pub ticket_number: i64,
+    /// Transcript of the ticket, if it has been closed.
+    pub transcript_id: Option<String>,
+    /// Discord message ID, for message hits.
+    pub message_id: Option<String>,
+    pub subject: Option<String>,
+    pub user_id: String,
+    pub category: Option<String>,
+    /// HTML-escaped excerpt with matched terms wrapped in `<mark>`.
+    pub snippet: String,
+    /// bm25 score; lower is a better match.
+    pub rank: f64,
+    pub created_at: String,
+}
@@ -0,0 +1,70 @@
+use super::TicketStatus;
+
+/// Row from the `ticket_sla_targets` table.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, sqlx::FromRow)]
+pub struct SlaTarget {
+    pub guild_id: String,
+    /// "low", "normal", "high", or "urgent".
+    pub priority: String,
+    pub first_response_minutes: Option<i64>,
+    pub resolution_minutes: Option<i64>,
+    pub updated_at: String,
+}
+
+/// Targets for one priority. Leaving both out removes the priority's targets.
+#[derive(Debug, serde::Deserialize)]
+pub struct SetSlaTarget {
+    pub priority: String,
+    pub first_response_minutes: Option<i64>,
+    pub resolution_minutes: Option<i64>,
+}
+
+/// Row from the `ticket_sla_alerts` table: a warning or breach raised for
+/// one of a ticket's deadlines.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, sqlx::FromRow)]
+pub struct SlaAlert {
+    pub id: i64,
+    pub ticket_id: i64,
+    pub guild_id: String,
+    /// "first_response" or "resolution".
+    pub target: String,
+    /// "warning" or "breach".
+    pub level: String,
+    pub due_at: String,
+    pub created_at: String,
+}
+
+/// SLA results for one priority over a report's tickets.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct SlaSummary {
+    pub priority: String,
+    pub tickets: i64,
+    pub first_response_met: i64,
+    pub first_response_breached: i64,
+    pub resolution_met: i64,
+    pub resolution_breached: i64,
+    pub avg_first_response_minutes: Option<f64>,
+    pub avg_resolution_minutes: Option<f64>,
+}
+
+/// A missed deadline. `met_at` is `None` while the ticket is still overdue.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SlaBreach {
+    pub ticket_id: i64,
+    pub ticket_number: i64,
+    pub priority: String,
+    pub status: TicketStatus,
+    pub claimed_by: Option<String>,
+    /// "first_response" or "resolution".
+    pub target: String,
+    pub due_at: String,
+    pub met_at: Option<String>,
+    pub overdue_minutes: i64,
+}
+
+/// Response of the SLA breach report.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SlaReport {
+    pub summary: Vec<SlaSummary>,
+    pub breaches: Vec<SlaBreach>,
+}
@@ -10,6 +10,8 @@ pub struct TempPunishment {
     /// ISO-8601 timestamp when the punishment expires.
     pub expires_at: String,
     pub created_at: String,
+    /// Set once the punishment expired and its lift was queued for the bot.
+    pub lifted_at: Option<String>,
 }
 
 /// Payload for creating a new temporary punishment.
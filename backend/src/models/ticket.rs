@@ -10,8 +10,7 @@ pub struct Ticket {
     pub guild_id: String,
     pub category: Option<String>,
     pub subject: Option<String>,
-    /// "open", "closed", or "review".
-    pub status: String,
+    pub status: TicketStatus,
     /// "low", "normal", "high", or "urgent".
     pub priority: String,
     pub claimed_by: Option<String>,
@@ -21,6 +20,96 @@ pub struct Ticket {
     pub review_rating: Option<i64>,
     pub last_activity: String,
     pub created_at: String,
+    /// First message from someone other than the owner or a bot.
+    pub first_response_at: Option<String>,
+    pub first_response_by: Option<String>,
+    /// SLA deadlines from the guild's targets for this priority, if any.
+    pub first_response_due_at: Option<String>,
+    pub resolution_due_at: Option<String>,
+    /// When the bot warned about inactivity; cleared by any later activity.
+    pub inactivity_warned_at: Option<String>,
+    /// Whether inactivity warnings and auto-close apply to this ticket.
+    pub auto_close: bool,
+}
+
+/// Lifecycle state of a ticket, stored as snake_case TEXT.
+///
+/// Moves between states are only allowed along the edges in
+/// [`TicketStatus::can_transition_to`]; `services::ticket` enforces them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum TicketStatus {
+    Open,
+    /// Staff replied and are waiting on the ticket author.
+    AwaitingUser,
+    /// Parked by staff, e.g. waiting on a third party.
+    OnHold,
+    /// Handled and waiting for the author's review before closing.
+    Review,
+    Closed,
+}
+
+impl TicketStatus {
+    pub const ALL: [TicketStatus; 5] = [
+        TicketStatus::Open,
+        TicketStatus::AwaitingUser,
+        TicketStatus::OnHold,
+        TicketStatus::Review,
+        TicketStatus::Closed,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TicketStatus::Open => "open",
+            TicketStatus::AwaitingUser => "awaiting_user",
+            TicketStatus::OnHold => "on_hold",
+            TicketStatus::Review => "review",
+            TicketStatus::Closed => "closed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.as_str() == value)
+    }
+
+    /// Whether the ticket still needs work (open, awaiting user, or on hold).
+    pub fn is_active(self) -> bool {
+        matches!(self, TicketStatus::Open | TicketStatus::AwaitingUser | TicketStatus::OnHold)
+    }
+
+    /// The active statuses as an SQL list, e.g. `('open', 'awaiting_user', 'on_hold')`,
+    /// for `status IN` clauses built with `format!`.
+    pub fn active_sql() -> &'static str {
+        static ACTIVE: std::sync::LazyLock<String> =
+            std::sync::LazyLock::new(|| TicketStatus::sql_list(TicketStatus::ALL.into_iter().filter(|s| s.is_active())));
+        &ACTIVE
+    }
+
+    /// `statuses` as an SQL list of string literals, e.g. `('open', 'closed')`.
+    pub fn sql_list(statuses: impl IntoIterator<Item = TicketStatus>) -> String {
+        let quoted: Vec<String> = statuses.into_iter().map(|s| format!("'{s}'")).collect();
+        format!("({})", quoted.join(", "))
+    }
+
+    /// Active tickets can move to any other state; reviewed tickets can be
+    /// closed or reopened; closed tickets can only be reopened.
+    pub fn can_transition_to(self, next: TicketStatus) -> bool {
+        if self == next {
+            return false;
+        }
+        match self {
+            TicketStatus::Open | TicketStatus::AwaitingUser | TicketStatus::OnHold => true,
+            TicketStatus::Review => matches!(next, TicketStatus::Open | TicketStatus::Closed),
+            TicketStatus::Closed => next == TicketStatus::Open,
+        }
+    }
+}
+
+impl std::fmt::Display for TicketStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 /// Payload for creating a new ticket.
@@ -47,6 +136,25 @@ pub struct UpdateTicket {
     pub last_activity: Option<String>,
 }
 
+// ── Ticket Event ────────────────────────────────────────────────────────────
+
+/// Row from the `ticket_events` table: one entry in a ticket's history.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, sqlx::FromRow)]
+pub struct TicketEvent {
+    pub id: i64,
+    pub ticket_id: i64,
+    pub guild_id: String,
+    /// "created", "status", "claim", "priority", "inactivity_warning", or
+    /// "auto_close".
+    pub event_type: String,
+    pub from_value: Option<String>,
+    pub to_value: Option<String>,
+    /// Discord user who made the change.
+    pub actor_id: String,
+    pub note: Option<String>,
+    pub created_at: String,
+}
+
 // ── Ticket Category ─────────────────────────────────────────────────────────
 
 /// Row from the `ticket_categories` table.
@@ -58,6 +166,16 @@ pub struct TicketCategory {
     pub emoji: Option<String>,
     pub description: Option<String>,
     pub created_at: String,
+    /// Inactivity rule overrides; `None` uses the guild's, 0 turns it off.
+    pub inactivity_warn_hours: Option<i64>,
+    pub inactivity_close_hours: Option<i64>,
+    /// Routing override; `None` uses the guild's policy.
+    pub routing: Option<super::RoutingPolicy>,
+    /// Most active tickets a user may have in this category; `None` is
+    /// unlimited. The guild's limit still applies.
+    pub max_open: Option<i64>,
+    /// Role needed to open tickets in this category, on top of the guild's.
+    pub required_role: Option<String>,
 }
 
 /// Payload for creating a new ticket category.
@@ -80,6 +198,8 @@ pub struct TicketBlacklist {
     pub reason: Option<String>,
     pub added_by: String,
     pub created_at: String,
+    /// When the scheduler lifts the entry; `None` is permanent.
+    pub expires_at: Option<String>,
 }
 
 /// Payload for blacklisting a user from opening tickets.
@@ -89,4 +209,61 @@ pub struct CreateTicketBlacklist {
     pub user_id: String,
     pub reason: Option<String>,
     pub added_by: String,
+    pub expires_at: Option<String>,
+}
+
+/// Row from the `ticket_blacklist_events` table: one add, removal or
+/// expiry of a blacklist entry.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, sqlx::FromRow)]
+pub struct TicketBlacklistEvent {
+    pub id: i64,
+    pub guild_id: String,
+    pub user_id: String,
+    /// "add", "remove", or "expire".
+    pub action: String,
+    pub reason: Option<String>,
+    /// Moderator who made the change; "system" for expiries.
+    pub actor_id: String,
+    /// For adds, when the entry was set to lapse.
+    pub expires_at: Option<String>,
+    pub created_at: String,
+}
+
+// ── Ticket Open Policy ──────────────────────────────────────────────────────
+
+/// Why a user may not open a ticket, tagged by `reason` for the bot.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum TicketDenial {
+    Blacklisted { note: Option<String> },
+    /// The member lacks the guild's or the category's required role.
+    MissingRole { role_id: String },
+    /// The user is at the guild's limit of active tickets.
+    TooManyOpen { limit: i64 },
+    /// The user is at the category's limit of active tickets.
+    CategoryLimit { category: String, limit: i64 },
+    /// The user's last ticket closed too recently; `retry_at` is UTC.
+    Cooldown { retry_at: String },
+}
+
+impl TicketDenial {
+    /// A sentence to show the user.
+    pub fn message(&self) -> String {
+        match self {
+            TicketDenial::Blacklisted { note: Some(note) } => {
+                format!("You are blacklisted from tickets. Reason: {note}")
+            }
+            TicketDenial::Blacklisted { note: None } => "You are blacklisted from tickets.".to_string(),
+            TicketDenial::MissingRole { .. } => "You don't have the role needed to open this ticket.".to_string(),
+            TicketDenial::TooManyOpen { limit } => {
+                format!("You already have {limit} open ticket(s). Close one before opening another.")
+            }
+            TicketDenial::CategoryLimit { category, limit } => {
+                format!("You already have {limit} open ticket(s) in {category}.")
+            }
+            TicketDenial::Cooldown { retry_at } => {
+                format!("You recently closed a ticket. You can open another after {retry_at} UTC.")
+            }
+        }
+    }
 }
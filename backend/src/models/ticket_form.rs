@@ -0,0 +1,70 @@
+use super::Ticket;
+
+/// Row from the `ticket_form_questions` table: one question of a ticket
+/// category's form, asked in the bot's modal when a ticket is opened.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, sqlx::FromRow)]
+pub struct TicketFormQuestion {
+    pub id: i64,
+    pub category_id: i64,
+    /// Order in the form, starting at 0.
+    pub position: i64,
+    pub label: String,
+    pub style: QuestionStyle,
+    pub required: bool,
+    pub min_length: Option<i64>,
+    pub max_length: Option<i64>,
+    pub placeholder: Option<String>,
+    pub created_at: String,
+}
+
+/// Input style of a form question, matching Discord's text input styles.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum QuestionStyle {
+    /// Single line.
+    #[default]
+    Short,
+    /// Multi-line.
+    Paragraph,
+}
+
+/// One question when replacing a category's form; the list order is the
+/// form order.
+#[derive(Debug, serde::Deserialize)]
+pub struct SetTicketFormQuestion {
+    pub label: String,
+    #[serde(default)]
+    pub style: QuestionStyle,
+    #[serde(default)]
+    pub required: bool,
+    pub min_length: Option<i64>,
+    pub max_length: Option<i64>,
+    pub placeholder: Option<String>,
+}
+
+/// Row from the `ticket_form_answers` table. `question_id` is `None` once
+/// the question has been removed from the form.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, sqlx::FromRow)]
+pub struct TicketFormAnswer {
+    pub ticket_id: i64,
+    pub question_id: Option<i64>,
+    pub position: i64,
+    pub label: String,
+    pub value: String,
+}
+
+/// An answer as submitted by the bot when opening a ticket.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct TicketAnswerInput {
+    pub question_id: i64,
+    pub value: String,
+}
+
+/// A ticket with the form answers given when it was opened.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TicketDetail {
+    #[serde(flatten)]
+    pub ticket: Ticket,
+    pub answers: Vec<TicketFormAnswer>,
+}
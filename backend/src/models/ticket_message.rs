@@ -0,0 +1,64 @@
+/// Row from the `ticket_messages` table: one Discord message posted in a
+/// ticket channel.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, sqlx::FromRow)]
+pub struct TicketMessage {
+    pub id: i64,
+    pub ticket_id: i64,
+    pub guild_id: String,
+    /// Discord message ID.
+    pub message_id: String,
+    pub author_id: String,
+    pub author_name: String,
+    pub author_avatar: Option<String>,
+    pub author_bot: bool,
+    pub content: String,
+    /// JSON-encoded array of [`MessageAttachment`].
+    pub attachments: String,
+    /// JSON-encoded array of Discord embed objects.
+    pub embeds: String,
+    pub edited_at: Option<String>,
+    pub deleted_at: Option<String>,
+    pub created_at: String,
+}
+
+/// A file attached to a ticket message.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct MessageAttachment {
+    pub name: String,
+    pub url: String,
+    pub content_type: Option<String>,
+    pub size: Option<i64>,
+}
+
+/// Payload the bot sends for each message it sees in a ticket channel.
+///
+/// Re-sending a known `message_id` updates the stored copy, so the bot can
+/// backfill a channel without creating duplicates.
+#[derive(Debug, serde::Deserialize)]
+pub struct IngestTicketMessage {
+    pub message_id: String,
+    pub author_id: String,
+    pub author_name: String,
+    pub author_avatar: Option<String>,
+    #[serde(default)]
+    pub author_bot: bool,
+    #[serde(default)]
+    pub content: String,
+    #[serde(default)]
+    pub attachments: Vec<MessageAttachment>,
+    #[serde(default)]
+    pub embeds: Vec<serde_json::Value>,
+    /// When Discord says the message was sent. Defaults to now.
+    pub created_at: Option<String>,
+    pub edited_at: Option<String>,
+}
+
+/// Payload for an edited ticket message.
+#[derive(Debug, serde::Deserialize)]
+pub struct EditTicketMessage {
+    pub content: Option<String>,
+    pub attachments: Option<Vec<MessageAttachment>>,
+    pub embeds: Option<Vec<serde_json::Value>>,
+    /// Defaults to now.
+    pub edited_at: Option<String>,
+}
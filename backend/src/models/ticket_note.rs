@@ -0,0 +1,51 @@
+// ── Notes ────────────────────────────────────────────────────────────────────
+
+/// Row from the `ticket_notes` table: staff-only context on a ticket.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, sqlx::FromRow)]
+pub struct TicketNote {
+    pub id: i64,
+    pub ticket_id: i64,
+    pub guild_id: String,
+    pub author_id: String,
+    pub body: String,
+    /// Pinned notes are listed first.
+    pub pinned: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Payload for adding a note.
+#[derive(Debug, serde::Deserialize)]
+pub struct CreateTicketNote {
+    pub body: String,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// Partial-update payload for a note.
+#[derive(Debug, serde::Deserialize)]
+pub struct UpdateTicketNote {
+    pub body: Option<String>,
+    pub pinned: Option<bool>,
+}
+
+// ── Tags ─────────────────────────────────────────────────────────────────────
+
+/// Row from the `ticket_tags` table: one entry of a guild's tag vocabulary.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, sqlx::FromRow)]
+pub struct TicketTag {
+    pub id: i64,
+    pub guild_id: String,
+    /// Unique per guild, ignoring case.
+    pub name: String,
+    /// `#rrggbb`.
+    pub color: Option<String>,
+    pub created_at: String,
+}
+
+/// Payload for creating or editing a tag.
+#[derive(Debug, serde::Deserialize)]
+pub struct SetTicketTag {
+    pub name: String,
+    pub color: Option<String>,
+}
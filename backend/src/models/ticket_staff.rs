@@ -0,0 +1,53 @@
+/// How new tickets are assigned to staff.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum RoutingPolicy {
+    /// Tickets wait for someone to claim them.
+    #[default]
+    Manual,
+    /// Available staff take turns, least recently assigned first.
+    RoundRobin,
+    /// Whoever has the fewest active tickets.
+    LeastOpen,
+}
+
+impl RoutingPolicy {
+    pub const ALL: [RoutingPolicy; 3] = [RoutingPolicy::Manual, RoutingPolicy::RoundRobin, RoutingPolicy::LeastOpen];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RoutingPolicy::Manual => "manual",
+            RoutingPolicy::RoundRobin => "round_robin",
+            RoutingPolicy::LeastOpen => "least_open",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.as_str() == value)
+    }
+}
+
+/// Row from the `ticket_staff` table: a roster member tickets can be
+/// routed to.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, sqlx::FromRow)]
+pub struct TicketStaff {
+    pub guild_id: String,
+    pub user_id: String,
+    /// Unavailable staff get no new tickets and hand off their active ones.
+    pub available: bool,
+    /// With milliseconds, for round-robin ordering.
+    pub last_assigned_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// A roster member with their current load.
+#[derive(Debug, Clone, serde::Serialize, sqlx::FromRow)]
+pub struct TicketStaffLoad {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub staff: TicketStaff,
+    /// Active tickets claimed by this member.
+    pub active_tickets: i64,
+}
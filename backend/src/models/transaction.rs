@@ -0,0 +1,55 @@
+/// Row from the append-only `transactions` ledger.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, sqlx::FromRow)]
+pub struct Transaction {
+    pub id: i64,
+    pub user_id: String,
+    /// Guild whose balance changed.
+    pub guild_id: String,
+    /// Signed amount: positive credits, negative debits.
+    pub amount: i64,
+    pub balance_after: i64,
+    /// "daily", "transfer_in", "transfer_out", "grant", or "take".
+    pub kind: String,
+    /// The other user in a transfer.
+    pub counterparty_id: Option<String>,
+    /// Dashboard user who made a grant or take.
+    pub actor_id: Option<String>,
+    pub reason: Option<String>,
+    pub created_at: String,
+}
+
+/// Row from the `member_balances` table: a member's money in one guild.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, sqlx::FromRow)]
+pub struct MemberBalance {
+    pub guild_id: String,
+    pub user_id: String,
+    pub balance: i64,
+    /// Last `/daily` claim in this guild.
+    pub last_daily: Option<String>,
+    /// Consecutive days with a `/daily` claim.
+    pub daily_streak: i64,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Outcome of a `/daily` claim.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DailyClaim {
+    /// `false` if the user is still on cooldown; nothing was paid.
+    pub claimed: bool,
+    pub amount: i64,
+    /// Consecutive days claimed, including this one.
+    pub streak: i64,
+    pub balance: i64,
+    /// When the next claim becomes available.
+    pub next_claim_at: String,
+}
+
+/// A user's position on the balance leaderboard.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, sqlx::FromRow)]
+pub struct BalanceEntry {
+    /// 1-based rank by balance; ties share a rank.
+    pub rank: i64,
+    pub user_id: String,
+    pub balance: i64,
+}
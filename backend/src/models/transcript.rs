@@ -14,9 +14,83 @@ pub struct Transcript {
     pub subject: Option<String>,
     pub category: Option<String>,
     pub message_count: i64,
-    /// Full HTML transcript body.
+    /// Full HTML transcript body; empty once archived (see `storage`).
     pub html: String,
     pub created_at: String,
+    /// Theme `html` was rendered with.
+    pub theme: TranscriptTheme,
+    /// Where the HTML body is kept.
+    #[serde(default)]
+    pub storage: TranscriptStorage,
+    /// zstd file holding the body when `storage` is `file`.
+    #[serde(skip)]
+    pub archive_path: Option<String>,
+    pub archived_at: Option<String>,
+}
+
+/// Where a transcript's HTML body is stored, as lowercase TEXT.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum TranscriptStorage {
+    /// Uncompressed in the `html` column.
+    #[default]
+    Html,
+    /// zstd-compressed in the `html_zstd` column.
+    Zstd,
+    /// zstd-compressed in a file in the archive directory.
+    File,
+}
+
+/// Colour scheme for rendered transcripts, stored as lowercase TEXT.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum TranscriptTheme {
+    /// Discord's dark palette.
+    #[default]
+    Dark,
+    Light,
+}
+
+impl TranscriptTheme {
+    pub const ALL: [TranscriptTheme; 2] = [TranscriptTheme::Dark, TranscriptTheme::Light];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TranscriptTheme::Dark => "dark",
+            TranscriptTheme::Light => "light",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.as_str() == value)
+    }
+}
+
+/// Row from the `transcript_share_links` table.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, sqlx::FromRow)]
+pub struct TranscriptShareLink {
+    /// UUID, also the signed token's `jti`.
+    pub id: String,
+    pub transcript_id: String,
+    pub guild_id: String,
+    pub created_by: String,
+    pub expires_at: String,
+    pub revoked_at: Option<String>,
+    pub revoked_by: Option<String>,
+    pub view_count: i64,
+    pub last_viewed_at: Option<String>,
+    pub created_at: String,
+}
+
+/// Display names for a transcript header, as known by the bot at close time.
+/// Any left out are taken from the ticket's messages, or fall back to IDs.
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct TranscriptNames {
+    pub guild_name: Option<String>,
+    pub user_name: Option<String>,
+    pub closed_by_name: Option<String>,
 }
 
 /// Payload for creating a new transcript.
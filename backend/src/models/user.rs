@@ -3,10 +3,12 @@
 pub struct User {
     /// Discord user ID.
     pub id: String,
+    /// Legacy global XP, superseded by the per-guild `member_levels` table.
     pub xp: i64,
     pub level: i64,
+    /// Legacy global balance and `/daily` claim, superseded by the
+    /// per-guild `member_balances` table.
     pub balance: i64,
-    /// ISO-8601 timestamp of the last `/daily` claim.
     pub last_daily: Option<String>,
     /// 0 = hidden, 1 = visible.
     pub visible_xp: i64,
@@ -0,0 +1,51 @@
+use axum::{
+    extract::{Path, Query, State},
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+
+use crate::auth::middleware::GuildAdmin;
+use crate::error::{AppError, AppResult};
+use crate::models::{AnalyticsBucket, SupportAnalytics};
+use crate::services::analytics;
+use crate::state::AppState;
+use crate::timestamp;
+
+/// Router nested under /guilds/:id
+pub fn router() -> Router<AppState> {
+    Router::new().route("/{id}/analytics/support", get(support_analytics))
+}
+
+// ── GET /guilds/:id/analytics/support ───────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct SupportAnalyticsQuery {
+    /// Earliest ticket open date/time to include (inclusive).
+    pub from: Option<String>,
+    /// Latest ticket open date/time to include (inclusive; a bare date
+    /// means the whole day).
+    pub to: Option<String>,
+    /// `day` (default), `week` or `month`.
+    pub bucket: Option<String>,
+}
+
+/// Review ratings, tickets handled and response times per staff member,
+/// per category and over time.
+async fn support_analytics(
+    State(state): State<AppState>,
+    _admin: GuildAdmin,
+    Path(id): Path<String>,
+    Query(params): Query<SupportAnalyticsQuery>,
+) -> AppResult<Json<SupportAnalytics>> {
+    let (from, to) = timestamp::parse_range(params.from.as_deref(), params.to.as_deref())?;
+    let bucket = params
+        .bucket
+        .as_deref()
+        .map(|v| AnalyticsBucket::parse(v).ok_or_else(|| AppError::BadRequest(format!("Invalid bucket '{v}'"))))
+        .transpose()?
+        .unwrap_or_default();
+
+    let analytics = analytics::support(&state.db, &id, from.as_deref(), to.as_deref(), bucket).await?;
+    Ok(Json(analytics))
+}
@@ -6,9 +6,10 @@ use axum::{
 use serde::Deserialize;
 use serde_json::json;
 
-use crate::auth::middleware::AuthUser;
+use crate::auth::middleware::GuildAdmin;
 use crate::error::{AppError, AppResult};
 use crate::models::AutoRole;
+use crate::services::dashboard_log;
 use crate::state::AppState;
 
 pub fn router() -> Router<AppState> {
@@ -24,7 +25,7 @@ pub fn router() -> Router<AppState> {
 
 async fn list_auto_roles(
     State(state): State<AppState>,
-    _user: AuthUser,
+    _admin: GuildAdmin,
     Path(id): Path<String>,
 ) -> AppResult<Json<Vec<AutoRole>>> {
     let roles = sqlx::query_as::<_, AutoRole>(
@@ -48,7 +49,7 @@ pub struct CreateAutoRoleBody {
 
 async fn create_auto_role(
     State(state): State<AppState>,
-    _user: AuthUser,
+    admin: GuildAdmin,
     Path(id): Path<String>,
     Json(body): Json<CreateAutoRoleBody>,
 ) -> AppResult<Json<AutoRole>> {
@@ -80,6 +81,20 @@ async fn create_auto_role(
     .fetch_one(&state.db)
     .await?;
 
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "auto_role.create",
+        json!({
+            "id": auto_role.id,
+            "role_id": auto_role.role_id,
+            "role_type": auto_role.role_type,
+            "delay": auto_role.delay,
+        }),
+    )
+    .await;
+
     Ok(Json(auto_role))
 }
 
@@ -87,7 +102,7 @@ async fn create_auto_role(
 
 async fn delete_auto_role(
     State(state): State<AppState>,
-    _user: AuthUser,
+    admin: GuildAdmin,
     Path((id, arid)): Path<(String, i64)>,
 ) -> AppResult<Json<serde_json::Value>> {
     let result = sqlx::query("DELETE FROM auto_roles WHERE guild_id = ? AND id = ?")
@@ -100,5 +115,7 @@ async fn delete_auto_role(
         return Err(AppError::NotFound(format!("Auto role {arid} not found")));
     }
 
+    dashboard_log::record(&state, &admin, &id, "auto_role.delete", json!({ "id": arid })).await;
+
     Ok(Json(json!({ "deleted": true, "id": arid })))
 }
@@ -1,6 +1,8 @@
 use axum::{
-    extract::{Path, State},
-    routing::{get, post, put},
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{delete, get, post, put},
     Json, Router,
 };
 use serde::Deserialize;
@@ -8,14 +10,42 @@ use serde_json::json;
 
 use crate::auth::middleware::BotAuth;
 use crate::error::{AppError, AppResult};
-use crate::models::{BotConfig, CreateWarn, Ticket, UpdateBotConfig, Warn};
-use crate::services::moderation;
+use crate::models::{
+    BotConfig, CreateReminder, CreateWarn, DailyClaim, EditTicketMessage, IngestTicketMessage, MemberBalance,
+    PendingAction, Reminder, Ticket, TicketAnswerInput, TicketBlacklist, TicketCategory, TicketDetail,
+    TicketFormQuestion, TicketMessage, Transaction, TranscriptNames, UpdateBotConfig, Warn,
+};
+use crate::routes::{tickets, transcripts};
+use crate::services::{
+    economy, leveling, moderation, pending_action, reminder, ticket, ticket_form, ticket_inactivity,
+    ticket_message, ticket_routing, transcript, transcript_share,
+};
 use crate::state::AppState;
+use crate::ws;
 
 pub fn router() -> Router<AppState> {
     Router::new()
         // Ticket creation from bot
         .route("/guilds/{id}/tickets", post(bot_create_ticket))
+        .route("/guilds/{id}/tickets/can-open", post(bot_can_open_ticket))
+        .route("/guilds/{id}/tickets/{tid}/close", post(bot_close_ticket))
+        .route("/guilds/{id}/tickets/{tid}/auto-close", put(bot_set_auto_close))
+        .route("/guilds/{id}/ticket-categories", get(bot_list_categories))
+        .route("/guilds/{id}/ticket-categories/{cid}/form", get(bot_get_category_form))
+        .route("/guilds/{id}/ticket-staff/{uid}/availability", put(bot_set_availability))
+        .route(
+            "/guilds/{id}/ticket-blacklist",
+            get(bot_list_blacklist).post(bot_add_to_blacklist),
+        )
+        .route("/guilds/{id}/ticket-blacklist/{bid}", delete(bot_remove_from_blacklist))
+        .route("/transcripts/{tid}/share-links", post(bot_create_share_link))
+        // Ticket message stream, rendered into transcripts on close
+        .route("/ticket-channels/{channel_id}", get(bot_get_channel_ticket))
+        .route("/ticket-channels/{channel_id}/messages", post(bot_ingest_ticket_messages))
+        .route(
+            "/ticket-messages/{message_id}",
+            put(bot_edit_ticket_message).delete(bot_delete_ticket_message),
+        )
         // Warn creation from bot
         .route("/guilds/{id}/warns", post(bot_create_warn))
         // XP management
@@ -24,17 +54,41 @@ pub fn router() -> Router<AppState> {
         .route("/giveaway-enter", post(bot_giveaway_enter))
         // Bot config (status etc.)
         .route("/config", get(bot_get_config).put(bot_update_config))
+        // Reminder delivery queue
+        .route("/reminders", post(bot_create_reminder))
+        .route("/reminders/claim", post(bot_claim_reminders))
+        .route("/reminders/{rid}", delete(bot_cancel_reminder))
+        .route("/users/{user_id}/reminders", get(bot_list_user_reminders))
+        .route("/reminders/{rid}/ack", post(bot_ack_reminder))
+        .route("/reminders/{rid}/fail", post(bot_fail_reminder))
+        // Outbox of Discord actions (unbans, unmutes), pulled or pushed over /bot/ws
+        .route("/actions/claim", post(bot_claim_actions))
+        .route("/actions/{aid}/ack", post(bot_ack_action))
+        .route("/actions/{aid}/fail", post(bot_fail_action))
+        .route("/ws", get(ws::bot::bot_ws_handler))
+        // Economy
+        .route("/guilds/{id}/economy/daily", post(bot_claim_daily))
+        .route("/guilds/{id}/economy/transfer", post(bot_transfer))
+        .route("/guilds/{id}/economy/{user_id}", get(bot_get_balance))
+        .route("/guilds/{id}/economy/{user_id}/transactions", get(bot_list_transactions))
 }
 
 // ── POST /bot/guilds/:id/tickets ────────────────────────────────────────────
 
+/// The ticket number is assigned by the backend; any `number` the bot sends
+/// is ignored.
 #[derive(Debug, Deserialize)]
 pub struct BotCreateTicketBody {
-    pub number: i64,
     pub channel_id: String,
     pub user_id: String,
     pub category: Option<String>,
     pub subject: Option<String>,
+    /// Answers to the category's form questions.
+    #[serde(default)]
+    pub answers: Vec<TicketAnswerInput>,
+    /// The member's roles, checked against required roles.
+    #[serde(default)]
+    pub role_ids: Vec<String>,
 }
 
 async fn bot_create_ticket(
@@ -42,36 +96,295 @@ async fn bot_create_ticket(
     _auth: BotAuth,
     Path(id): Path<String>,
     Json(body): Json<BotCreateTicketBody>,
-) -> AppResult<Json<Ticket>> {
-    sqlx::query(
-        "INSERT INTO tickets (number, channel_id, user_id, guild_id, category, subject) \
-         VALUES (?, ?, ?, ?, ?, ?)",
-    )
-    .bind(body.number)
-    .bind(&body.channel_id)
-    .bind(&body.user_id)
-    .bind(&id)
-    .bind(&body.category)
-    .bind(&body.subject)
-    .execute(&state.db)
-    .await?;
-
-    let ticket = sqlx::query_as::<_, Ticket>(
-        "SELECT * FROM tickets WHERE id = last_insert_rowid()",
+) -> AppResult<Json<TicketDetail>> {
+    let detail = ticket::create_ticket(
+        &state.db,
+        &id,
+        &body.channel_id,
+        &body.user_id,
+        body.category.as_deref(),
+        body.subject.as_deref(),
+        &body.answers,
+        &body.role_ids,
     )
-    .fetch_one(&state.db)
     .await?;
 
     // Broadcast via WebSocket
     let _ = state.ws_tx.send(crate::state::WsEvent::TicketUpdate {
         guild_id: id,
-        ticket_id: ticket.id,
+        ticket_id: detail.ticket.id,
         status: "open".to_string(),
     });
 
+    Ok(Json(detail))
+}
+
+// ── POST /bot/guilds/:id/tickets/can-open ───────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct BotCanOpenBody {
+    pub user_id: String,
+    pub category: Option<String>,
+    #[serde(default)]
+    pub role_ids: Vec<String>,
+}
+
+/// Run the open policy before the bot asks for a form or creates a channel.
+/// Creating the ticket checks it again.
+async fn bot_can_open_ticket(
+    State(state): State<AppState>,
+    _auth: BotAuth,
+    Path(id): Path<String>,
+    Json(body): Json<BotCanOpenBody>,
+) -> AppResult<Json<serde_json::Value>> {
+    let conn = &mut *state.db.acquire().await?;
+    let denial = ticket::can_open(conn, &id, &body.user_id, body.category.as_deref(), &body.role_ids).await?;
+
+    Ok(Json(json!({
+        "allowed": denial.is_none(),
+        "message": denial.as_ref().map(|d| d.message()),
+        "denial": denial,
+    })))
+}
+
+// ── POST /bot/guilds/:id/tickets/:tid/close ─────────────────────────────────
+
+/// Display names are optional; see [`TranscriptNames`].
+#[derive(Debug, Deserialize)]
+pub struct BotCloseTicketBody {
+    pub closed_by: String,
+    #[serde(flatten)]
+    pub names: TranscriptNames,
+}
+
+/// Close a ticket and render its transcript from the ingested messages.
+async fn bot_close_ticket(
+    State(state): State<AppState>,
+    _auth: BotAuth,
+    Path((id, tid)): Path<(String, i64)>,
+    Json(body): Json<BotCloseTicketBody>,
+) -> AppResult<Json<serde_json::Value>> {
+    ticket::get_guild_ticket(&state.db, &id, tid).await?;
+    let (ticket, transcript) = ticket::close_ticket(&state.db, tid, &body.closed_by, &body.names).await?;
+
+    let _ = state.ws_tx.send(crate::state::WsEvent::TicketUpdate {
+        guild_id: id,
+        ticket_id: ticket.id,
+        status: ticket.status.as_str().to_string(),
+    });
+
+    Ok(Json(json!({ "ticket": ticket, "transcript": transcript })))
+}
+
+// ── PUT /bot/guilds/:id/tickets/:tid/auto-close ─────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct BotAutoCloseBody {
+    pub enabled: bool,
+    pub actor_id: String,
+}
+
+/// Opt a ticket in or out of inactivity auto-close.
+async fn bot_set_auto_close(
+    State(state): State<AppState>,
+    _auth: BotAuth,
+    Path((id, tid)): Path<(String, i64)>,
+    Json(body): Json<BotAutoCloseBody>,
+) -> AppResult<Json<Ticket>> {
+    let ticket = ticket_inactivity::set_auto_close(&state.db, &id, tid, body.enabled, &body.actor_id).await?;
+    Ok(Json(ticket))
+}
+
+// ── GET /bot/guilds/:id/ticket-categories ───────────────────────────────────
+
+async fn bot_list_categories(
+    State(state): State<AppState>,
+    _auth: BotAuth,
+    Path(id): Path<String>,
+) -> AppResult<Json<Vec<TicketCategory>>> {
+    let categories = ticket::get_categories(&state.db, &id).await?;
+    Ok(Json(categories))
+}
+
+// ── GET /bot/guilds/:id/ticket-categories/:cid/form ─────────────────────────
+
+/// Questions to put in the ticket modal for a category.
+async fn bot_get_category_form(
+    State(state): State<AppState>,
+    _auth: BotAuth,
+    Path((id, cid)): Path<(String, i64)>,
+) -> AppResult<Json<Vec<TicketFormQuestion>>> {
+    let form = ticket_form::get_form(&state.db, &id, cid).await?;
+    Ok(Json(form))
+}
+
+// ── PUT /bot/guilds/:id/ticket-staff/:uid/availability ──────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct BotAvailabilityBody {
+    pub available: bool,
+}
+
+/// A staff member toggling their own availability from Discord.
+async fn bot_set_availability(
+    State(state): State<AppState>,
+    _auth: BotAuth,
+    Path((id, uid)): Path<(String, String)>,
+    Json(body): Json<BotAvailabilityBody>,
+) -> AppResult<Json<serde_json::Value>> {
+    let (staff, reassigned) = ticket_routing::set_staff(&state.db, &id, &uid, body.available).await?;
+    tickets::broadcast_reassigned(&state, &reassigned);
+
+    Ok(Json(json!({ "staff": staff, "reassigned": reassigned })))
+}
+
+// ── GET /bot/guilds/:id/ticket-blacklist ────────────────────────────────────
+
+async fn bot_list_blacklist(
+    State(state): State<AppState>,
+    _auth: BotAuth,
+    Path(id): Path<String>,
+    Query(params): Query<tickets::BlacklistQuery>,
+) -> AppResult<Json<serde_json::Value>> {
+    Ok(Json(tickets::blacklist_page(&state, &id, &params).await?))
+}
+
+// ── POST /bot/guilds/:id/ticket-blacklist ───────────────────────────────────
+
+/// `added_by` is the moderator who ran the command. Leave `expires_at` out
+/// for a permanent entry.
+#[derive(Debug, Deserialize)]
+pub struct BotAddBlacklistBody {
+    pub user_id: String,
+    pub reason: Option<String>,
+    pub expires_at: Option<String>,
+    pub added_by: String,
+}
+
+async fn bot_add_to_blacklist(
+    State(state): State<AppState>,
+    _auth: BotAuth,
+    Path(id): Path<String>,
+    Json(body): Json<BotAddBlacklistBody>,
+) -> AppResult<Json<TicketBlacklist>> {
+    let expires_at = tickets::parse_blacklist_expiry(body.expires_at.as_deref())?;
+    let entry = ticket::add_blacklist(
+        &state.db,
+        &id,
+        &body.user_id,
+        body.reason.as_deref(),
+        expires_at.as_deref(),
+        &body.added_by,
+    )
+    .await?;
+
+    Ok(Json(entry))
+}
+
+// ── DELETE /bot/guilds/:id/ticket-blacklist/:bid ────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct BotRemoveBlacklistQuery {
+    pub removed_by: String,
+    pub reason: Option<String>,
+}
+
+async fn bot_remove_from_blacklist(
+    State(state): State<AppState>,
+    _auth: BotAuth,
+    Path((id, bid)): Path<(String, i64)>,
+    Query(params): Query<BotRemoveBlacklistQuery>,
+) -> AppResult<Json<serde_json::Value>> {
+    ticket::remove_blacklist(&state.db, &id, bid, &params.removed_by, params.reason.as_deref()).await?;
+    Ok(Json(json!({ "deleted": true, "id": bid })))
+}
+
+// ── POST /bot/transcripts/:tid/share-links ──────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct BotCreateShareLinkBody {
+    pub created_by: String,
+    pub expires_in_hours: Option<i64>,
+}
+
+/// Create a share link the bot can hand to the ticket owner.
+async fn bot_create_share_link(
+    State(state): State<AppState>,
+    _auth: BotAuth,
+    Path(tid): Path<String>,
+    Json(body): Json<BotCreateShareLinkBody>,
+) -> AppResult<Json<serde_json::Value>> {
+    let transcript = transcript::get_transcript(&state.db, &tid).await?;
+    let (link, token) = transcript_share::create(
+        &state.db,
+        &state.config.session_secret,
+        &transcript,
+        &body.created_by,
+        body.expires_in_hours,
+    )
+    .await?;
+
+    Ok(Json(transcripts::share_link_response(&state, link, token)))
+}
+
+// ── GET /bot/ticket-channels/:channel_id ────────────────────────────────────
+
+async fn bot_get_channel_ticket(
+    State(state): State<AppState>,
+    _auth: BotAuth,
+    Path(channel_id): Path<String>,
+) -> AppResult<Json<Ticket>> {
+    let ticket = ticket::find_by_channel(&state.db, &channel_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("No ticket for channel {channel_id}")))?;
+
     Ok(Json(ticket))
 }
 
+// ── POST /bot/ticket-channels/:channel_id/messages ──────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct BotIngestMessagesBody {
+    pub messages: Vec<IngestTicketMessage>,
+}
+
+async fn bot_ingest_ticket_messages(
+    State(state): State<AppState>,
+    _auth: BotAuth,
+    Path(channel_id): Path<String>,
+    Json(body): Json<BotIngestMessagesBody>,
+) -> AppResult<Response> {
+    // Channels that never held a ticket are dropped without an error, so the
+    // bot can forward anything that looks like a ticket channel
+    let Some((ticket, written)) = ticket_message::ingest(&state.db, &channel_id, &body.messages).await? else {
+        return Ok(StatusCode::NO_CONTENT.into_response());
+    };
+    Ok(Json(json!({ "ticket_id": ticket.id, "written": written })).into_response())
+}
+
+// ── PUT /bot/ticket-messages/:message_id ────────────────────────────────────
+
+async fn bot_edit_ticket_message(
+    State(state): State<AppState>,
+    _auth: BotAuth,
+    Path(message_id): Path<String>,
+    Json(body): Json<EditTicketMessage>,
+) -> AppResult<Json<TicketMessage>> {
+    let message = ticket_message::edit(&state.db, &message_id, body).await?;
+    Ok(Json(message))
+}
+
+// ── DELETE /bot/ticket-messages/:message_id ─────────────────────────────────
+
+async fn bot_delete_ticket_message(
+    State(state): State<AppState>,
+    _auth: BotAuth,
+    Path(message_id): Path<String>,
+) -> AppResult<Json<TicketMessage>> {
+    let message = ticket_message::mark_deleted(&state.db, &message_id).await?;
+    Ok(Json(message))
+}
+
 // ── POST /bot/guilds/:id/warns ──────────────────────────────────────────────
 
 #[derive(Debug, Deserialize)]
@@ -105,8 +418,11 @@ async fn bot_create_warn(
 
 #[derive(Debug, Deserialize)]
 pub struct BotAddXpBody {
+    pub guild_id: String,
     pub user_id: String,
-    pub amount: i64,
+    /// Fixed amount to grant. When omitted, a message grant is made using
+    /// the guild's `xp_min`/`xp_max` range and `xp_cooldown`.
+    pub amount: Option<i64>,
 }
 
 async fn bot_add_xp(
@@ -114,43 +430,20 @@ async fn bot_add_xp(
     _auth: BotAuth,
     Json(body): Json<BotAddXpBody>,
 ) -> AppResult<Json<serde_json::Value>> {
-    // Upsert user: create if not exists, then add XP
-    sqlx::query(
-        "INSERT INTO users (id, xp) VALUES (?, ?) \
-         ON CONFLICT(id) DO UPDATE SET xp = xp + excluded.xp, updated_at = datetime('now')",
-    )
-    .bind(&body.user_id)
-    .bind(body.amount)
-    .execute(&state.db)
-    .await?;
-
-    // Check for level up
-    let user_row: (i64, i64) = sqlx::query_as(
-        "SELECT xp, level FROM users WHERE id = ?",
-    )
-    .bind(&body.user_id)
-    .fetch_one(&state.db)
-    .await?;
-
-    let (xp, current_level) = user_row;
-    let new_level = calculate_level(xp);
-
-    let leveled_up = if new_level > current_level {
-        sqlx::query("UPDATE users SET level = ?, updated_at = datetime('now') WHERE id = ?")
-            .bind(new_level)
-            .bind(&body.user_id)
-            .execute(&state.db)
-            .await?;
-        true
-    } else {
-        false
+    let result = match body.amount {
+        Some(amount) => leveling::grant_xp(&state.db, &body.guild_id, &body.user_id, amount).await?,
+        None => leveling::add_xp(&state.db, &body.guild_id, &body.user_id).await?,
     };
 
     Ok(Json(json!({
+        "guild_id": body.guild_id,
         "user_id": body.user_id,
-        "xp": xp,
-        "level": new_level,
-        "leveled_up": leveled_up,
+        "xp": result.member.xp,
+        "level": result.member.level,
+        "xp_gained": result.xp_gained,
+        "leveled_up": result.leveled_up,
+        "roles_to_add": result.roles.roles_to_add,
+        "roles_to_remove": result.roles.roles_to_remove,
     })))
 }
 
@@ -277,17 +570,212 @@ async fn bot_update_config(
     Ok(Json(config))
 }
 
-// ── Helpers ─────────────────────────────────────────────────────────────────
+// ── POST /bot/reminders ─────────────────────────────────────────────────────
 
-/// Calculate the level from total XP using a simple formula.
-/// Level N requires N * 100 XP (cumulative: level 1 = 100 XP, level 2 = 300 XP total, etc.)
-fn calculate_level(xp: i64) -> i64 {
-    // Inverse of cumulative XP formula: total = level * (level + 1) * 50
-    // Solve for level: level = (-1 + sqrt(1 + 4 * total / 50)) / 2
-    let discriminant = 1.0 + (xp as f64 * 4.0 / 50.0);
-    if discriminant < 0.0 {
-        return 0;
-    }
-    let level = (-1.0 + discriminant.sqrt()) / 2.0;
-    level.floor() as i64
+async fn bot_create_reminder(
+    State(state): State<AppState>,
+    _auth: BotAuth,
+    Json(body): Json<CreateReminder>,
+) -> AppResult<Json<Reminder>> {
+    let reminder = reminder::create(&state.db, body).await?;
+    Ok(Json(reminder))
+}
+
+// ── GET /bot/users/:user_id/reminders ───────────────────────────────────────
+
+/// The user's reminders that haven't been delivered yet.
+async fn bot_list_user_reminders(
+    State(state): State<AppState>,
+    _auth: BotAuth,
+    Path(user_id): Path<String>,
+) -> AppResult<Json<Vec<Reminder>>> {
+    let reminders = reminder::list_for_user(&state.db, &user_id).await?;
+    Ok(Json(reminders))
+}
+
+// ── POST /bot/reminders/claim ───────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct BotClaimRemindersBody {
+    pub limit: Option<i64>,
+    pub lease_secs: Option<i64>,
+}
+
+async fn bot_claim_reminders(
+    State(state): State<AppState>,
+    _auth: BotAuth,
+    Json(body): Json<BotClaimRemindersBody>,
+) -> AppResult<Json<Vec<Reminder>>> {
+    let limit = body.limit.unwrap_or(25);
+    let lease_secs = body.lease_secs.unwrap_or(reminder::DEFAULT_LEASE_SECS);
+
+    let claimed = reminder::claim(&state.db, limit, lease_secs).await?;
+    Ok(Json(claimed))
+}
+
+// ── POST /bot/reminders/:rid/ack ────────────────────────────────────────────
+
+async fn bot_ack_reminder(
+    State(state): State<AppState>,
+    _auth: BotAuth,
+    Path(rid): Path<i64>,
+) -> AppResult<Json<Reminder>> {
+    let reminder = reminder::ack(&state.db, rid).await?;
+    Ok(Json(reminder))
+}
+
+// ── POST /bot/reminders/:rid/fail ───────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct BotFailReminderBody {
+    pub error: Option<String>,
+}
+
+async fn bot_fail_reminder(
+    State(state): State<AppState>,
+    _auth: BotAuth,
+    Path(rid): Path<i64>,
+    Json(body): Json<BotFailReminderBody>,
+) -> AppResult<Json<Reminder>> {
+    let error = body.error.as_deref().unwrap_or("Delivery failed");
+    let reminder = reminder::fail(&state.db, rid, error).await?;
+    Ok(Json(reminder))
+}
+
+// ── DELETE /bot/reminders/:rid ──────────────────────────────────────────────
+
+async fn bot_cancel_reminder(
+    State(state): State<AppState>,
+    _auth: BotAuth,
+    Path(rid): Path<i64>,
+) -> AppResult<Json<serde_json::Value>> {
+    reminder::cancel(&state.db, rid).await?;
+    Ok(Json(json!({ "deleted": true, "id": rid })))
+}
+
+// ── POST /bot/actions/claim ─────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct BotClaimActionsBody {
+    pub limit: Option<i64>,
+    pub lease_secs: Option<i64>,
+}
+
+async fn bot_claim_actions(
+    State(state): State<AppState>,
+    _auth: BotAuth,
+    Json(body): Json<BotClaimActionsBody>,
+) -> AppResult<Json<Vec<PendingAction>>> {
+    let limit = body.limit.unwrap_or(25);
+    let lease_secs = body.lease_secs.unwrap_or(pending_action::DEFAULT_LEASE_SECS);
+
+    let claimed = pending_action::claim(&state.db, limit, lease_secs).await?;
+    Ok(Json(claimed))
+}
+
+// ── POST /bot/actions/:aid/ack ──────────────────────────────────────────────
+
+async fn bot_ack_action(
+    State(state): State<AppState>,
+    _auth: BotAuth,
+    Path(aid): Path<i64>,
+) -> AppResult<Json<PendingAction>> {
+    let action = pending_action::ack(&state.db, aid).await?;
+    Ok(Json(action))
+}
+
+// ── POST /bot/actions/:aid/fail ─────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct BotFailActionBody {
+    pub error: Option<String>,
+}
+
+async fn bot_fail_action(
+    State(state): State<AppState>,
+    _auth: BotAuth,
+    Path(aid): Path<i64>,
+    Json(body): Json<BotFailActionBody>,
+) -> AppResult<Json<PendingAction>> {
+    let error = body.error.as_deref().unwrap_or("Action failed");
+    let action = pending_action::fail(&state.db, aid, error).await?;
+    Ok(Json(action))
+}
+
+// ── GET /bot/guilds/:id/economy/:user_id ────────────────────────────────────
+
+async fn bot_get_balance(
+    State(state): State<AppState>,
+    _auth: BotAuth,
+    Path((guild_id, user_id)): Path<(String, String)>,
+) -> AppResult<Json<MemberBalance>> {
+    let member = economy::get_balance(&state.db, &guild_id, &user_id).await?;
+    Ok(Json(member))
+}
+
+// ── POST /bot/guilds/:id/economy/daily ──────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct BotClaimDailyBody {
+    pub user_id: String,
+}
+
+async fn bot_claim_daily(
+    State(state): State<AppState>,
+    _auth: BotAuth,
+    Path(guild_id): Path<String>,
+    Json(body): Json<BotClaimDailyBody>,
+) -> AppResult<Json<DailyClaim>> {
+    let claim = economy::claim_daily(&state.db, &guild_id, &body.user_id).await?;
+    Ok(Json(claim))
+}
+
+// ── POST /bot/guilds/:id/economy/transfer ───────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct BotTransferBody {
+    pub from_user_id: String,
+    pub to_user_id: String,
+    pub amount: i64,
+    pub reason: Option<String>,
+}
+
+async fn bot_transfer(
+    State(state): State<AppState>,
+    _auth: BotAuth,
+    Path(guild_id): Path<String>,
+    Json(body): Json<BotTransferBody>,
+) -> AppResult<Json<Transaction>> {
+    let entry = economy::transfer(
+        &state.db,
+        &guild_id,
+        &body.from_user_id,
+        &body.to_user_id,
+        body.amount,
+        body.reason.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(entry))
+}
+
+// ── GET /bot/guilds/:id/economy/:user_id/transactions ───────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct BotTransactionsQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+async fn bot_list_transactions(
+    State(state): State<AppState>,
+    _auth: BotAuth,
+    Path((guild_id, user_id)): Path<(String, String)>,
+    Query(params): Query<BotTransactionsQuery>,
+) -> AppResult<Json<Vec<Transaction>>> {
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    let offset = params.offset.unwrap_or(0).max(0);
+
+    let entries = economy::list_transactions(&state.db, &guild_id, &user_id, limit, offset).await?;
+    Ok(Json(entries))
 }
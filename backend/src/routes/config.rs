@@ -5,8 +5,9 @@ use axum::{
 };
 use serde_json::json;
 
-use crate::auth::middleware::AuthUser;
+use crate::auth::middleware::GuildAdmin;
 use crate::error::AppResult;
+use crate::services::dashboard_log;
 use crate::state::AppState;
 
 pub fn router() -> Router<AppState> {
@@ -20,12 +21,15 @@ pub fn router() -> Router<AppState> {
 /// returns a stub response. The bot should be called via its API to perform
 /// the actual channel creation, then update the guild config accordingly.
 async fn setup_log_channels(
-    State(_state): State<AppState>,
-    _user: AuthUser,
+    State(state): State<AppState>,
+    admin: GuildAdmin,
     Path(id): Path<String>,
 ) -> AppResult<Json<serde_json::Value>> {
     // TODO: Call the bot's internal API to create log channels in the guild,
     // then update the guild config with the new channel IDs.
+    let details = json!({ "status": "not_implemented" });
+    dashboard_log::record(&state, &admin, &id, "config.logs_setup", details).await;
+
     Ok(Json(json!({
         "message": "Log channel auto-creation requires the bot. This is a placeholder.",
         "guild_id": id,
@@ -0,0 +1,130 @@
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    routing::{get, post},
+};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::auth::middleware::GuildAdmin;
+use crate::error::{AppError, AppResult};
+use crate::models::{BalanceEntry, Transaction};
+use crate::services::{dashboard_log, economy};
+use crate::state::AppState;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/{id}/economy/leaderboard", get(get_leaderboard))
+        .route("/{id}/economy/grant", post(grant_balance))
+        .route("/{id}/economy/take", post(take_balance))
+        .route(
+            "/{id}/economy/users/{uid}/transactions",
+            get(list_user_transactions),
+        )
+}
+
+// ── Query params ────────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct PageQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+// ── GET /guilds/:id/economy/leaderboard ─────────────────────────────────────
+
+async fn get_leaderboard(
+    State(state): State<AppState>,
+    _admin: GuildAdmin,
+    Path(id): Path<String>,
+    Query(params): Query<PageQuery>,
+) -> AppResult<Json<Vec<BalanceEntry>>> {
+    let limit = params.limit.unwrap_or(25).clamp(1, 100);
+    let offset = params.offset.unwrap_or(0).max(0);
+
+    let entries = economy::get_leaderboard(&state.db, &id, limit, offset).await?;
+    Ok(Json(entries))
+}
+
+// ── POST /guilds/:id/economy/grant ──────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct AdjustBalanceBody {
+    pub user_id: String,
+    pub amount: i64,
+    pub reason: Option<String>,
+}
+
+async fn grant_balance(
+    State(state): State<AppState>,
+    admin: GuildAdmin,
+    Path(id): Path<String>,
+    Json(body): Json<AdjustBalanceBody>,
+) -> AppResult<Json<Transaction>> {
+    adjust_balance(&state, &admin, &id, body, 1).await
+}
+
+// ── POST /guilds/:id/economy/take ───────────────────────────────────────────
+
+async fn take_balance(
+    State(state): State<AppState>,
+    admin: GuildAdmin,
+    Path(id): Path<String>,
+    Json(body): Json<AdjustBalanceBody>,
+) -> AppResult<Json<Transaction>> {
+    adjust_balance(&state, &admin, &id, body, -1).await
+}
+
+async fn adjust_balance(
+    state: &AppState,
+    admin: &GuildAdmin,
+    guild_id: &str,
+    body: AdjustBalanceBody,
+    sign: i64,
+) -> AppResult<Json<Transaction>> {
+    if body.amount <= 0 {
+        return Err(AppError::BadRequest("amount must be positive".to_string()));
+    }
+
+    let entry = economy::adjust(
+        &state.db,
+        guild_id,
+        &body.user_id,
+        sign * body.amount,
+        &admin.user.id,
+        body.reason.as_deref(),
+    )
+    .await?;
+
+    dashboard_log::record(
+        state,
+        admin,
+        guild_id,
+        &format!("economy.{}", entry.kind),
+        json!({
+            "user_id": entry.user_id,
+            "amount": entry.amount,
+            "balance_after": entry.balance_after,
+            "reason": entry.reason,
+        }),
+    )
+    .await;
+
+    Ok(Json(entry))
+}
+
+// ── GET /guilds/:id/economy/users/:uid/transactions ─────────────────────────
+
+/// A member's ledger in this guild, newest first.
+async fn list_user_transactions(
+    State(state): State<AppState>,
+    _admin: GuildAdmin,
+    Path((id, uid)): Path<(String, String)>,
+    Query(params): Query<PageQuery>,
+) -> AppResult<Json<Vec<Transaction>>> {
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+    let offset = params.offset.unwrap_or(0).max(0);
+
+    let entries = economy::list_transactions(&state.db, &id, &uid, limit, offset).await?;
+    Ok(Json(entries))
+}
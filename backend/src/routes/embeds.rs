@@ -6,9 +6,10 @@ use axum::{
 use serde::Deserialize;
 use serde_json::json;
 
-use crate::auth::middleware::AuthUser;
+use crate::auth::middleware::GuildAdmin;
 use crate::error::{AppError, AppResult};
 use crate::models::EmbedTemplate;
+use crate::services::dashboard_log;
 use crate::state::AppState;
 
 pub fn router() -> Router<AppState> {
@@ -27,7 +28,7 @@ pub fn router() -> Router<AppState> {
 
 async fn list_embed_templates(
     State(state): State<AppState>,
-    _user: AuthUser,
+    _admin: GuildAdmin,
     Path(id): Path<String>,
 ) -> AppResult<Json<Vec<EmbedTemplate>>> {
     let templates = sqlx::query_as::<_, EmbedTemplate>(
@@ -51,7 +52,7 @@ pub struct CreateEmbedTemplateBody {
 
 async fn create_embed_template(
     State(state): State<AppState>,
-    _user: AuthUser,
+    admin: GuildAdmin,
     Path(id): Path<String>,
     Json(body): Json<CreateEmbedTemplateBody>,
 ) -> AppResult<Json<EmbedTemplate>> {
@@ -82,6 +83,15 @@ async fn create_embed_template(
     .fetch_one(&state.db)
     .await?;
 
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "embed_template.create",
+        json!({ "id": template.id, "name": template.name }),
+    )
+    .await;
+
     Ok(Json(template))
 }
 
@@ -89,7 +99,7 @@ async fn create_embed_template(
 
 async fn delete_embed_template(
     State(state): State<AppState>,
-    _user: AuthUser,
+    admin: GuildAdmin,
     Path((id, eid)): Path<(String, i64)>,
 ) -> AppResult<Json<serde_json::Value>> {
     let result = sqlx::query(
@@ -106,5 +116,7 @@ async fn delete_embed_template(
         )));
     }
 
+    dashboard_log::record(&state, &admin, &id, "embed_template.delete", json!({ "id": eid })).await;
+
     Ok(Json(json!({ "deleted": true, "id": eid })))
 }
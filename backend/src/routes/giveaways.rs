@@ -6,9 +6,10 @@ use axum::{
 use serde::Deserialize;
 use serde_json::json;
 
-use crate::auth::middleware::AuthUser;
+use crate::auth::middleware::GuildAdmin;
 use crate::error::{AppError, AppResult};
 use crate::models::Giveaway;
+use crate::services::dashboard_log;
 use crate::state::AppState;
 
 pub fn router() -> Router<AppState> {
@@ -24,7 +25,7 @@ pub fn router() -> Router<AppState> {
 
 async fn list_giveaways(
     State(state): State<AppState>,
-    _user: AuthUser,
+    _admin: GuildAdmin,
     Path(id): Path<String>,
 ) -> AppResult<Json<Vec<Giveaway>>> {
     let giveaways = sqlx::query_as::<_, Giveaway>(
@@ -51,7 +52,7 @@ pub struct CreateGiveawayBody {
 
 async fn create_giveaway(
     State(state): State<AppState>,
-    user: AuthUser,
+    admin: GuildAdmin,
     Path(id): Path<String>,
     Json(body): Json<CreateGiveawayBody>,
 ) -> AppResult<Json<Giveaway>> {
@@ -62,7 +63,7 @@ async fn create_giveaway(
     .bind(&id)
     .bind(&body.channel_id)
     .bind(&body.message_id)
-    .bind(&user.id)
+    .bind(&admin.user.id)
     .bind(&body.prize)
     .bind(body.winners.unwrap_or(1))
     .bind(&body.required_role)
@@ -76,6 +77,15 @@ async fn create_giveaway(
     .fetch_one(&state.db)
     .await?;
 
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "giveaway.create",
+        json!({ "id": giveaway.id, "prize": giveaway.prize, "ends_at": giveaway.ends_at }),
+    )
+    .await;
+
     Ok(Json(giveaway))
 }
 
@@ -83,7 +93,7 @@ async fn create_giveaway(
 
 async fn delete_giveaway(
     State(state): State<AppState>,
-    _user: AuthUser,
+    admin: GuildAdmin,
     Path((id, gid)): Path<(String, i64)>,
 ) -> AppResult<Json<serde_json::Value>> {
     let result = sqlx::query("DELETE FROM giveaways WHERE guild_id = ? AND id = ?")
@@ -96,6 +106,8 @@ async fn delete_giveaway(
         return Err(AppError::NotFound(format!("Giveaway {gid} not found")));
     }
 
+    dashboard_log::record(&state, &admin, &id, "giveaway.delete", json!({ "id": gid })).await;
+
     Ok(Json(json!({ "deleted": true, "id": gid })))
 }
 
@@ -103,7 +115,7 @@ async fn delete_giveaway(
 
 async fn end_giveaway(
     State(state): State<AppState>,
-    _user: AuthUser,
+    admin: GuildAdmin,
     Path((id, gid)): Path<(String, i64)>,
 ) -> AppResult<Json<Giveaway>> {
     let giveaway = sqlx::query_as::<_, Giveaway>(
@@ -142,6 +154,15 @@ async fn end_giveaway(
     .fetch_one(&state.db)
     .await?;
 
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "giveaway.end",
+        json!({ "id": gid, "winner_ids": winner_ids }),
+    )
+    .await;
+
     Ok(Json(updated))
 }
 
@@ -149,7 +170,7 @@ async fn end_giveaway(
 
 async fn reroll_giveaway(
     State(state): State<AppState>,
-    _user: AuthUser,
+    admin: GuildAdmin,
     Path((id, gid)): Path<(String, i64)>,
 ) -> AppResult<Json<Giveaway>> {
     let giveaway = sqlx::query_as::<_, Giveaway>(
@@ -187,6 +208,15 @@ async fn reroll_giveaway(
     .fetch_one(&state.db)
     .await?;
 
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "giveaway.reroll",
+        json!({ "id": gid, "winner_ids": winner_ids }),
+    )
+    .await;
+
     Ok(Json(updated))
 }
 
@@ -194,7 +224,7 @@ async fn reroll_giveaway(
 
 async fn enter_giveaway(
     State(state): State<AppState>,
-    user: AuthUser,
+    admin: GuildAdmin,
     Path((id, gid)): Path<(String, i64)>,
 ) -> AppResult<Json<serde_json::Value>> {
     let giveaway = sqlx::query_as::<_, Giveaway>(
@@ -213,11 +243,11 @@ async fn enter_giveaway(
     let mut participants: Vec<String> =
         serde_json::from_str(&giveaway.participants).unwrap_or_default();
 
-    if participants.contains(&user.id) {
+    if participants.contains(&admin.user.id) {
         return Err(AppError::BadRequest("Already entered this giveaway".to_string()));
     }
 
-    participants.push(user.id.clone());
+    participants.push(admin.user.id.clone());
     let participants_json = serde_json::to_string(&participants)
         .unwrap_or_else(|_| "[]".to_string());
 
@@ -228,9 +258,18 @@ async fn enter_giveaway(
         .execute(&state.db)
         .await?;
 
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "giveaway.enter",
+        json!({ "id": gid, "total_participants": participants.len() }),
+    )
+    .await;
+
     Ok(Json(json!({
         "entered": true,
-        "user_id": user.id,
+        "user_id": admin.user.id,
         "total_participants": participants.len(),
     })))
 }
@@ -6,9 +6,10 @@ use axum::{
 use serde_json::json;
 
 use crate::auth::discord_oauth::{self, has_guild_access};
-use crate::auth::middleware::AuthUser;
+use crate::auth::middleware::{AuthUser, GuildAdmin};
 use crate::error::{AppError, AppResult};
-use crate::models::GuildConfig;
+use crate::models::{GuildConfig, RoutingPolicy};
+use crate::services::{dashboard_log, leveling, stats};
 use crate::state::AppState;
 
 pub fn router() -> Router<AppState> {
@@ -67,49 +68,11 @@ async fn list_guilds(
 /// Return aggregate statistics for a guild.
 async fn guild_stats(
     State(state): State<AppState>,
-    _user: AuthUser,
+    _admin: GuildAdmin,
     Path(id): Path<String>,
 ) -> AppResult<Json<serde_json::Value>> {
-    let open_tickets: (i64,) = sqlx::query_as(
-        "SELECT COUNT(*) FROM tickets WHERE guild_id = ? AND status = 'open'",
-    )
-    .bind(&id)
-    .fetch_one(&state.db)
-    .await?;
-
-    let total_tickets: (i64,) =
-        sqlx::query_as("SELECT COUNT(*) FROM tickets WHERE guild_id = ?")
-            .bind(&id)
-            .fetch_one(&state.db)
-            .await?;
-
-    let total_warns: (i64,) =
-        sqlx::query_as("SELECT COUNT(*) FROM warns WHERE guild_id = ?")
-            .bind(&id)
-            .fetch_one(&state.db)
-            .await?;
-
-    let active_giveaways: (i64,) = sqlx::query_as(
-        "SELECT COUNT(*) FROM giveaways WHERE guild_id = ? AND ended = 0",
-    )
-    .bind(&id)
-    .fetch_one(&state.db)
-    .await?;
-
-    let total_suggestions: (i64,) =
-        sqlx::query_as("SELECT COUNT(*) FROM suggestions WHERE guild_id = ?")
-            .bind(&id)
-            .fetch_one(&state.db)
-            .await?;
-
-    Ok(Json(json!({
-        "guild_id": id,
-        "open_tickets": open_tickets.0,
-        "total_tickets": total_tickets.0,
-        "total_warns": total_warns.0,
-        "active_giveaways": active_giveaways.0,
-        "total_suggestions": total_suggestions.0,
-    })))
+    let stats = stats::guild_stats(&state.db, &id).await?;
+    Ok(Json(stats))
 }
 
 // ── GET /:id/channels ───────────────────────────────────────────────────────
@@ -117,7 +80,7 @@ async fn guild_stats(
 /// Placeholder: channel list requires the Discord bot.
 /// Returns empty array until bot provides this data.
 async fn guild_channels(
-    _user: AuthUser,
+    _admin: GuildAdmin,
     Path(_id): Path<String>,
 ) -> Json<serde_json::Value> {
     Json(json!({ "channels": [] }))
@@ -128,7 +91,7 @@ async fn guild_channels(
 /// Placeholder: role list requires the Discord bot.
 /// Returns empty array until bot provides this data.
 async fn guild_roles(
-    _user: AuthUser,
+    _admin: GuildAdmin,
     Path(_id): Path<String>,
 ) -> Json<serde_json::Value> {
     Json(json!({ "roles": [] }))
@@ -139,7 +102,7 @@ async fn guild_roles(
 /// Get the guild configuration. Upserts a default row if one does not exist.
 async fn get_config(
     State(state): State<AppState>,
-    _user: AuthUser,
+    _admin: GuildAdmin,
     Path(id): Path<String>,
 ) -> AppResult<Json<GuildConfig>> {
     // Upsert: insert default if missing
@@ -162,7 +125,7 @@ async fn get_config(
 /// Accepts a raw JSON object so we can dynamically update only the fields provided.
 async fn update_config(
     State(state): State<AppState>,
-    _user: AuthUser,
+    admin: GuildAdmin,
     Path(id): Path<String>,
     Json(body): Json<serde_json::Value>,
 ) -> AppResult<Json<GuildConfig>> {
@@ -176,15 +139,42 @@ async fn update_config(
         .as_object()
         .ok_or_else(|| AppError::BadRequest("Body must be a JSON object".to_string()))?;
 
+    let before = sqlx::query_as::<_, GuildConfig>("SELECT * FROM guilds WHERE id = ?")
+        .bind(&id)
+        .fetch_one(&state.db)
+        .await?;
+
     if fields.is_empty() {
-        let config = sqlx::query_as::<_, GuildConfig>("SELECT * FROM guilds WHERE id = ?")
-            .bind(&id)
-            .fetch_one(&state.db)
-            .await?;
-        return Ok(Json(config));
+        return Ok(Json(before));
+    }
+
+    let reward_mode = fields.get("level_reward_mode").map(|m| m.as_str());
+    if reward_mode.is_some_and(|m| !matches!(m, Some("stack" | "replace"))) {
+        return Err(AppError::BadRequest(
+            "level_reward_mode must be 'stack' or 'replace'".to_string(),
+        ));
+    }
+    validate_level_curve(&before, fields)?;
+    validate_transcript_retention(fields)?;
+    for key in [
+        "ticket_inactivity_warn_hours",
+        "ticket_inactivity_close_hours",
+        "ticket_max_open",
+        "ticket_cooldown_minutes",
+    ] {
+        if fields.get(key).is_some_and(|h| !h.is_null() && h.as_i64().is_none_or(|h| h < 1)) {
+            return Err(AppError::BadRequest(format!("{key} must be a positive integer or null")));
+        }
+    }
+    let routing = fields.get("ticket_routing").map(|r| r.as_str());
+    if routing.is_some_and(|r| r.and_then(RoutingPolicy::parse).is_none()) {
+        return Err(AppError::BadRequest(
+            "ticket_routing must be 'manual', 'round_robin' or 'least_open'".to_string(),
+        ));
     }
 
     update_guild_fields(&state.db, &id, fields).await?;
+    leveling::mark_curve_changed(&state.db, &id, &before).await?;
 
     // Return the updated config
     let config = sqlx::query_as::<_, GuildConfig>("SELECT * FROM guilds WHERE id = ?")
@@ -192,9 +182,80 @@ async fn update_config(
         .fetch_one(&state.db)
         .await?;
 
+    let changes = dashboard_log::diff(&json!(before), &json!(config));
+    if changes.as_object().is_some_and(|c| !c.is_empty()) {
+        dashboard_log::record_changes(
+            &state,
+            &admin,
+            &id,
+            "config.update",
+            json!({ "fields": fields.keys().collect::<Vec<_>>() }),
+            changes,
+        )
+        .await;
+    }
+
     Ok(Json(config))
 }
 
+/// Reject level curve settings that would leave the guild with an unusable
+/// curve, checking the submitted fields merged over the current config.
+fn validate_level_curve(
+    before: &GuildConfig,
+    fields: &serde_json::Map<String, serde_json::Value>,
+) -> AppResult<()> {
+    if !fields.keys().any(|k| k.starts_with("level_curve")) {
+        return Ok(());
+    }
+
+    let kind = match fields.get("level_curve") {
+        Some(v) => v.as_str().ok_or_else(|| AppError::BadRequest("level_curve must be a string".to_string()))?,
+        None => &before.level_curve,
+    };
+    let step = match fields.get("level_curve_step") {
+        Some(v) => v
+            .as_i64()
+            .ok_or_else(|| AppError::BadRequest("level_curve_step must be an integer".to_string()))?,
+        None => before.level_curve_step,
+    };
+    // Arrays are stored as JSON text by update_guild_fields, so accept both
+    let table = match fields.get("level_curve_table") {
+        Some(serde_json::Value::Null) => None,
+        Some(serde_json::Value::String(s)) => Some(s.clone()),
+        Some(v) => Some(v.to_string()),
+        None => before.level_curve_table.clone(),
+    };
+
+    leveling::LevelCurve::parse(kind, step, table.as_deref())?;
+    Ok(())
+}
+
+/// Reject transcript retention settings the scheduler can't apply.
+fn validate_transcript_retention(fields: &serde_json::Map<String, serde_json::Value>) -> AppResult<()> {
+    let days = fields.get("transcript_retention_days");
+    if days.is_some_and(|d| !d.is_null() && d.as_i64().is_none_or(|d| d < 1)) {
+        return Err(AppError::BadRequest(
+            "transcript_retention_days must be a positive integer or null".to_string(),
+        ));
+    }
+
+    let action = fields.get("transcript_retention_action").map(|a| a.as_str());
+    if action.is_some_and(|a| !matches!(a, Some("archive" | "delete"))) {
+        return Err(AppError::BadRequest(
+            "transcript_retention_action must be 'archive' or 'delete'".to_string(),
+        ));
+    }
+
+    let storage = fields.get("transcript_archive_storage").map(|s| s.as_str());
+    if storage.is_some_and(|s| !matches!(s, Some("inline" | "directory"))) {
+        return Err(AppError::BadRequest(
+            "transcript_archive_storage must be 'inline' or 'directory'".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
 /// Update guild config fields one-by-one from a JSON map.
 async fn update_guild_fields(
     db: &sqlx::SqlitePool,
@@ -208,7 +269,11 @@ async fn update_guild_fields(
         "ticket_category_id", "ticket_transcript_channel", "ticket_review_channel",
         "ticket_public_review_channel", "ticket_counter", "ticket_support_role",
         "ticket_modal_label", "ticket_modal_placeholder", "ticket_modal_required",
+        "ticket_inactivity_warn_hours", "ticket_inactivity_close_hours", "ticket_routing",
+        "ticket_max_open", "ticket_cooldown_minutes", "ticket_required_role",
+        "transcript_retention_days", "transcript_retention_action", "transcript_archive_storage",
         "level_up_channel", "level_up_message", "xp_cooldown", "xp_min", "xp_max",
+        "level_curve", "level_curve_step", "level_curve_table", "level_reward_mode",
         "welcome_channel", "welcome_message", "leave_channel", "leave_message",
         "suggestion_channel", "suggestion_approved_channel",
         "starboard_channel", "starboard_threshold",
@@ -5,9 +5,10 @@ use axum::{
 };
 use serde::Deserialize;
 
-use crate::auth::middleware::AuthUser;
+use crate::auth::middleware::GuildAdmin;
 use crate::error::AppResult;
-use crate::models::User;
+use crate::models::LeaderboardEntry;
+use crate::services::leveling;
 use crate::state::AppState;
 
 pub fn router() -> Router<AppState> {
@@ -24,25 +25,17 @@ pub struct LeaderboardQuery {
 
 // ── GET /guilds/:id/leaderboard ─────────────────────────────────────────────
 
-/// Return the top users by XP. Since users are global (not per-guild in the
-/// current schema), this returns the global leaderboard. If a per-guild XP
-/// table is added later, this can be adjusted.
+/// Return the guild's members ranked by XP.
 async fn get_leaderboard(
     State(state): State<AppState>,
-    _user: AuthUser,
-    Path(_id): Path<String>,
+    _admin: GuildAdmin,
+    Path(id): Path<String>,
     Query(params): Query<LeaderboardQuery>,
-) -> AppResult<Json<Vec<User>>> {
+) -> AppResult<Json<Vec<LeaderboardEntry>>> {
     let limit = params.limit.unwrap_or(25).clamp(1, 100);
     let offset = params.offset.unwrap_or(0).max(0);
 
-    let users = sqlx::query_as::<_, User>(
-        "SELECT * FROM users WHERE visible_xp = 1 ORDER BY xp DESC LIMIT ? OFFSET ?",
-    )
-    .bind(limit)
-    .bind(offset)
-    .fetch_all(&state.db)
-    .await?;
+    let entries = leveling::get_leaderboard(&state.db, &id, limit, offset).await?;
 
-    Ok(Json(users))
+    Ok(Json(entries))
 }
@@ -0,0 +1,178 @@
+use axum::{
+    extract::{Path, State},
+    routing::{get, put},
+    Json, Router,
+};
+use serde_json::json;
+
+use crate::auth::middleware::GuildAdmin;
+use crate::error::{AppError, AppResult};
+use crate::models::{CreateLevelReward, LevelReward, UpdateLevelReward};
+use crate::services::dashboard_log;
+use crate::state::AppState;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/{id}/level-rewards",
+            get(list_level_rewards).post(create_level_reward),
+        )
+        .route(
+            "/{id}/level-rewards/{lrid}",
+            put(update_level_reward).delete(delete_level_reward),
+        )
+}
+
+// ── GET /guilds/:id/level-rewards ───────────────────────────────────────────
+
+async fn list_level_rewards(
+    State(state): State<AppState>,
+    _admin: GuildAdmin,
+    Path(id): Path<String>,
+) -> AppResult<Json<Vec<LevelReward>>> {
+    let rewards = sqlx::query_as::<_, LevelReward>(
+        "SELECT * FROM level_rewards WHERE guild_id = ? ORDER BY level ASC, id ASC",
+    )
+    .bind(&id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(rewards))
+}
+
+// ── POST /guilds/:id/level-rewards ──────────────────────────────────────────
+
+async fn create_level_reward(
+    State(state): State<AppState>,
+    admin: GuildAdmin,
+    Path(id): Path<String>,
+    Json(body): Json<CreateLevelReward>,
+) -> AppResult<Json<LevelReward>> {
+    let action = body.action.as_deref().unwrap_or("add");
+    validate_reward(body.level, action)?;
+
+    let reward = sqlx::query_as::<_, LevelReward>(
+        "INSERT INTO level_rewards (guild_id, level, role_id, action) VALUES (?, ?, ?, ?) RETURNING *",
+    )
+    .bind(&id)
+    .bind(body.level)
+    .bind(&body.role_id)
+    .bind(action)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| duplicate_reward(e, body.level, &body.role_id))?;
+
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "level_reward.create",
+        json!({
+            "id": reward.id,
+            "level": reward.level,
+            "role_id": reward.role_id,
+            "action": reward.action,
+        }),
+    )
+    .await;
+
+    Ok(Json(reward))
+}
+
+// ── PUT /guilds/:id/level-rewards/:lrid ─────────────────────────────────────
+
+async fn update_level_reward(
+    State(state): State<AppState>,
+    admin: GuildAdmin,
+    Path((id, lrid)): Path<(String, i64)>,
+    Json(body): Json<UpdateLevelReward>,
+) -> AppResult<Json<LevelReward>> {
+    let before = sqlx::query_as::<_, LevelReward>(
+        "SELECT * FROM level_rewards WHERE guild_id = ? AND id = ?",
+    )
+    .bind(&id)
+    .bind(lrid)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("Level reward {lrid} not found")))?;
+
+    let level = body.level.unwrap_or(before.level);
+    let role_id = body.role_id.as_deref().unwrap_or(&before.role_id);
+    let action = body.action.as_deref().unwrap_or(&before.action);
+    validate_reward(level, action)?;
+
+    let reward = sqlx::query_as::<_, LevelReward>(
+        "UPDATE level_rewards SET level = ?, role_id = ?, action = ? \
+         WHERE guild_id = ? AND id = ? RETURNING *",
+    )
+    .bind(level)
+    .bind(role_id)
+    .bind(action)
+    .bind(&id)
+    .bind(lrid)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| duplicate_reward(e, level, role_id))?;
+
+    let changes = dashboard_log::diff(&json!(before), &json!(reward));
+    if changes.as_object().is_some_and(|c| !c.is_empty()) {
+        dashboard_log::record_changes(
+            &state,
+            &admin,
+            &id,
+            "level_reward.update",
+            json!({ "id": lrid }),
+            changes,
+        )
+        .await;
+    }
+
+    Ok(Json(reward))
+}
+
+// ── DELETE /guilds/:id/level-rewards/:lrid ──────────────────────────────────
+
+async fn delete_level_reward(
+    State(state): State<AppState>,
+    admin: GuildAdmin,
+    Path((id, lrid)): Path<(String, i64)>,
+) -> AppResult<Json<serde_json::Value>> {
+    let result = sqlx::query("DELETE FROM level_rewards WHERE guild_id = ? AND id = ?")
+        .bind(&id)
+        .bind(lrid)
+        .execute(&state.db)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Level reward {lrid} not found")));
+    }
+
+    dashboard_log::record(&state, &admin, &id, "level_reward.delete", json!({ "id": lrid })).await;
+
+    Ok(Json(json!({ "deleted": true, "id": lrid })))
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+fn validate_reward(level: i64, action: &str) -> AppResult<()> {
+    if level < 1 {
+        return Err(AppError::BadRequest("level must be at least 1".to_string()));
+    }
+    if !matches!(action, "add" | "remove") {
+        return Err(AppError::BadRequest(format!(
+            "Invalid action '{action}'. Must be 'add' or 'remove'"
+        )));
+    }
+    Ok(())
+}
+
+fn duplicate_reward(e: sqlx::Error, level: i64, role_id: &str) -> AppError {
+    match e {
+        sqlx::Error::Database(ref db_err) if db_err.message().contains("UNIQUE") => {
+            AppError::BadRequest(format!(
+                "A reward for role {role_id} at level {level} already exists"
+            ))
+        }
+        other => AppError::Database(other),
+    }
+}
@@ -6,10 +6,11 @@ use axum::{
 use serde::Deserialize;
 use serde_json::json;
 
-use crate::auth::middleware::AuthUser;
+use crate::auth::middleware::GuildAdmin;
 use crate::error::AppResult;
 use crate::models::DashboardLog;
 use crate::state::AppState;
+use crate::timestamp;
 
 pub fn router() -> Router<AppState> {
     Router::new().route("/{id}/logs", get(list_logs))
@@ -22,13 +23,19 @@ pub struct LogsQuery {
     pub page: Option<i64>,
     pub limit: Option<i64>,
     pub action: Option<String>,
+    /// Only entries made by this Discord user ID.
+    pub user_id: Option<String>,
+    /// Inclusive lower bound, `YYYY-MM-DD` or `YYYY-MM-DD HH:MM:SS` (UTC).
+    pub from: Option<String>,
+    /// Inclusive upper bound, same format as `from`. A bare date covers the whole day.
+    pub to: Option<String>,
 }
 
 // ── GET /guilds/:id/logs ────────────────────────────────────────────────────
 
 async fn list_logs(
     State(state): State<AppState>,
-    _user: AuthUser,
+    _admin: GuildAdmin,
     Path(id): Path<String>,
     Query(params): Query<LogsQuery>,
 ) -> AppResult<Json<serde_json::Value>> {
@@ -36,52 +43,53 @@ async fn list_logs(
     let limit = params.limit.unwrap_or(50).clamp(1, 200);
     let offset = (page - 1) * limit;
 
-    let (logs, total) = if let Some(ref action) = params.action {
-        let logs = sqlx::query_as::<_, DashboardLog>(
-            "SELECT * FROM dashboard_logs WHERE guild_id = ? AND action = ? \
-             ORDER BY created_at DESC LIMIT ? OFFSET ?",
-        )
-        .bind(&id)
-        .bind(action)
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(&state.db)
-        .await?;
+    // Build the WHERE clause from whichever filters were supplied
+    let mut conditions = vec!["guild_id = ?"];
+    let mut binds = vec![id.clone()];
 
-        let total: (i64,) = sqlx::query_as(
-            "SELECT COUNT(*) FROM dashboard_logs WHERE guild_id = ? AND action = ?",
-        )
-        .bind(&id)
-        .bind(action)
-        .fetch_one(&state.db)
-        .await?;
+    if let Some(ref action) = params.action {
+        conditions.push("action = ?");
+        binds.push(action.clone());
+    }
+    if let Some(ref user_id) = params.user_id {
+        conditions.push("user_id = ?");
+        binds.push(user_id.clone());
+    }
+    let (from, to) = timestamp::parse_range(params.from.as_deref(), params.to.as_deref())?;
+    if let Some(from) = from {
+        conditions.push("created_at >= ?");
+        binds.push(from);
+    }
+    if let Some(to) = to {
+        conditions.push("created_at <= ?");
+        binds.push(to);
+    }
 
-        (logs, total.0)
-    } else {
-        let logs = sqlx::query_as::<_, DashboardLog>(
-            "SELECT * FROM dashboard_logs WHERE guild_id = ? \
-             ORDER BY created_at DESC LIMIT ? OFFSET ?",
-        )
-        .bind(&id)
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(&state.db)
-        .await?;
+    let where_clause = conditions.join(" AND ");
 
-        let total: (i64,) = sqlx::query_as(
-            "SELECT COUNT(*) FROM dashboard_logs WHERE guild_id = ?",
-        )
-        .bind(&id)
-        .fetch_one(&state.db)
-        .await?;
+    let sql = format!(
+        "SELECT * FROM dashboard_logs WHERE {where_clause} \
+         ORDER BY created_at DESC, id DESC LIMIT ? OFFSET ?"
+    );
+    let mut query = sqlx::query_as::<_, DashboardLog>(&sql);
+    for value in &binds {
+        query = query.bind(value);
+    }
+    let logs = query.bind(limit).bind(offset).fetch_all(&state.db).await?;
 
-        (logs, total.0)
-    };
+    let count_sql = format!("SELECT COUNT(*) FROM dashboard_logs WHERE {where_clause}");
+    let mut count_query = sqlx::query_as::<_, (i64,)>(&count_sql);
+    for value in &binds {
+        count_query = count_query.bind(value);
+    }
+    let total = count_query.fetch_one(&state.db).await?;
 
     Ok(Json(json!({
         "logs": logs,
-        "total": total,
+        "total": total.0,
         "page": page,
         "limit": limit,
     })))
 }
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
@@ -1,14 +1,18 @@
+pub mod analytics;
 pub mod auth;
 pub mod auto_roles;
 pub mod bot_actions;
 pub mod config;
+pub mod economy;
 pub mod embeds;
 pub mod giveaways;
 pub mod guilds;
 pub mod leaderboard;
+pub mod level_rewards;
 pub mod logs;
 pub mod moderation;
 pub mod reaction_roles;
+pub mod search;
 pub mod suggestions;
 pub mod tickets;
 pub mod transcripts;
@@ -17,6 +21,7 @@ use axum::{routing::get, Router};
 use tower_http::services::{ServeDir, ServeFile};
 
 use crate::state::AppState;
+use crate::ws;
 
 /// Build the top-level router with all sub-routers nested.
 pub fn build_router(state: AppState) -> Router {
@@ -35,15 +40,23 @@ pub fn build_router(state: AppState) -> Router {
         .nest("/guilds", auto_roles::router())
         .nest("/guilds", embeds::router())
         .nest("/guilds", leaderboard::router())
+        .nest("/guilds", level_rewards::router())
+        .nest("/guilds", economy::router())
         .nest("/guilds", transcripts::guild_router())
+        .nest("/guilds", search::router())
+        .nest("/guilds", analytics::router())
         .nest("/guilds", logs::router())
         .nest("/guilds", config::router())
-        .nest("/bot", bot_actions::router());
+        .nest("/bot", bot_actions::router())
+        // Live dashboard events (session cookie required)
+        .route("/ws", get(ws::handlers::ws_handler));
 
     let app = Router::new()
         .nest("/api/v1", api)
-        // Public transcript view (requires auth via query or cookie)
+        // Transcript view for the ticket owner and guild staff (session cookie)
         .route("/transcript/{id}", get(transcripts::view_transcript))
+        // Share links: the signed token stands in for a login
+        .route("/transcript/shared/{token}", get(transcripts::view_shared_transcript))
         .with_state(state);
 
     // Serve SPA static files as fallback
@@ -6,10 +6,10 @@ use axum::{
 use serde::Deserialize;
 use serde_json::json;
 
-use crate::auth::middleware::AuthUser;
+use crate::auth::middleware::GuildAdmin;
 use crate::error::AppResult;
 use crate::models::{CreateWarn, Warn};
-use crate::services::moderation;
+use crate::services::{dashboard_log, moderation};
 use crate::state::AppState;
 
 pub fn router() -> Router<AppState> {
@@ -27,7 +27,7 @@ pub fn router() -> Router<AppState> {
 /// List all warns for a guild.
 async fn list_warns(
     State(state): State<AppState>,
-    _user: AuthUser,
+    _admin: GuildAdmin,
     Path(id): Path<String>,
 ) -> AppResult<Json<Vec<Warn>>> {
     let warns = sqlx::query_as::<_, Warn>(
@@ -46,7 +46,7 @@ async fn list_warns(
 /// List all warns for a specific user in a guild.
 async fn list_user_warns(
     State(state): State<AppState>,
-    _user: AuthUser,
+    _admin: GuildAdmin,
     Path((id, uid)): Path<(String, String)>,
 ) -> AppResult<Json<Vec<Warn>>> {
     let warns = moderation::get_warns(&state.db, &id, &uid).await?;
@@ -64,7 +64,7 @@ pub struct CreateWarnBody {
 /// Create a new warn. The moderator is the authenticated user.
 async fn create_warn(
     State(state): State<AppState>,
-    user: AuthUser,
+    admin: GuildAdmin,
     Path(id): Path<String>,
     Json(body): Json<CreateWarnBody>,
 ) -> AppResult<Json<Warn>> {
@@ -72,13 +72,22 @@ async fn create_warn(
         &state.db,
         CreateWarn {
             target_user_id: body.target_user_id,
-            moderator_id: user.id,
+            moderator_id: admin.user.id.clone(),
             reason: body.reason,
-            guild_id: id,
+            guild_id: id.clone(),
         },
     )
     .await?;
 
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "warn.create",
+        json!({ "id": warn.id, "target_user_id": warn.target_user_id, "reason": warn.reason }),
+    )
+    .await;
+
     Ok(Json(warn))
 }
 
@@ -87,10 +96,12 @@ async fn create_warn(
 /// Delete a specific warn by ID.
 async fn delete_warn(
     State(state): State<AppState>,
-    _user: AuthUser,
+    admin: GuildAdmin,
     Path((id, wid)): Path<(String, i64)>,
 ) -> AppResult<Json<serde_json::Value>> {
     moderation::delete_warn(&state.db, &id, wid).await?;
+    dashboard_log::record(&state, &admin, &id, "warn.delete", json!({ "id": wid })).await;
+
     Ok(Json(json!({ "deleted": true, "id": wid })))
 }
 
@@ -99,9 +110,18 @@ async fn delete_warn(
 /// Clear all warns for a user in a guild.
 async fn clear_user_warns(
     State(state): State<AppState>,
-    _user: AuthUser,
+    admin: GuildAdmin,
     Path((id, uid)): Path<(String, String)>,
 ) -> AppResult<Json<serde_json::Value>> {
     let count = moderation::clear_warns(&state.db, &id, &uid).await?;
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "warn.clear",
+        json!({ "target_user_id": uid, "cleared": count }),
+    )
+    .await;
+
     Ok(Json(json!({ "cleared": count })))
 }
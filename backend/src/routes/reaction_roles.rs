@@ -6,9 +6,10 @@ use axum::{
 use serde::Deserialize;
 use serde_json::json;
 
-use crate::auth::middleware::AuthUser;
+use crate::auth::middleware::GuildAdmin;
 use crate::error::{AppError, AppResult};
 use crate::models::ReactionRole;
+use crate::services::dashboard_log;
 use crate::state::AppState;
 
 pub fn router() -> Router<AppState> {
@@ -24,7 +25,7 @@ pub fn router() -> Router<AppState> {
 
 async fn list_reaction_roles(
     State(state): State<AppState>,
-    _user: AuthUser,
+    _admin: GuildAdmin,
     Path(id): Path<String>,
 ) -> AppResult<Json<Vec<ReactionRole>>> {
     let roles = sqlx::query_as::<_, ReactionRole>(
@@ -56,7 +57,7 @@ pub struct RoleEntry {
 
 async fn create_reaction_roles(
     State(state): State<AppState>,
-    _user: AuthUser,
+    admin: GuildAdmin,
     Path(id): Path<String>,
     Json(body): Json<CreateReactionRolesBody>,
 ) -> AppResult<Json<Vec<ReactionRole>>> {
@@ -91,6 +92,19 @@ async fn create_reaction_roles(
         .await?,
     );
 
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "reaction_roles.create",
+        json!({
+            "channel_id": body.channel_id,
+            "message_id": body.message_id,
+            "count": created.len(),
+        }),
+    )
+    .await;
+
     Ok(Json(created))
 }
 
@@ -99,7 +113,7 @@ async fn create_reaction_roles(
 /// Delete all reaction roles associated with a specific message ID.
 async fn delete_reaction_roles(
     State(state): State<AppState>,
-    _user: AuthUser,
+    admin: GuildAdmin,
     Path((id, mid)): Path<(String, String)>,
 ) -> AppResult<Json<serde_json::Value>> {
     let result = sqlx::query(
@@ -116,6 +130,15 @@ async fn delete_reaction_roles(
         )));
     }
 
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "reaction_roles.delete",
+        json!({ "message_id": mid, "count": result.rows_affected() }),
+    )
+    .await;
+
     Ok(Json(json!({
         "deleted": true,
         "message_id": mid,
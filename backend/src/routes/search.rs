@@ -0,0 +1,70 @@
+use axum::{
+    extract::{Path, Query, State},
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::auth::middleware::GuildAdmin;
+use crate::error::{AppError, AppResult};
+use crate::models::{SearchFilters, SearchKind};
+use crate::services::search;
+use crate::state::AppState;
+
+/// Router nested under /guilds/:id
+pub fn router() -> Router<AppState> {
+    Router::new().route("/{id}/search", get(search_guild))
+}
+
+// ── GET /guilds/:id/search ──────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    pub q: String,
+    /// `transcript`, `ticket` or `message`; all kinds when unset.
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub user_id: Option<String>,
+    pub category: Option<String>,
+    pub closed_by: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// Full-text search over the guild's transcripts, ticket subjects and
+/// ticket messages.
+async fn search_guild(
+    State(state): State<AppState>,
+    _admin: GuildAdmin,
+    Path(id): Path<String>,
+    Query(params): Query<SearchQuery>,
+) -> AppResult<Json<serde_json::Value>> {
+    let page = params.page.unwrap_or(1).max(1);
+    let limit = params.limit.unwrap_or(25).clamp(1, 100);
+    let offset = (page - 1) * limit;
+
+    let kind = params
+        .kind
+        .as_deref()
+        .map(|v| SearchKind::parse(v).ok_or_else(|| AppError::BadRequest(format!("Invalid type '{v}'"))))
+        .transpose()?;
+    let filters = SearchFilters {
+        kind,
+        user_id: params.user_id,
+        category: params.category,
+        closed_by: params.closed_by,
+        from: params.from,
+        to: params.to,
+    };
+
+    let results = search::search(&state.db, &id, &params.q, &filters, limit, offset).await?;
+
+    Ok(Json(json!({
+        "results": results,
+        "page": page,
+        "limit": limit,
+    })))
+}
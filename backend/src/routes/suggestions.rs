@@ -6,9 +6,10 @@ use axum::{
 use serde::Deserialize;
 use serde_json::json;
 
-use crate::auth::middleware::AuthUser;
+use crate::auth::middleware::GuildAdmin;
 use crate::error::{AppError, AppResult};
 use crate::models::Suggestion;
+use crate::services::dashboard_log;
 use crate::state::AppState;
 
 pub fn router() -> Router<AppState> {
@@ -26,7 +27,7 @@ pub fn router() -> Router<AppState> {
 
 async fn list_suggestions(
     State(state): State<AppState>,
-    _user: AuthUser,
+    _admin: GuildAdmin,
     Path(id): Path<String>,
 ) -> AppResult<Json<Vec<Suggestion>>> {
     let suggestions = sqlx::query_as::<_, Suggestion>(
@@ -49,7 +50,7 @@ pub struct CreateSuggestionBody {
 
 async fn create_suggestion(
     State(state): State<AppState>,
-    user: AuthUser,
+    admin: GuildAdmin,
     Path(id): Path<String>,
     Json(body): Json<CreateSuggestionBody>,
 ) -> AppResult<Json<Suggestion>> {
@@ -57,7 +58,7 @@ async fn create_suggestion(
         "INSERT INTO suggestions (guild_id, user_id, message_id, content) VALUES (?, ?, ?, ?)",
     )
     .bind(&id)
-    .bind(&user.id)
+    .bind(&admin.user.id)
     .bind(&body.message_id)
     .bind(&body.content)
     .execute(&state.db)
@@ -69,6 +70,15 @@ async fn create_suggestion(
     .fetch_one(&state.db)
     .await?;
 
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "suggestion.create",
+        json!({ "id": suggestion.id }),
+    )
+    .await;
+
     Ok(Json(suggestion))
 }
 
@@ -81,7 +91,7 @@ pub struct VoteBody {
 
 async fn vote_suggestion(
     State(state): State<AppState>,
-    _user: AuthUser,
+    admin: GuildAdmin,
     Path((id, sid)): Path<(String, i64)>,
     Json(body): Json<VoteBody>,
 ) -> AppResult<Json<Suggestion>> {
@@ -127,6 +137,15 @@ async fn vote_suggestion(
     .fetch_one(&state.db)
     .await?;
 
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "suggestion.vote",
+        json!({ "id": sid, "vote": body.vote }),
+    )
+    .await;
+
     Ok(Json(suggestion))
 }
 
@@ -139,7 +158,7 @@ pub struct StaffActionBody {
 
 async fn approve_suggestion(
     State(state): State<AppState>,
-    user: AuthUser,
+    admin: GuildAdmin,
     Path((id, sid)): Path<(String, i64)>,
     body: Option<Json<StaffActionBody>>,
 ) -> AppResult<Json<Suggestion>> {
@@ -149,7 +168,7 @@ async fn approve_suggestion(
         "UPDATE suggestions SET status = 'approved', staff_id = ?, staff_reason = ? \
          WHERE guild_id = ? AND id = ?",
     )
-    .bind(&user.id)
+    .bind(&admin.user.id)
     .bind(&reason)
     .bind(&id)
     .bind(sid)
@@ -168,6 +187,15 @@ async fn approve_suggestion(
     .fetch_one(&state.db)
     .await?;
 
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "suggestion.approve",
+        json!({ "id": sid, "reason": suggestion.staff_reason }),
+    )
+    .await;
+
     Ok(Json(suggestion))
 }
 
@@ -175,7 +203,7 @@ async fn approve_suggestion(
 
 async fn reject_suggestion(
     State(state): State<AppState>,
-    user: AuthUser,
+    admin: GuildAdmin,
     Path((id, sid)): Path<(String, i64)>,
     body: Option<Json<StaffActionBody>>,
 ) -> AppResult<Json<Suggestion>> {
@@ -185,7 +213,7 @@ async fn reject_suggestion(
         "UPDATE suggestions SET status = 'rejected', staff_id = ?, staff_reason = ? \
          WHERE guild_id = ? AND id = ?",
     )
-    .bind(&user.id)
+    .bind(&admin.user.id)
     .bind(&reason)
     .bind(&id)
     .bind(sid)
@@ -204,5 +232,14 @@ async fn reject_suggestion(
     .fetch_one(&state.db)
     .await?;
 
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "suggestion.reject",
+        json!({ "id": sid, "reason": suggestion.staff_reason }),
+    )
+    .await;
+
     Ok(Json(suggestion))
 }
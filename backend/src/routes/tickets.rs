@@ -6,30 +6,61 @@ use axum::{
 use serde::Deserialize;
 use serde_json::json;
 
-use crate::auth::middleware::AuthUser;
+use crate::auth::middleware::GuildAdmin;
 use crate::error::{AppError, AppResult};
-use crate::models::{Ticket, TicketBlacklist, TicketCategory};
+use crate::models::{
+    CreateTicketNote, RoutingPolicy, SetSlaTarget, SetTicketFormQuestion, SetTicketTag, SlaReport, SlaTarget,
+    Ticket, TicketBlacklist, TicketCategory, TicketDetail, TicketEvent, TicketFormQuestion, TicketMessage,
+    TicketNote, TicketStaffLoad, TicketStatus, TicketTag, TranscriptNames, UpdateTicketNote,
+};
+use crate::services::{
+    dashboard_log, sla, ticket, ticket_form, ticket_inactivity, ticket_message, ticket_note, ticket_routing, ticket_tag,
+};
 use crate::state::AppState;
+use crate::timestamp;
 
 pub fn router() -> Router<AppState> {
     Router::new()
         // Tickets
         .route("/{id}/tickets", get(list_tickets))
+        .route("/{id}/tickets/sla", get(sla_report))
+        .route("/{id}/tickets/sla/targets", get(get_sla_targets).put(set_sla_targets))
         .route("/{id}/tickets/{tid}", get(get_ticket))
+        .route("/{id}/tickets/{tid}/history", get(get_history))
+        .route("/{id}/tickets/{tid}/messages", get(list_messages))
+        .route("/{id}/tickets/{tid}/status", post(update_status))
         .route("/{id}/tickets/{tid}/close", post(close_ticket))
+        .route("/{id}/tickets/{tid}/reopen", post(reopen_ticket))
         .route("/{id}/tickets/{tid}/claim", post(claim_ticket))
         .route("/{id}/tickets/{tid}/priority", put(update_priority))
+        .route("/{id}/tickets/{tid}/auto-close", put(set_auto_close))
+        // Staff notes and tags
+        .route("/{id}/tickets/{tid}/notes", get(list_notes).post(create_note))
+        .route("/{id}/tickets/{tid}/notes/{nid}", put(update_note).delete(delete_note))
+        .route("/{id}/tickets/{tid}/tags", get(list_ticket_tags).post(add_ticket_tag))
+        .route("/{id}/tickets/{tid}/tags/{tag_id}", delete(remove_ticket_tag))
         // Ticket categories
         .route(
             "/{id}/ticket-categories",
             get(list_categories).post(create_category),
         )
         .route("/{id}/ticket-categories/{cid}", delete(delete_category))
+        .route("/{id}/ticket-categories/{cid}/inactivity", put(set_category_inactivity))
+        .route("/{id}/ticket-categories/{cid}/form", get(get_category_form).put(set_category_form))
+        .route("/{id}/ticket-categories/{cid}/routing", put(set_category_routing))
+        .route("/{id}/ticket-categories/{cid}/limits", put(set_category_limits))
+        // Tag vocabulary
+        .route("/{id}/ticket-tags", get(list_tags).post(create_tag))
+        .route("/{id}/ticket-tags/{tag_id}", put(update_tag).delete(delete_tag))
+        // Staff roster for automatic assignment
+        .route("/{id}/ticket-staff", get(list_staff))
+        .route("/{id}/ticket-staff/{uid}", put(set_staff).delete(remove_staff))
         // Ticket blacklist
         .route(
             "/{id}/ticket-blacklist",
             get(list_blacklist).post(add_to_blacklist),
         )
+        .route("/{id}/ticket-blacklist/history", get(blacklist_history))
         .route("/{id}/ticket-blacklist/{bid}", delete(remove_from_blacklist))
 }
 
@@ -40,13 +71,15 @@ pub struct PaginationParams {
     pub page: Option<i64>,
     pub limit: Option<i64>,
     pub status: Option<String>,
+    /// Comma-separated tag names; tickets must carry all of them.
+    pub tags: Option<String>,
 }
 
 // ── GET /guilds/:id/tickets ─────────────────────────────────────────────────
 
 async fn list_tickets(
     State(state): State<AppState>,
-    _user: AuthUser,
+    _admin: GuildAdmin,
     Path(id): Path<String>,
     Query(params): Query<PaginationParams>,
 ) -> AppResult<Json<serde_json::Value>> {
@@ -54,46 +87,11 @@ async fn list_tickets(
     let limit = params.limit.unwrap_or(25).clamp(1, 100);
     let offset = (page - 1) * limit;
 
-    let (tickets, total) = if let Some(ref status) = params.status {
-        let tickets = sqlx::query_as::<_, Ticket>(
-            "SELECT * FROM tickets WHERE guild_id = ? AND status = ? \
-             ORDER BY created_at DESC LIMIT ? OFFSET ?",
-        )
-        .bind(&id)
-        .bind(status)
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(&state.db)
-        .await?;
-
-        let total: (i64,) = sqlx::query_as(
-            "SELECT COUNT(*) FROM tickets WHERE guild_id = ? AND status = ?",
-        )
-        .bind(&id)
-        .bind(status)
-        .fetch_one(&state.db)
-        .await?;
-
-        (tickets, total.0)
-    } else {
-        let tickets = sqlx::query_as::<_, Ticket>(
-            "SELECT * FROM tickets WHERE guild_id = ? \
-             ORDER BY created_at DESC LIMIT ? OFFSET ?",
-        )
-        .bind(&id)
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(&state.db)
-        .await?;
-
-        let total: (i64,) =
-            sqlx::query_as("SELECT COUNT(*) FROM tickets WHERE guild_id = ?")
-                .bind(&id)
-                .fetch_one(&state.db)
-                .await?;
+    let status = params.status.as_deref().map(parse_status).transpose()?;
+    let tags: Vec<String> = params.tags.as_deref().unwrap_or_default().split(',').map(String::from).collect();
 
-        (tickets, total.0)
-    };
+    let tickets = ticket::list_tickets(&state.db, &id, status, &tags, limit, offset).await?;
+    let total = ticket::count_tickets(&state.db, &id, status, &tags).await?;
 
     Ok(Json(json!({
         "tickets": tickets,
@@ -107,19 +105,43 @@ async fn list_tickets(
 
 async fn get_ticket(
     State(state): State<AppState>,
-    _user: AuthUser,
+    _admin: GuildAdmin,
     Path((id, tid)): Path<(String, i64)>,
-) -> AppResult<Json<Ticket>> {
-    let ticket = sqlx::query_as::<_, Ticket>(
-        "SELECT * FROM tickets WHERE guild_id = ? AND id = ?",
-    )
-    .bind(&id)
-    .bind(tid)
-    .fetch_optional(&state.db)
-    .await?
-    .ok_or_else(|| AppError::NotFound(format!("Ticket {tid} not found")))?;
+) -> AppResult<Json<TicketDetail>> {
+    let ticket = ticket::get_guild_ticket(&state.db, &id, tid).await?;
+    let answers = ticket_form::get_answers(&state.db, tid).await?;
+    Ok(Json(TicketDetail { ticket, answers }))
+}
 
-    Ok(Json(ticket))
+// ── GET /guilds/:id/tickets/:tid/history ────────────────────────────────────
+
+async fn get_history(
+    State(state): State<AppState>,
+    _admin: GuildAdmin,
+    Path((id, tid)): Path<(String, i64)>,
+) -> AppResult<Json<Vec<TicketEvent>>> {
+    ticket::get_guild_ticket(&state.db, &id, tid).await?;
+    let events = ticket::get_history(&state.db, tid).await?;
+    Ok(Json(events))
+}
+
+// ── GET /guilds/:id/tickets/:tid/messages ───────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct MessagesQuery {
+    pub include_deleted: Option<bool>,
+}
+
+async fn list_messages(
+    State(state): State<AppState>,
+    _admin: GuildAdmin,
+    Path((id, tid)): Path<(String, i64)>,
+    Query(params): Query<MessagesQuery>,
+) -> AppResult<Json<Vec<TicketMessage>>> {
+    ticket::get_guild_ticket(&state.db, &id, tid).await?;
+    let messages =
+        ticket_message::list(&state.db, tid, params.include_deleted.unwrap_or(false)).await?;
+    Ok(Json(messages))
 }
 
 // ── POST /guilds/:id/tickets/:tid/close ─────────────────────────────────────
@@ -131,78 +153,109 @@ pub struct CloseTicketBody {
 
 async fn close_ticket(
     State(state): State<AppState>,
-    user: AuthUser,
+    admin: GuildAdmin,
     Path((id, tid)): Path<(String, i64)>,
     body: Option<Json<CloseTicketBody>>,
 ) -> AppResult<Json<Ticket>> {
     let closed_by = body
         .and_then(|b| b.closed_by.clone())
-        .unwrap_or_else(|| user.id.clone());
+        .unwrap_or_else(|| admin.user.id.clone());
 
-    let result = sqlx::query(
-        "UPDATE tickets SET status = 'closed', closed_by = ?, closed_at = datetime('now'), \
-         last_activity = datetime('now') WHERE guild_id = ? AND id = ? AND status = 'open'",
+    ticket::get_guild_ticket(&state.db, &id, tid).await?;
+    let (ticket, transcript) =
+        ticket::close_ticket(&state.db, tid, &closed_by, &TranscriptNames::default()).await?;
+
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "ticket.close",
+        json!({ "id": tid, "closed_by": closed_by, "transcript_id": transcript.id }),
     )
-    .bind(&closed_by)
-    .bind(&id)
-    .bind(tid)
-    .execute(&state.db)
-    .await?;
+    .await;
 
-    if result.rows_affected() == 0 {
-        return Err(AppError::NotFound(
-            "Ticket not found or already closed".to_string(),
-        ));
-    }
+    broadcast_status(&state, &ticket);
+
+    Ok(Json(ticket))
+}
+
+// ── POST /guilds/:id/tickets/:tid/reopen ────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct ReopenTicketBody {
+    pub note: Option<String>,
+}
+
+async fn reopen_ticket(
+    State(state): State<AppState>,
+    admin: GuildAdmin,
+    Path((id, tid)): Path<(String, i64)>,
+    body: Option<Json<ReopenTicketBody>>,
+) -> AppResult<Json<Ticket>> {
+    let note = body.and_then(|b| b.note.clone());
 
-    let ticket = sqlx::query_as::<_, Ticket>(
-        "SELECT * FROM tickets WHERE guild_id = ? AND id = ?",
+    let before = ticket::get_guild_ticket(&state.db, &id, tid).await?;
+    let ticket = ticket::reopen_ticket(&state.db, tid, &admin.user.id, note.as_deref()).await?;
+
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "ticket.reopen",
+        json!({ "id": tid, "from": before.status, "note": note }),
     )
-    .bind(&id)
-    .bind(tid)
-    .fetch_one(&state.db)
-    .await?;
+    .await;
 
-    // Broadcast update via WebSocket
-    let _ = state.ws_tx.send(crate::state::WsEvent::TicketUpdate {
-        guild_id: id,
-        ticket_id: tid,
-        status: "closed".to_string(),
-    });
+    broadcast_status(&state, &ticket);
 
     Ok(Json(ticket))
 }
 
-// ── POST /guilds/:id/tickets/:tid/claim ─────────────────────────────────────
+// ── POST /guilds/:id/tickets/:tid/status ────────────────────────────────────
 
-async fn claim_ticket(
+#[derive(Debug, Deserialize)]
+pub struct UpdateStatusBody {
+    pub status: String,
+    pub note: Option<String>,
+}
+
+async fn update_status(
     State(state): State<AppState>,
-    user: AuthUser,
+    admin: GuildAdmin,
     Path((id, tid)): Path<(String, i64)>,
+    Json(body): Json<UpdateStatusBody>,
 ) -> AppResult<Json<Ticket>> {
-    let result = sqlx::query(
-        "UPDATE tickets SET claimed_by = ?, last_activity = datetime('now') \
-         WHERE guild_id = ? AND id = ? AND claimed_by IS NULL",
-    )
-    .bind(&user.id)
-    .bind(&id)
-    .bind(tid)
-    .execute(&state.db)
-    .await?;
+    let next = parse_status(&body.status)?;
 
-    if result.rows_affected() == 0 {
-        return Err(AppError::BadRequest(
-            "Ticket not found or already claimed".to_string(),
-        ));
-    }
+    let before = ticket::get_guild_ticket(&state.db, &id, tid).await?;
+    let ticket =
+        ticket::transition(&state.db, tid, next, &admin.user.id, body.note.as_deref()).await?;
 
-    let ticket = sqlx::query_as::<_, Ticket>(
-        "SELECT * FROM tickets WHERE guild_id = ? AND id = ?",
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "ticket.status",
+        json!({ "id": tid, "from": before.status, "to": ticket.status, "note": body.note }),
     )
-    .bind(&id)
-    .bind(tid)
-    .fetch_one(&state.db)
-    .await?;
+    .await;
+
+    broadcast_status(&state, &ticket);
+
+    Ok(Json(ticket))
+}
+
+// ── POST /guilds/:id/tickets/:tid/claim ─────────────────────────────────────
+
+async fn claim_ticket(
+    State(state): State<AppState>,
+    admin: GuildAdmin,
+    Path((id, tid)): Path<(String, i64)>,
+) -> AppResult<Json<Ticket>> {
+    ticket::get_guild_ticket(&state.db, &id, tid).await?;
+    let ticket = ticket::claim_ticket(&state.db, tid, &admin.user.id).await?;
+
+    dashboard_log::record(&state, &admin, &id, "ticket.claim", json!({ "id": tid })).await;
 
     Ok(Json(ticket))
 }
@@ -216,49 +269,296 @@ pub struct UpdatePriorityBody {
 
 async fn update_priority(
     State(state): State<AppState>,
-    _user: AuthUser,
+    admin: GuildAdmin,
     Path((id, tid)): Path<(String, i64)>,
     Json(body): Json<UpdatePriorityBody>,
 ) -> AppResult<Json<Ticket>> {
-    let valid = ["low", "normal", "high", "urgent"];
-    if !valid.contains(&body.priority.as_str()) {
-        return Err(AppError::BadRequest(format!(
-            "Invalid priority '{}'. Must be one of: {}",
-            body.priority,
-            valid.join(", ")
-        )));
-    }
+    ticket::get_guild_ticket(&state.db, &id, tid).await?;
+    let ticket = ticket::update_priority(&state.db, tid, &body.priority, &admin.user.id).await?;
 
-    let result = sqlx::query(
-        "UPDATE tickets SET priority = ?, last_activity = datetime('now') \
-         WHERE guild_id = ? AND id = ?",
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "ticket.priority",
+        json!({ "id": tid, "priority": body.priority }),
     )
-    .bind(&body.priority)
-    .bind(&id)
-    .bind(tid)
-    .execute(&state.db)
-    .await?;
+    .await;
 
-    if result.rows_affected() == 0 {
-        return Err(AppError::NotFound(format!("Ticket {tid} not found")));
-    }
+    Ok(Json(ticket))
+}
+
+// ── PUT /guilds/:id/tickets/:tid/auto-close ─────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct AutoCloseBody {
+    pub enabled: bool,
+}
+
+/// Opt a ticket in or out of inactivity warnings and auto-close.
+async fn set_auto_close(
+    State(state): State<AppState>,
+    admin: GuildAdmin,
+    Path((id, tid)): Path<(String, i64)>,
+    Json(body): Json<AutoCloseBody>,
+) -> AppResult<Json<Ticket>> {
+    let ticket = ticket_inactivity::set_auto_close(&state.db, &id, tid, body.enabled, &admin.user.id).await?;
 
-    let ticket = sqlx::query_as::<_, Ticket>(
-        "SELECT * FROM tickets WHERE guild_id = ? AND id = ?",
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "ticket.auto_close",
+        json!({ "id": tid, "enabled": body.enabled }),
     )
-    .bind(&id)
-    .bind(tid)
-    .fetch_one(&state.db)
-    .await?;
+    .await;
 
     Ok(Json(ticket))
 }
 
+// ── GET /guilds/:id/tickets/:tid/notes ──────────────────────────────────────
+
+/// Staff-only notes, pinned first.
+async fn list_notes(
+    State(state): State<AppState>,
+    _admin: GuildAdmin,
+    Path((id, tid)): Path<(String, i64)>,
+) -> AppResult<Json<Vec<TicketNote>>> {
+    ticket::get_guild_ticket(&state.db, &id, tid).await?;
+    let notes = ticket_note::list(&state.db, tid).await?;
+    Ok(Json(notes))
+}
+
+// ── POST /guilds/:id/tickets/:tid/notes ─────────────────────────────────────
+
+async fn create_note(
+    State(state): State<AppState>,
+    admin: GuildAdmin,
+    Path((id, tid)): Path<(String, i64)>,
+    Json(body): Json<CreateTicketNote>,
+) -> AppResult<Json<TicketNote>> {
+    let ticket = ticket::get_guild_ticket(&state.db, &id, tid).await?;
+    let note = ticket_note::create(&state.db, &ticket, &admin.user.id, body).await?;
+
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "ticket.note_create",
+        json!({ "ticket_id": tid, "id": note.id }),
+    )
+    .await;
+
+    Ok(Json(note))
+}
+
+// ── PUT /guilds/:id/tickets/:tid/notes/:nid ─────────────────────────────────
+
+async fn update_note(
+    State(state): State<AppState>,
+    admin: GuildAdmin,
+    Path((id, tid, nid)): Path<(String, i64, i64)>,
+    Json(body): Json<UpdateTicketNote>,
+) -> AppResult<Json<TicketNote>> {
+    ticket::get_guild_ticket(&state.db, &id, tid).await?;
+    let note = ticket_note::update(&state.db, tid, nid, body).await?;
+
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "ticket.note_update",
+        json!({ "ticket_id": tid, "id": nid }),
+    )
+    .await;
+
+    Ok(Json(note))
+}
+
+// ── DELETE /guilds/:id/tickets/:tid/notes/:nid ──────────────────────────────
+
+async fn delete_note(
+    State(state): State<AppState>,
+    admin: GuildAdmin,
+    Path((id, tid, nid)): Path<(String, i64, i64)>,
+) -> AppResult<Json<serde_json::Value>> {
+    ticket::get_guild_ticket(&state.db, &id, tid).await?;
+    ticket_note::delete(&state.db, tid, nid).await?;
+
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "ticket.note_delete",
+        json!({ "ticket_id": tid, "id": nid }),
+    )
+    .await;
+
+    Ok(Json(json!({ "deleted": true, "id": nid })))
+}
+
+// ── GET /guilds/:id/tickets/:tid/tags ───────────────────────────────────────
+
+async fn list_ticket_tags(
+    State(state): State<AppState>,
+    _admin: GuildAdmin,
+    Path((id, tid)): Path<(String, i64)>,
+) -> AppResult<Json<Vec<TicketTag>>> {
+    ticket::get_guild_ticket(&state.db, &id, tid).await?;
+    let tags = ticket_tag::for_ticket(&state.db, tid).await?;
+    Ok(Json(tags))
+}
+
+// ── POST /guilds/:id/tickets/:tid/tags ──────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct AddTicketTagBody {
+    pub tag_id: i64,
+}
+
+/// Attach a tag; returns the ticket's tags.
+async fn add_ticket_tag(
+    State(state): State<AppState>,
+    admin: GuildAdmin,
+    Path((id, tid)): Path<(String, i64)>,
+    Json(body): Json<AddTicketTagBody>,
+) -> AppResult<Json<Vec<TicketTag>>> {
+    ticket::get_guild_ticket(&state.db, &id, tid).await?;
+    ticket_tag::attach(&state.db, &id, tid, body.tag_id, &admin.user.id).await?;
+
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "ticket.tag_add",
+        json!({ "ticket_id": tid, "tag_id": body.tag_id }),
+    )
+    .await;
+
+    let tags = ticket_tag::for_ticket(&state.db, tid).await?;
+    Ok(Json(tags))
+}
+
+// ── DELETE /guilds/:id/tickets/:tid/tags/:tag_id ────────────────────────────
+
+async fn remove_ticket_tag(
+    State(state): State<AppState>,
+    admin: GuildAdmin,
+    Path((id, tid, tag_id)): Path<(String, i64, i64)>,
+) -> AppResult<Json<serde_json::Value>> {
+    ticket::get_guild_ticket(&state.db, &id, tid).await?;
+    ticket_tag::detach(&state.db, tid, tag_id).await?;
+
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "ticket.tag_remove",
+        json!({ "ticket_id": tid, "tag_id": tag_id }),
+    )
+    .await;
+
+    Ok(Json(json!({ "deleted": true, "id": tag_id })))
+}
+
+// ── GET /guilds/:id/tickets/sla ─────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct SlaReportQuery {
+    /// Earliest ticket open date/time to include (inclusive).
+    pub from: Option<String>,
+    /// Latest ticket open date/time to include (inclusive; a bare date
+    /// means the whole day).
+    pub to: Option<String>,
+    pub priority: Option<String>,
+}
+
+/// SLA results per priority and every missed deadline.
+async fn sla_report(
+    State(state): State<AppState>,
+    _admin: GuildAdmin,
+    Path(id): Path<String>,
+    Query(params): Query<SlaReportQuery>,
+) -> AppResult<Json<SlaReport>> {
+    let (from, to) = timestamp::parse_range(params.from.as_deref(), params.to.as_deref())?;
+
+    let report = sla::report(&state.db, &id, from.as_deref(), to.as_deref(), params.priority.as_deref()).await?;
+    Ok(Json(report))
+}
+
+// ── GET /guilds/:id/tickets/sla/targets ─────────────────────────────────────
+
+async fn get_sla_targets(
+    State(state): State<AppState>,
+    _admin: GuildAdmin,
+    Path(id): Path<String>,
+) -> AppResult<Json<Vec<SlaTarget>>> {
+    let targets = sla::get_targets(&state.db, &id).await?;
+    Ok(Json(targets))
+}
+
+// ── PUT /guilds/:id/tickets/sla/targets ─────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct SetSlaTargetsBody {
+    pub targets: Vec<SetSlaTarget>,
+}
+
+async fn set_sla_targets(
+    State(state): State<AppState>,
+    admin: GuildAdmin,
+    Path(id): Path<String>,
+    Json(body): Json<SetSlaTargetsBody>,
+) -> AppResult<Json<Vec<SlaTarget>>> {
+    let targets = sla::set_targets(&state.db, &id, &body.targets).await?;
+
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "ticket.sla_targets",
+        json!({ "targets": targets }),
+    )
+    .await;
+
+    Ok(Json(targets))
+}
+
+fn parse_status(value: &str) -> AppResult<TicketStatus> {
+    TicketStatus::parse(value).ok_or_else(|| {
+        let valid: Vec<&str> = TicketStatus::ALL.iter().map(|s| s.as_str()).collect();
+        AppError::BadRequest(format!(
+            "Invalid status '{value}'. Must be one of: {}",
+            valid.join(", ")
+        ))
+    })
+}
+
+/// Tell dashboards about tickets handed to another staff member and wake
+/// the bot for the queued `ticket_reassigned` actions.
+pub(crate) fn broadcast_reassigned(state: &AppState, tickets: &[Ticket]) {
+    if tickets.is_empty() {
+        return;
+    }
+    for ticket in tickets {
+        broadcast_status(state, ticket);
+    }
+    state.bot_actions.notify_waiters();
+}
+
+fn broadcast_status(state: &AppState, ticket: &Ticket) {
+    let _ = state.ws_tx.send(crate::state::WsEvent::TicketUpdate {
+        guild_id: ticket.guild_id.clone(),
+        ticket_id: ticket.id,
+        status: ticket.status.as_str().to_string(),
+    });
+}
+
 // ── GET /guilds/:id/ticket-categories ───────────────────────────────────────
 
 async fn list_categories(
     State(state): State<AppState>,
-    _user: AuthUser,
+    _admin: GuildAdmin,
     Path(id): Path<String>,
 ) -> AppResult<Json<Vec<TicketCategory>>> {
     let categories = sqlx::query_as::<_, TicketCategory>(
@@ -282,7 +582,7 @@ pub struct CreateCategoryBody {
 
 async fn create_category(
     State(state): State<AppState>,
-    _user: AuthUser,
+    admin: GuildAdmin,
     Path(id): Path<String>,
     Json(body): Json<CreateCategoryBody>,
 ) -> AppResult<Json<TicketCategory>> {
@@ -308,6 +608,15 @@ async fn create_category(
     .fetch_one(&state.db)
     .await?;
 
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "ticket_category.create",
+        json!({ "id": category.id, "name": category.name }),
+    )
+    .await;
+
     Ok(Json(category))
 }
 
@@ -315,7 +624,7 @@ async fn create_category(
 
 async fn delete_category(
     State(state): State<AppState>,
-    _user: AuthUser,
+    admin: GuildAdmin,
     Path((id, cid)): Path<(String, i64)>,
 ) -> AppResult<Json<serde_json::Value>> {
     let result = sqlx::query(
@@ -330,85 +639,431 @@ async fn delete_category(
         return Err(AppError::NotFound(format!("Category {cid} not found")));
     }
 
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "ticket_category.delete",
+        json!({ "id": cid }),
+    )
+    .await;
+
     Ok(Json(json!({ "deleted": true, "id": cid })))
 }
 
+// ── PUT /guilds/:id/ticket-categories/:cid/inactivity ───────────────────────
+
+/// Leave a field out (or null) to use the guild's rule; 0 turns it off.
+#[derive(Debug, Deserialize)]
+pub struct CategoryInactivityBody {
+    pub warn_hours: Option<i64>,
+    pub close_hours: Option<i64>,
+}
+
+async fn set_category_inactivity(
+    State(state): State<AppState>,
+    admin: GuildAdmin,
+    Path((id, cid)): Path<(String, i64)>,
+    Json(body): Json<CategoryInactivityBody>,
+) -> AppResult<Json<TicketCategory>> {
+    let category =
+        ticket_inactivity::set_category_rule(&state.db, &id, cid, body.warn_hours, body.close_hours).await?;
+
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "ticket_category.inactivity",
+        json!({ "id": cid, "warn_hours": body.warn_hours, "close_hours": body.close_hours }),
+    )
+    .await;
+
+    Ok(Json(category))
+}
+
+// ── GET /guilds/:id/ticket-categories/:cid/form ─────────────────────────────
+
+async fn get_category_form(
+    State(state): State<AppState>,
+    _admin: GuildAdmin,
+    Path((id, cid)): Path<(String, i64)>,
+) -> AppResult<Json<Vec<TicketFormQuestion>>> {
+    let form = ticket_form::get_form(&state.db, &id, cid).await?;
+    Ok(Json(form))
+}
+
+// ── PUT /guilds/:id/ticket-categories/:cid/form ─────────────────────────────
+
+/// The full, ordered question list; an empty list removes the form.
+#[derive(Debug, Deserialize)]
+pub struct SetCategoryFormBody {
+    pub questions: Vec<SetTicketFormQuestion>,
+}
+
+async fn set_category_form(
+    State(state): State<AppState>,
+    admin: GuildAdmin,
+    Path((id, cid)): Path<(String, i64)>,
+    Json(body): Json<SetCategoryFormBody>,
+) -> AppResult<Json<Vec<TicketFormQuestion>>> {
+    let form = ticket_form::set_form(&state.db, &id, cid, &body.questions).await?;
+
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "ticket_category.form",
+        json!({ "id": cid, "questions": form.len() }),
+    )
+    .await;
+
+    Ok(Json(form))
+}
+
+// ── PUT /guilds/:id/ticket-categories/:cid/routing ──────────────────────────
+
+/// `routing` null (or left out) uses the guild's policy.
+#[derive(Debug, Deserialize)]
+pub struct CategoryRoutingBody {
+    pub routing: Option<String>,
+}
+
+async fn set_category_routing(
+    State(state): State<AppState>,
+    admin: GuildAdmin,
+    Path((id, cid)): Path<(String, i64)>,
+    Json(body): Json<CategoryRoutingBody>,
+) -> AppResult<Json<TicketCategory>> {
+    let routing = body
+        .routing
+        .as_deref()
+        .map(|v| RoutingPolicy::parse(v).ok_or_else(|| AppError::BadRequest(format!("Invalid routing '{v}'"))))
+        .transpose()?;
+    let category = ticket_routing::set_category_routing(&state.db, &id, cid, routing).await?;
+
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "ticket_category.routing",
+        json!({ "id": cid, "routing": body.routing }),
+    )
+    .await;
+
+    Ok(Json(category))
+}
+
+// ── PUT /guilds/:id/ticket-categories/:cid/limits ───────────────────────────
+
+/// Leave a field out (or null) to turn that rule off for the category.
+#[derive(Debug, Deserialize)]
+pub struct CategoryLimitsBody {
+    pub max_open: Option<i64>,
+    pub required_role: Option<String>,
+}
+
+async fn set_category_limits(
+    State(state): State<AppState>,
+    admin: GuildAdmin,
+    Path((id, cid)): Path<(String, i64)>,
+    Json(body): Json<CategoryLimitsBody>,
+) -> AppResult<Json<TicketCategory>> {
+    let category =
+        ticket::set_category_limits(&state.db, &id, cid, body.max_open, body.required_role.as_deref()).await?;
+
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "ticket_category.limits",
+        json!({ "id": cid, "max_open": body.max_open, "required_role": body.required_role }),
+    )
+    .await;
+
+    Ok(Json(category))
+}
+
+// ── GET /guilds/:id/ticket-tags ─────────────────────────────────────────────
+
+async fn list_tags(
+    State(state): State<AppState>,
+    _admin: GuildAdmin,
+    Path(id): Path<String>,
+) -> AppResult<Json<Vec<TicketTag>>> {
+    let tags = ticket_tag::list(&state.db, &id).await?;
+    Ok(Json(tags))
+}
+
+// ── POST /guilds/:id/ticket-tags ────────────────────────────────────────────
+
+async fn create_tag(
+    State(state): State<AppState>,
+    admin: GuildAdmin,
+    Path(id): Path<String>,
+    Json(body): Json<SetTicketTag>,
+) -> AppResult<Json<TicketTag>> {
+    let tag = ticket_tag::create(&state.db, &id, &body).await?;
+
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "ticket_tag.create",
+        json!({ "id": tag.id, "name": tag.name }),
+    )
+    .await;
+
+    Ok(Json(tag))
+}
+
+// ── PUT /guilds/:id/ticket-tags/:tag_id ─────────────────────────────────────
+
+async fn update_tag(
+    State(state): State<AppState>,
+    admin: GuildAdmin,
+    Path((id, tag_id)): Path<(String, i64)>,
+    Json(body): Json<SetTicketTag>,
+) -> AppResult<Json<TicketTag>> {
+    let tag = ticket_tag::update(&state.db, &id, tag_id, &body).await?;
+
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "ticket_tag.update",
+        json!({ "id": tag_id, "name": tag.name, "color": tag.color }),
+    )
+    .await;
+
+    Ok(Json(tag))
+}
+
+// ── DELETE /guilds/:id/ticket-tags/:tag_id ──────────────────────────────────
+
+async fn delete_tag(
+    State(state): State<AppState>,
+    admin: GuildAdmin,
+    Path((id, tag_id)): Path<(String, i64)>,
+) -> AppResult<Json<serde_json::Value>> {
+    ticket_tag::delete(&state.db, &id, tag_id).await?;
+
+    dashboard_log::record(&state, &admin, &id, "ticket_tag.delete", json!({ "id": tag_id })).await;
+
+    Ok(Json(json!({ "deleted": true, "id": tag_id })))
+}
+
+// ── GET /guilds/:id/ticket-staff ────────────────────────────────────────────
+
+async fn list_staff(
+    State(state): State<AppState>,
+    _admin: GuildAdmin,
+    Path(id): Path<String>,
+) -> AppResult<Json<Vec<TicketStaffLoad>>> {
+    let staff = ticket_routing::list_staff(&state.db, &id).await?;
+    Ok(Json(staff))
+}
+
+// ── PUT /guilds/:id/ticket-staff/:uid ───────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct SetStaffBody {
+    pub available: bool,
+}
+
+/// Add a roster member or toggle their availability. Going unavailable
+/// hands their routed tickets to other staff.
+async fn set_staff(
+    State(state): State<AppState>,
+    admin: GuildAdmin,
+    Path((id, uid)): Path<(String, String)>,
+    Json(body): Json<SetStaffBody>,
+) -> AppResult<Json<serde_json::Value>> {
+    let (staff, reassigned) = ticket_routing::set_staff(&state.db, &id, &uid, body.available).await?;
+    broadcast_reassigned(&state, &reassigned);
+
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "ticket_staff.set",
+        json!({ "user_id": uid, "available": body.available, "reassigned": reassigned.len() }),
+    )
+    .await;
+
+    Ok(Json(json!({ "staff": staff, "reassigned": reassigned })))
+}
+
+// ── DELETE /guilds/:id/ticket-staff/:uid ────────────────────────────────────
+
+async fn remove_staff(
+    State(state): State<AppState>,
+    admin: GuildAdmin,
+    Path((id, uid)): Path<(String, String)>,
+) -> AppResult<Json<serde_json::Value>> {
+    let reassigned = ticket_routing::remove_staff(&state.db, &id, &uid).await?;
+    broadcast_reassigned(&state, &reassigned);
+
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "ticket_staff.remove",
+        json!({ "user_id": uid, "reassigned": reassigned.len() }),
+    )
+    .await;
+
+    Ok(Json(json!({ "deleted": true, "id": uid, "reassigned": reassigned })))
+}
+
 // ── GET /guilds/:id/ticket-blacklist ────────────────────────────────────────
 
+#[derive(Debug, Deserialize)]
+pub struct BlacklistQuery {
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+    /// Matches user IDs, the adding moderator, or the reason.
+    pub search: Option<String>,
+}
+
 async fn list_blacklist(
     State(state): State<AppState>,
-    _user: AuthUser,
+    _admin: GuildAdmin,
     Path(id): Path<String>,
-) -> AppResult<Json<Vec<TicketBlacklist>>> {
-    let list = sqlx::query_as::<_, TicketBlacklist>(
-        "SELECT * FROM ticket_blacklists WHERE guild_id = ? ORDER BY created_at DESC",
-    )
-    .bind(&id)
-    .fetch_all(&state.db)
-    .await?;
+    Query(params): Query<BlacklistQuery>,
+) -> AppResult<Json<serde_json::Value>> {
+    Ok(Json(blacklist_page(&state, &id, &params).await?))
+}
+
+/// One page of a guild's active blacklist entries, for this route and the
+/// bot's.
+pub(crate) async fn blacklist_page(
+    state: &AppState,
+    guild_id: &str,
+    params: &BlacklistQuery,
+) -> AppResult<serde_json::Value> {
+    let page = params.page.unwrap_or(1).max(1);
+    let limit = params.limit.unwrap_or(25).clamp(1, 100);
+    let offset = (page - 1) * limit;
+    let search = params.search.as_deref().map(str::trim).filter(|s| !s.is_empty());
 
-    Ok(Json(list))
+    let entries = ticket::list_blacklist(&state.db, guild_id, search, limit, offset).await?;
+    let total = ticket::count_blacklist(&state.db, guild_id, search).await?;
+
+    Ok(json!({
+        "entries": entries,
+        "total": total,
+        "page": page,
+        "limit": limit,
+    }))
 }
 
 // ── POST /guilds/:id/ticket-blacklist ───────────────────────────────────────
 
+/// Leave `expires_at` out for a permanent entry.
 #[derive(Debug, Deserialize)]
 pub struct AddBlacklistBody {
     pub user_id: String,
     pub reason: Option<String>,
+    pub expires_at: Option<String>,
 }
 
 async fn add_to_blacklist(
     State(state): State<AppState>,
-    user: AuthUser,
+    admin: GuildAdmin,
     Path(id): Path<String>,
     Json(body): Json<AddBlacklistBody>,
 ) -> AppResult<Json<TicketBlacklist>> {
-    sqlx::query(
-        "INSERT INTO ticket_blacklists (guild_id, user_id, reason, added_by) VALUES (?, ?, ?, ?)",
+    let expires_at = parse_blacklist_expiry(body.expires_at.as_deref())?;
+    let entry = ticket::add_blacklist(
+        &state.db,
+        &id,
+        &body.user_id,
+        body.reason.as_deref(),
+        expires_at.as_deref(),
+        &admin.user.id,
     )
-    .bind(&id)
-    .bind(&body.user_id)
-    .bind(&body.reason)
-    .bind(&user.id)
-    .execute(&state.db)
-    .await
-    .map_err(|e| match e {
-        sqlx::Error::Database(ref db_err) if db_err.message().contains("UNIQUE") => {
-            AppError::BadRequest("User is already blacklisted".to_string())
-        }
-        other => AppError::Database(other),
-    })?;
+    .await?;
 
-    let entry = sqlx::query_as::<_, TicketBlacklist>(
-        "SELECT * FROM ticket_blacklists WHERE id = last_insert_rowid()",
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "ticket_blacklist.add",
+        json!({ "id": entry.id, "user_id": entry.user_id, "reason": entry.reason, "expires_at": entry.expires_at }),
     )
-    .fetch_one(&state.db)
-    .await?;
+    .await;
 
     Ok(Json(entry))
 }
 
+/// Normalise a blacklist `expires_at`, which must be in the future. A bare
+/// date lasts through the end of that day.
+pub(crate) fn parse_blacklist_expiry(value: Option<&str>) -> AppResult<Option<String>> {
+    let expires_at = value
+        .map(|v| {
+            timestamp::normalize(v, "23:59:59")
+                .ok_or_else(|| AppError::BadRequest(format!("Invalid expires_at '{v}'")))
+        })
+        .transpose()?;
+    if expires_at.as_deref().and_then(timestamp::parse).is_some_and(|at| at <= timestamp::now()) {
+        return Err(AppError::BadRequest("expires_at must be in the future".to_string()));
+    }
+    Ok(expires_at)
+}
+
+// ── GET /guilds/:id/ticket-blacklist/history ────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct BlacklistHistoryQuery {
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+    pub user_id: Option<String>,
+}
+
+async fn blacklist_history(
+    State(state): State<AppState>,
+    _admin: GuildAdmin,
+    Path(id): Path<String>,
+    Query(params): Query<BlacklistHistoryQuery>,
+) -> AppResult<Json<serde_json::Value>> {
+    let page = params.page.unwrap_or(1).max(1);
+    let limit = params.limit.unwrap_or(25).clamp(1, 100);
+    let offset = (page - 1) * limit;
+
+    let (events, total) =
+        ticket::blacklist_history(&state.db, &id, params.user_id.as_deref(), limit, offset).await?;
+
+    Ok(Json(json!({
+        "events": events,
+        "total": total,
+        "page": page,
+        "limit": limit,
+    })))
+}
+
 // ── DELETE /guilds/:id/ticket-blacklist/:bid ────────────────────────────────
 
+#[derive(Debug, Deserialize)]
+pub struct RemoveBlacklistQuery {
+    pub reason: Option<String>,
+}
+
 async fn remove_from_blacklist(
     State(state): State<AppState>,
-    _user: AuthUser,
+    admin: GuildAdmin,
     Path((id, bid)): Path<(String, i64)>,
+    Query(params): Query<RemoveBlacklistQuery>,
 ) -> AppResult<Json<serde_json::Value>> {
-    let result = sqlx::query(
-        "DELETE FROM ticket_blacklists WHERE guild_id = ? AND id = ?",
-    )
-    .bind(&id)
-    .bind(bid)
-    .execute(&state.db)
-    .await?;
+    let entry = ticket::remove_blacklist(&state.db, &id, bid, &admin.user.id, params.reason.as_deref()).await?;
 
-    if result.rows_affected() == 0 {
-        return Err(AppError::NotFound(format!(
-            "Blacklist entry {bid} not found"
-        )));
-    }
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "ticket_blacklist.remove",
+        json!({ "id": bid, "user_id": entry.user_id, "reason": params.reason }),
+    )
+    .await;
 
     Ok(Json(json!({ "deleted": true, "id": bid })))
 }
@@ -1,21 +1,32 @@
 use axum::{
     extract::{Path, Query, State},
-    http::{header, StatusCode},
+    http::{header, HeaderMap},
     response::{Html, IntoResponse, Response},
-    routing::get,
+    routing::{delete, get},
     Json, Router,
 };
 use serde::Deserialize;
 use serde_json::json;
 
-use crate::auth::middleware::AuthUser;
+use crate::auth::discord_oauth;
+use crate::auth::middleware::{can_manage_guild, AuthUser, GuildAdmin};
 use crate::error::{AppError, AppResult};
-use crate::models::Transcript;
+use crate::models::{Transcript, TranscriptShareLink, TranscriptTheme};
+use crate::services::transcript_export::{self, ExportFormat};
+use crate::services::{dashboard_log, transcript, transcript_share};
+use crate::timestamp;
 use crate::state::AppState;
 
 /// Router nested under /guilds/:id
 pub fn guild_router() -> Router<AppState> {
-    Router::new().route("/{id}/transcripts", get(list_transcripts))
+    Router::new()
+        .route("/{id}/transcripts", get(list_transcripts))
+        .route("/{id}/transcripts/export", get(export_transcripts))
+        .route(
+            "/{id}/transcripts/{tid}/share-links",
+            get(list_share_links).post(create_share_link),
+        )
+        .route("/{id}/transcripts/{tid}/share-links/{lid}", delete(revoke_share_link))
 }
 
 // ── GET /guilds/:id/transcripts ─────────────────────────────────────────────
@@ -28,7 +39,7 @@ pub struct TranscriptListQuery {
 
 async fn list_transcripts(
     State(state): State<AppState>,
-    _user: AuthUser,
+    _admin: GuildAdmin,
     Path(id): Path<String>,
     Query(params): Query<TranscriptListQuery>,
 ) -> AppResult<Json<serde_json::Value>> {
@@ -81,24 +92,278 @@ pub struct TranscriptSummary {
     pub created_at: String,
 }
 
+// ── GET /guilds/:id/transcripts/export ──────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct BulkExportQuery {
+    /// Earliest close date/time to include (inclusive).
+    pub from: Option<String>,
+    /// Latest close date/time to include (inclusive; a bare date means the
+    /// whole day).
+    pub to: Option<String>,
+    pub format: Option<String>,
+}
+
+/// Download a ZIP of the guild's transcripts closed within a date range.
+async fn export_transcripts(
+    State(state): State<AppState>,
+    _admin: GuildAdmin,
+    Path(id): Path<String>,
+    Query(params): Query<BulkExportQuery>,
+) -> AppResult<Response> {
+    let format = match params.format.as_deref() {
+        Some(value) => parse_format(value)?,
+        None => ExportFormat::Html,
+    };
+    let (from, to) = timestamp::parse_range(params.from.as_deref(), params.to.as_deref())?;
+
+    let transcripts = sqlx::query_as::<_, Transcript>(
+        "SELECT * FROM transcripts WHERE guild_id = ? \
+         AND (? IS NULL OR created_at >= ?) AND (? IS NULL OR created_at <= ?) \
+         ORDER BY created_at ASC LIMIT ?",
+    )
+    .bind(&id)
+    .bind(&from)
+    .bind(&from)
+    .bind(&to)
+    .bind(&to)
+    .bind(transcript_export::MAX_BULK_EXPORT + 1)
+    .fetch_all(&state.db)
+    .await?;
+
+    if transcripts.len() as i64 > transcript_export::MAX_BULK_EXPORT {
+        return Err(AppError::BadRequest(format!(
+            "More than {} transcripts in range; narrow the dates",
+            transcript_export::MAX_BULK_EXPORT
+        )));
+    }
+
+    let zip = transcript_export::export_zip(&state.db, &transcripts, format).await?;
+    let file_name = format!("transcripts-{id}.zip");
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{file_name}\"")),
+        ],
+        zip,
+    )
+        .into_response())
+}
+
+// ── GET /guilds/:id/transcripts/:tid/share-links ────────────────────────────
+
+async fn list_share_links(
+    State(state): State<AppState>,
+    _admin: GuildAdmin,
+    Path((id, tid)): Path<(String, String)>,
+) -> AppResult<Json<Vec<TranscriptShareLink>>> {
+    guild_transcript(&state, &id, &tid).await?;
+    let links = transcript_share::list(&state.db, &tid).await?;
+    Ok(Json(links))
+}
+
+// ── POST /guilds/:id/transcripts/:tid/share-links ───────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct CreateShareLinkBody {
+    /// Defaults to 72 hours; at most 30 days.
+    pub expires_in_hours: Option<i64>,
+}
+
+async fn create_share_link(
+    State(state): State<AppState>,
+    admin: GuildAdmin,
+    Path((id, tid)): Path<(String, String)>,
+    body: Option<Json<CreateShareLinkBody>>,
+) -> AppResult<Json<serde_json::Value>> {
+    let transcript = guild_transcript(&state, &id, &tid).await?;
+    let expires_in_hours = body.and_then(|b| b.expires_in_hours);
+
+    let (link, token) = transcript_share::create(
+        &state.db,
+        &state.config.session_secret,
+        &transcript,
+        &admin.user.id,
+        expires_in_hours,
+    )
+    .await?;
+
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "transcript.share",
+        json!({ "transcript_id": tid, "link_id": link.id, "expires_at": link.expires_at }),
+    )
+    .await;
+
+    Ok(Json(share_link_response(&state, link, token)))
+}
+
+// ── DELETE /guilds/:id/transcripts/:tid/share-links/:lid ────────────────────
+
+async fn revoke_share_link(
+    State(state): State<AppState>,
+    admin: GuildAdmin,
+    Path((id, tid, lid)): Path<(String, String, String)>,
+) -> AppResult<Json<TranscriptShareLink>> {
+    guild_transcript(&state, &id, &tid).await?;
+    let link = transcript_share::revoke(&state.db, &tid, &lid, &admin.user.id).await?;
+
+    dashboard_log::record(
+        &state,
+        &admin,
+        &id,
+        "transcript.unshare",
+        json!({ "transcript_id": tid, "link_id": lid }),
+    )
+    .await;
+
+    Ok(Json(link))
+}
+
+/// The link plus its token and the public URL to hand out.
+pub fn share_link_response(state: &AppState, link: TranscriptShareLink, token: String) -> serde_json::Value {
+    let url = format!("{}/transcript/shared/{token}", state.config.web_url);
+    json!({ "link": link, "token": token, "url": url })
+}
+
 // ── GET /transcript/:id ─────────────────────────────────────────────────────
 
-/// Serve the transcript HTML for viewing. Requires authentication.
+#[derive(Debug, Deserialize)]
+pub struct ViewTranscriptQuery {
+    /// "html" (default), "json", "md", "txt" or "pdf". Overrides `Accept`.
+    pub format: Option<String>,
+    /// Render HTML with this theme instead of the stored one.
+    pub theme: Option<String>,
+}
+
+/// Serve a transcript for viewing or download to its ticket's owner or the
+/// guild's staff (managers and the ticket support role).
+///
+/// The format comes from `?format=`, else the `Accept` header, else HTML.
+/// Non-HTML formats are sent as attachments.
 pub async fn view_transcript(
     State(state): State<AppState>,
     user: AuthUser,
     Path(id): Path<String>,
+    Query(params): Query<ViewTranscriptQuery>,
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    let transcript = transcript::get_transcript(&state.db, &id).await?;
+    // The owner gets the transcript as-is; staff also see internal notes
+    let for_staff = user.id != transcript.user_id;
+    if for_staff && !is_staff(&state, &user, &transcript).await? {
+        return Err(AppError::Forbidden);
+    }
+
+    serve(&state, &transcript, &params, &headers, for_staff).await
+}
+
+// ── GET /transcript/shared/:token ───────────────────────────────────────────
+
+/// Serve a transcript through a share link. No login required; the signed
+/// token is the credential. Accepts the same `format`/`theme` options.
+pub async fn view_shared_transcript(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+    Query(params): Query<ViewTranscriptQuery>,
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    let transcript = transcript_share::redeem(&state.db, &state.config.session_secret, &token).await?;
+    serve(&state, &transcript, &params, &headers, false).await
+}
+
+async fn serve(
+    state: &AppState,
+    transcript: &Transcript,
+    params: &ViewTranscriptQuery,
+    headers: &HeaderMap,
+    for_staff: bool,
 ) -> AppResult<Response> {
-    let transcript = sqlx::query_as::<_, Transcript>(
-        "SELECT * FROM transcripts WHERE id = ?",
+    let format = match params.format.as_deref() {
+        Some(value) => parse_format(value)?,
+        None => headers
+            .get(header::ACCEPT)
+            .and_then(|v| v.to_str().ok())
+            .map(ExportFormat::from_accept)
+            .unwrap_or(ExportFormat::Html),
+    };
+    let theme = params
+        .theme
+        .as_deref()
+        .map(|theme| {
+            TranscriptTheme::parse(theme).ok_or_else(|| {
+                AppError::BadRequest(format!("Invalid theme '{theme}'. Must be one of: dark, light"))
+            })
+        })
+        .transpose()?;
+
+    let body = transcript_export::export(&state.db, transcript, format, theme, for_staff).await?;
+
+    if format == ExportFormat::Html {
+        return Ok(Html(body).into_response());
+    }
+
+    let file_name = transcript_export::file_name(transcript, format);
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{file_name}\"")),
+        ],
+        body,
     )
-    .bind(&id)
+        .into_response())
+}
+
+/// Anyone who can manage the guild, or a member with the guild's ticket
+/// support role.
+async fn is_staff(state: &AppState, user: &AuthUser, transcript: &Transcript) -> AppResult<bool> {
+    if can_manage_guild(state, user, &transcript.guild_id).await? {
+        return Ok(true);
+    }
+
+    let support_role = sqlx::query_scalar::<_, Option<String>>(
+        "SELECT ticket_support_role FROM guilds WHERE id = ?",
+    )
+    .bind(&transcript.guild_id)
     .fetch_optional(&state.db)
     .await?
-    .ok_or_else(|| AppError::NotFound("Transcript not found".to_string()))?;
+    .flatten();
+    let Some(support_role) = support_role else {
+        return Ok(false);
+    };
 
-    // Optionally verify the user has access to this transcript's guild.
-    // For now, any authenticated user can view transcripts they have a link to.
+    match discord_oauth::get_guild_member(
+        &state.http,
+        &state.config.discord_token,
+        &transcript.guild_id,
+        &user.id,
+    )
+    .await
+    {
+        Ok(member) => Ok(member.is_some_and(|m| m.roles.contains(&support_role))),
+        Err(e) => {
+            tracing::warn!("Transcript access: member lookup failed for {}: {e}", user.id);
+            Ok(false)
+        }
+    }
+}
+
+/// Fetch a transcript, 404ing unless it belongs to `guild_id`.
+async fn guild_transcript(state: &AppState, guild_id: &str, transcript_id: &str) -> AppResult<Transcript> {
+    let transcript = transcript::get_transcript(&state.db, transcript_id).await?;
+    if transcript.guild_id != guild_id {
+        return Err(AppError::NotFound(format!("Transcript {transcript_id} not found")));
+    }
+    Ok(transcript)
+}
 
-    Ok(Html(transcript.html).into_response())
+fn parse_format(value: &str) -> AppResult<ExportFormat> {
+    ExportFormat::parse(value).ok_or_else(|| {
+        AppError::BadRequest(format!(
+            "Invalid format '{value}'. Must be one of: html, json, md, txt, pdf"
+        ))
+    })
 }
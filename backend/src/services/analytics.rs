@@ -0,0 +1,173 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, NaiveDate};
+use sqlx::SqlitePool;
+
+use crate::error::AppResult;
+use crate::models::{
+    AnalyticsBucket, CategorySupportStats, StaffSupportStats, SupportAnalytics, SupportBucketStats, SupportStats,
+    Ticket,
+};
+use crate::services::ticket::SYSTEM_ACTOR;
+use crate::timestamp;
+
+/// Review and response-time analytics for a guild's tickets opened within
+/// `from`..=`to` (normalized timestamps): overall, per staff member, per
+/// category and per `bucket` of opening time.
+pub async fn support(
+    pool: &SqlitePool,
+    guild_id: &str,
+    from: Option<&str>,
+    to: Option<&str>,
+    bucket: AnalyticsBucket,
+) -> AppResult<SupportAnalytics> {
+    let tickets = sqlx::query_as::<_, Ticket>(
+        "SELECT * FROM tickets WHERE guild_id = ? \
+         AND (? IS NULL OR created_at >= ?) AND (? IS NULL OR created_at <= ?) \
+         ORDER BY created_at ASC",
+    )
+    .bind(guild_id)
+    .bind(from)
+    .bind(from)
+    .bind(to)
+    .bind(to)
+    .fetch_all(pool)
+    .await?;
+
+    let mut overall = Tally::default();
+    let mut staff: BTreeMap<&str, (i64, i64, Tally)> = BTreeMap::new();
+    let mut categories: BTreeMap<Option<&str>, Tally> = BTreeMap::new();
+    let mut timeline: BTreeMap<NaiveDate, Tally> = BTreeMap::new();
+
+    for t in &tickets {
+        overall.add(t);
+        categories.entry(t.category.as_deref()).or_default().add(t);
+        if let Some(period) = timestamp::parse(&t.created_at).map(|dt| bucket_start(dt.date(), bucket)) {
+            timeline.entry(period).or_default().add(t);
+        }
+
+        // Owners closing their own ticket and auto-closes aren't staff work
+        let closer = t
+            .closed_by
+            .as_deref()
+            .filter(|c| *c != t.user_id && *c != SYSTEM_ACTOR);
+        if let Some(claimer) = t.claimed_by.as_deref() {
+            let entry = staff.entry(claimer).or_default();
+            entry.0 += 1;
+            entry.2.add(t);
+        }
+        if let Some(closer) = closer {
+            let entry = staff.entry(closer).or_default();
+            entry.1 += 1;
+            if t.claimed_by.as_deref() != Some(closer) {
+                entry.2.add(t);
+            }
+        }
+    }
+
+    let mut staff: Vec<StaffSupportStats> = staff
+        .into_iter()
+        .map(|(user_id, (claimed, closed, tally))| StaffSupportStats {
+            user_id: user_id.to_string(),
+            tickets_claimed: claimed,
+            tickets_closed: closed,
+            stats: tally.finish(),
+        })
+        .collect();
+    staff.sort_by_key(|s| std::cmp::Reverse(s.stats.tickets));
+
+    // Uncategorized sorts first in the map; list it last
+    let mut categories: Vec<CategorySupportStats> = categories
+        .into_iter()
+        .map(|(category, tally)| CategorySupportStats {
+            category: category.map(str::to_string),
+            stats: tally.finish(),
+        })
+        .collect();
+    categories.sort_by_key(|c| (c.category.is_none(), std::cmp::Reverse(c.stats.tickets)));
+
+    let timeline = timeline
+        .into_iter()
+        .map(|(period, tally)| SupportBucketStats {
+            period: period.format("%Y-%m-%d").to_string(),
+            stats: tally.finish(),
+        })
+        .collect();
+
+    Ok(SupportAnalytics {
+        bucket,
+        overall: overall.finish(),
+        staff,
+        categories,
+        timeline,
+    })
+}
+
+/// Accumulator behind one [`SupportStats`].
+#[derive(Default)]
+struct Tally {
+    tickets: i64,
+    closed: i64,
+    ratings: Vec<i64>,
+    response_times: Vec<i64>,
+    resolution_times: Vec<i64>,
+}
+
+impl Tally {
+    fn add(&mut self, t: &Ticket) {
+        self.tickets += 1;
+        if t.closed_at.is_some() {
+            self.closed += 1;
+        }
+        if let Some(rating) = t.review_rating.filter(|r| (1..=5).contains(r)) {
+            self.ratings.push(rating);
+        }
+        if let Some(minutes) = timestamp::minutes_since(&t.created_at, t.first_response_at.as_deref()) {
+            self.response_times.push(minutes);
+        }
+        if let Some(minutes) = timestamp::minutes_since(&t.created_at, t.closed_at.as_deref()) {
+            self.resolution_times.push(minutes);
+        }
+    }
+
+    fn finish(mut self) -> SupportStats {
+        let mut rating_distribution: BTreeMap<i64, i64> = (1..=5).map(|r| (r, 0)).collect();
+        for rating in &self.ratings {
+            *rating_distribution.entry(*rating).or_default() += 1;
+        }
+        let avg_rating = (!self.ratings.is_empty())
+            .then(|| self.ratings.iter().sum::<i64>() as f64 / self.ratings.len() as f64);
+
+        SupportStats {
+            tickets: self.tickets,
+            closed: self.closed,
+            rated: self.ratings.len() as i64,
+            avg_rating,
+            rating_distribution,
+            median_first_response_minutes: median(&mut self.response_times),
+            median_resolution_minutes: median(&mut self.resolution_times),
+        }
+    }
+}
+
+/// First day of the bucket containing `date`.
+fn bucket_start(date: NaiveDate, bucket: AnalyticsBucket) -> NaiveDate {
+    match bucket {
+        AnalyticsBucket::Day => date,
+        AnalyticsBucket::Week => date - chrono::Days::new(date.weekday().num_days_from_monday().into()),
+        AnalyticsBucket::Month => date.with_day(1).unwrap_or(date),
+    }
+}
+
+fn median(values: &mut [i64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable();
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) as f64 / 2.0
+    } else {
+        values[mid] as f64
+    })
+}
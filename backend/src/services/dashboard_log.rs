@@ -0,0 +1,96 @@
+use serde_json::Value;
+
+use crate::auth::middleware::GuildAdmin;
+use crate::state::{AppState, WsEvent};
+
+/// Record a dashboard mutation in `dashboard_logs` and broadcast it to
+/// WebSocket clients subscribed to the guild.
+///
+/// `action` is a dotted identifier such as `ticket.close`; `details` is
+/// stored as a JSON string. A failed insert is logged rather than returned,
+/// since the mutation itself has already been applied.
+pub async fn record(
+    state: &AppState,
+    admin: &GuildAdmin,
+    guild_id: &str,
+    action: &str,
+    details: Value,
+) {
+    insert(state, admin, guild_id, action, details, None).await;
+}
+
+/// Like [`record`], but also stores a `{ column: { before, after } }` diff.
+pub async fn record_changes(
+    state: &AppState,
+    admin: &GuildAdmin,
+    guild_id: &str,
+    action: &str,
+    details: Value,
+    changes: Value,
+) {
+    insert(state, admin, guild_id, action, details, Some(changes)).await;
+}
+
+/// Compare two JSON objects and return the keys whose values differ.
+///
+/// Timestamps maintained by the database (`updated_at`) are ignored.
+pub fn diff(before: &Value, after: &Value) -> Value {
+    let mut changes = serde_json::Map::new();
+
+    if let (Some(before), Some(after)) = (before.as_object(), after.as_object()) {
+        for (key, new_value) in after {
+            if key == "updated_at" {
+                continue;
+            }
+            let old_value = before.get(key).unwrap_or(&Value::Null);
+            if old_value != new_value {
+                changes.insert(
+                    key.clone(),
+                    serde_json::json!({ "before": old_value, "after": new_value }),
+                );
+            }
+        }
+    }
+
+    Value::Object(changes)
+}
+
+async fn insert(
+    state: &AppState,
+    admin: &GuildAdmin,
+    guild_id: &str,
+    action: &str,
+    details: Value,
+    changes: Option<Value>,
+) {
+    let details = details.to_string();
+    let changes = changes.map(|c| c.to_string());
+
+    let result = sqlx::query(
+        "INSERT INTO dashboard_logs \
+         (guild_id, user_id, action, details, changes, method, path, ip_address, user_agent) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(guild_id)
+    .bind(&admin.user.id)
+    .bind(action)
+    .bind(&details)
+    .bind(&changes)
+    .bind(&admin.meta.method)
+    .bind(&admin.meta.path)
+    .bind(&admin.meta.ip_address)
+    .bind(&admin.meta.user_agent)
+    .execute(&state.db)
+    .await;
+
+    if let Err(e) = result {
+        tracing::error!("Failed to record dashboard log '{action}' for guild {guild_id}: {e}");
+    }
+
+    let _ = state.ws_tx.send(WsEvent::DashboardLog {
+        guild_id: guild_id.to_string(),
+        action: action.to_string(),
+        user_id: admin.user.id.clone(),
+        details,
+    });
+}
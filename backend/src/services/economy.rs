@@ -0,0 +1,360 @@
+use chrono::Duration;
+use sqlx::{SqliteConnection, SqlitePool};
+
+use crate::error::{AppError, AppResult};
+use crate::models::{BalanceEntry, DailyClaim, MemberBalance, Transaction};
+use crate::timestamp;
+
+/// Base `/daily` payout.
+pub const DAILY_BASE: i64 = 100;
+/// Extra payout per consecutive day after the first.
+pub const DAILY_STREAK_BONUS: i64 = 10;
+/// Streak days that count towards the bonus.
+pub const DAILY_STREAK_CAP: i64 = 10;
+
+/// Hours between daily claims, and how long a streak survives without one.
+const DAILY_COOLDOWN_HOURS: i64 = 24;
+const DAILY_STREAK_WINDOW_HOURS: i64 = 48;
+
+/// Largest single grant or take accepted by [`adjust`].
+pub const MAX_ADJUSTMENT: i64 = 1_000_000_000;
+
+/// Ceiling on any balance, so credits can never overflow the column.
+const MAX_BALANCE: i64 = 1_000_000_000_000_000;
+
+/// Fetch a member's balance in a guild, creating it at zero.
+pub async fn get_balance(
+    pool: &SqlitePool,
+    guild_id: &str,
+    user_id: &str,
+) -> AppResult<MemberBalance> {
+    let mut conn = pool.acquire().await?;
+    ensure_member(&mut conn, guild_id, user_id).await?;
+    fetch_member(&mut conn, guild_id, user_id).await
+}
+
+/// Claim the daily reward in a guild.
+///
+/// The cooldown check and the payout are a single conditional `UPDATE`, so
+/// two concurrent claims cannot both succeed. A claim within 48 hours of the
+/// previous one extends the streak; otherwise it restarts at 1.
+pub async fn claim_daily(
+    pool: &SqlitePool,
+    guild_id: &str,
+    user_id: &str,
+) -> AppResult<DailyClaim> {
+    let mut tx = pool.begin().await?;
+    ensure_member(&mut tx, guild_id, user_id).await?;
+
+    let cooldown = format!("-{DAILY_COOLDOWN_HOURS} hours");
+    let window = format!("-{DAILY_STREAK_WINDOW_HOURS} hours");
+    let claimed = sqlx::query_as::<_, (i64, i64)>(
+        "UPDATE member_balances SET \
+         daily_streak = CASE WHEN last_daily IS NOT NULL AND last_daily > datetime('now', ?) \
+                             THEN daily_streak + 1 ELSE 1 END, \
+         balance = MIN(balance + ? + ? * (MIN( \
+             CASE WHEN last_daily IS NOT NULL AND last_daily > datetime('now', ?) \
+                  THEN daily_streak + 1 ELSE 1 END, ?) - 1), ?), \
+         last_daily = datetime('now'), updated_at = datetime('now') \
+         WHERE guild_id = ? AND user_id = ? AND (last_daily IS NULL OR last_daily <= datetime('now', ?)) \
+         RETURNING balance, daily_streak",
+    )
+    .bind(&window)
+    .bind(DAILY_BASE)
+    .bind(DAILY_STREAK_BONUS)
+    .bind(&window)
+    .bind(DAILY_STREAK_CAP)
+    .bind(MAX_BALANCE)
+    .bind(guild_id)
+    .bind(user_id)
+    .bind(&cooldown)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some((balance, streak)) = claimed else {
+        // On cooldown: report when the next claim opens
+        let member = fetch_member(&mut tx, guild_id, user_id).await?;
+        tx.commit().await?;
+
+        return Ok(DailyClaim {
+            claimed: false,
+            amount: 0,
+            streak: member.daily_streak,
+            balance: member.balance,
+            next_claim_at: next_daily(member.last_daily.as_deref()),
+        });
+    };
+
+    let amount = daily_amount(streak);
+    record(
+        &mut tx, guild_id, user_id, amount, balance, "daily", None, None, None,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(DailyClaim {
+        claimed: true,
+        amount,
+        streak,
+        balance,
+        next_claim_at: next_daily(Some(&timestamp::format(timestamp::now()))),
+    })
+}
+
+/// Move `amount` between two members of a guild. Fails without changing
+/// anything if the sender cannot cover it. Returns the sender's ledger entry.
+pub async fn transfer(
+    pool: &SqlitePool,
+    guild_id: &str,
+    from_user_id: &str,
+    to_user_id: &str,
+    amount: i64,
+    reason: Option<&str>,
+) -> AppResult<Transaction> {
+    if amount <= 0 {
+        return Err(AppError::BadRequest("amount must be positive".to_string()));
+    }
+    if from_user_id == to_user_id {
+        return Err(AppError::BadRequest(
+            "Cannot transfer to yourself".to_string(),
+        ));
+    }
+
+    let mut tx = pool.begin().await?;
+    ensure_member(&mut tx, guild_id, from_user_id).await?;
+    ensure_member(&mut tx, guild_id, to_user_id).await?;
+
+    let sender_balance = debit(&mut tx, guild_id, from_user_id, amount).await?;
+    let receiver_balance = credit(&mut tx, guild_id, to_user_id, amount).await?;
+
+    let sent = record(
+        &mut tx,
+        guild_id,
+        from_user_id,
+        -amount,
+        sender_balance,
+        "transfer_out",
+        Some(to_user_id),
+        None,
+        reason,
+    )
+    .await?;
+    record(
+        &mut tx,
+        guild_id,
+        to_user_id,
+        amount,
+        receiver_balance,
+        "transfer_in",
+        Some(from_user_id),
+        None,
+        reason,
+    )
+    .await?;
+
+    tx.commit().await?;
+    Ok(sent)
+}
+
+/// Admin adjustment: credit (`amount > 0`, "grant") or debit (`amount < 0`,
+/// "take") a member's balance in one guild, by at most [`MAX_ADJUSTMENT`].
+/// Takes cannot push a balance below zero.
+pub async fn adjust(
+    pool: &SqlitePool,
+    guild_id: &str,
+    user_id: &str,
+    amount: i64,
+    actor_id: &str,
+    reason: Option<&str>,
+) -> AppResult<Transaction> {
+    if amount == 0 {
+        return Err(AppError::BadRequest("amount must not be zero".to_string()));
+    }
+    if !(-MAX_ADJUSTMENT..=MAX_ADJUSTMENT).contains(&amount) {
+        return Err(AppError::BadRequest(format!(
+            "amount must be at most {MAX_ADJUSTMENT}"
+        )));
+    }
+
+    let mut tx = pool.begin().await?;
+    ensure_member(&mut tx, guild_id, user_id).await?;
+
+    let (balance, kind) = if amount > 0 {
+        (credit(&mut tx, guild_id, user_id, amount).await?, "grant")
+    } else {
+        (debit(&mut tx, guild_id, user_id, -amount).await?, "take")
+    };
+
+    let entry = record(
+        &mut tx,
+        guild_id,
+        user_id,
+        amount,
+        balance,
+        kind,
+        None,
+        Some(actor_id),
+        reason,
+    )
+    .await?;
+
+    tx.commit().await?;
+    Ok(entry)
+}
+
+/// A member's ledger in a guild, newest first.
+pub async fn list_transactions(
+    pool: &SqlitePool,
+    guild_id: &str,
+    user_id: &str,
+    limit: i64,
+    offset: i64,
+) -> AppResult<Vec<Transaction>> {
+    let entries = sqlx::query_as::<_, Transaction>(
+        "SELECT * FROM transactions WHERE guild_id = ? AND user_id = ? \
+         ORDER BY id DESC LIMIT ? OFFSET ?",
+    )
+    .bind(guild_id)
+    .bind(user_id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(entries)
+}
+
+/// Richest members of a guild.
+pub async fn get_leaderboard(
+    pool: &SqlitePool,
+    guild_id: &str,
+    limit: i64,
+    offset: i64,
+) -> AppResult<Vec<BalanceEntry>> {
+    let entries = sqlx::query_as::<_, BalanceEntry>(
+        "SELECT RANK() OVER (ORDER BY balance DESC) AS rank, user_id, balance \
+         FROM member_balances WHERE guild_id = ? \
+         ORDER BY balance DESC, user_id ASC LIMIT ? OFFSET ?",
+    )
+    .bind(guild_id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(entries)
+}
+
+/// Payout for a claim that brings the streak to `streak` days.
+fn daily_amount(streak: i64) -> i64 {
+    DAILY_BASE + DAILY_STREAK_BONUS * (streak.min(DAILY_STREAK_CAP) - 1)
+}
+
+fn next_daily(last_daily: Option<&str>) -> String {
+    let now = timestamp::now();
+    let next = last_daily
+        .and_then(timestamp::parse)
+        .map(|last| last + Duration::hours(DAILY_COOLDOWN_HOURS))
+        .unwrap_or(now);
+    timestamp::format(next.max(now))
+}
+
+async fn ensure_member(
+    conn: &mut SqliteConnection,
+    guild_id: &str,
+    user_id: &str,
+) -> AppResult<()> {
+    sqlx::query("INSERT OR IGNORE INTO member_balances (guild_id, user_id) VALUES (?, ?)")
+        .bind(guild_id)
+        .bind(user_id)
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+async fn fetch_member(
+    conn: &mut SqliteConnection,
+    guild_id: &str,
+    user_id: &str,
+) -> AppResult<MemberBalance> {
+    let member = sqlx::query_as::<_, MemberBalance>(
+        "SELECT * FROM member_balances WHERE guild_id = ? AND user_id = ?",
+    )
+    .bind(guild_id)
+    .bind(user_id)
+    .fetch_one(conn)
+    .await?;
+    Ok(member)
+}
+
+/// Credit only if the result stays within [`MAX_BALANCE`].
+async fn credit(
+    conn: &mut SqliteConnection,
+    guild_id: &str,
+    user_id: &str,
+    amount: i64,
+) -> AppResult<i64> {
+    sqlx::query_scalar::<_, i64>(
+        "UPDATE member_balances SET balance = balance + ?, updated_at = datetime('now') \
+         WHERE guild_id = ? AND user_id = ? AND balance <= ? - ? RETURNING balance",
+    )
+    .bind(amount)
+    .bind(guild_id)
+    .bind(user_id)
+    .bind(MAX_BALANCE)
+    .bind(amount)
+    .fetch_optional(conn)
+    .await?
+    .ok_or_else(|| AppError::BadRequest(format!("User {user_id} would exceed the maximum balance")))
+}
+
+/// Debit only if the balance covers it, checked in the same statement.
+async fn debit(
+    conn: &mut SqliteConnection,
+    guild_id: &str,
+    user_id: &str,
+    amount: i64,
+) -> AppResult<i64> {
+    sqlx::query_scalar::<_, i64>(
+        "UPDATE member_balances SET balance = balance - ?, updated_at = datetime('now') \
+         WHERE guild_id = ? AND user_id = ? AND balance >= ? RETURNING balance",
+    )
+    .bind(amount)
+    .bind(guild_id)
+    .bind(user_id)
+    .bind(amount)
+    .fetch_optional(conn)
+    .await?
+    .ok_or_else(|| AppError::BadRequest(format!("User {user_id} has insufficient balance")))
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn record(
+    conn: &mut SqliteConnection,
+    guild_id: &str,
+    user_id: &str,
+    amount: i64,
+    balance_after: i64,
+    kind: &str,
+    counterparty_id: Option<&str>,
+    actor_id: Option<&str>,
+    reason: Option<&str>,
+) -> AppResult<Transaction> {
+    let entry = sqlx::query_as::<_, Transaction>(
+        "INSERT INTO transactions \
+         (user_id, guild_id, amount, balance_after, kind, counterparty_id, actor_id, reason) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?) RETURNING *",
+    )
+    .bind(user_id)
+    .bind(guild_id)
+    .bind(amount)
+    .bind(balance_after)
+    .bind(kind)
+    .bind(counterparty_id)
+    .bind(actor_id)
+    .bind(reason)
+    .fetch_one(conn)
+    .await?;
+
+    Ok(entry)
+}
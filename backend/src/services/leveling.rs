@@ -1,31 +1,139 @@
 use sqlx::SqlitePool;
 
-use crate::error::AppResult;
-use crate::models::User;
+use crate::error::{AppError, AppResult};
+use crate::models::{GuildConfig, LeaderboardEntry, LevelReward, MemberLevel, RoleChanges, User};
 
-/// XP required to reach a given level.
+/// Longest accepted `level_curve_table`.
+const MAX_CUSTOM_LEVELS: usize = 1000;
+
+/// Largest single fixed grant (or removal) accepted by [`grant_xp`].
+pub const MAX_XP_GRANT: i64 = 1_000_000;
+
+/// Ceiling on a member's stored XP, far beyond any real total but small
+/// enough that every curve's arithmetic stays in range.
+const MAX_XP: i64 = 1_000_000_000_000;
+
+/// How much XP each level costs in a guild, from the `level_curve*` columns.
 ///
-/// Uses the formula: `5 * level^2 + 50 * level + 100`.
-/// This is the XP needed *for that level alone*, not cumulative.
-pub fn calculate_level(xp: i64) -> i64 {
-    let mut level: i64 = 0;
-    let mut remaining = xp;
-
-    loop {
-        let needed = xp_for_level(level + 1);
-        if remaining < needed {
-            break;
+/// Every level calculation goes through this type so the dashboard, the bot
+/// and the recompute job always agree on a member's level.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LevelCurve {
+    /// Level N costs `step * N` XP, so level N needs `step * N(N+1)/2` total.
+    Linear { step: i64 },
+    /// MEE6-style: level N costs `5N² + 50N + 100` XP.
+    Quadratic,
+    /// Total XP needed for level 1, 2, ... (strictly increasing). Members
+    /// stop levelling past the end of the table.
+    Custom(Vec<i64>),
+}
+
+impl Default for LevelCurve {
+    fn default() -> Self {
+        LevelCurve::Linear { step: 100 }
+    }
+}
+
+impl LevelCurve {
+    /// Build a curve from its stored columns, rejecting unusable settings.
+    pub fn parse(kind: &str, step: i64, table: Option<&str>) -> AppResult<Self> {
+        match kind {
+            "linear" => {
+                if step < 1 {
+                    return Err(AppError::BadRequest("level_curve_step must be at least 1".to_string()));
+                }
+                Ok(LevelCurve::Linear { step })
+            }
+            "quadratic" => Ok(LevelCurve::Quadratic),
+            "custom" => {
+                let table = table.ok_or_else(|| {
+                    AppError::BadRequest("level_curve_table is required for a custom curve".to_string())
+                })?;
+                let thresholds: Vec<i64> = serde_json::from_str(table).map_err(|_| {
+                    AppError::BadRequest("level_curve_table must be a JSON array of integers".to_string())
+                })?;
+                if thresholds.is_empty() || thresholds.len() > MAX_CUSTOM_LEVELS {
+                    return Err(AppError::BadRequest(format!(
+                        "level_curve_table must have between 1 and {MAX_CUSTOM_LEVELS} entries"
+                    )));
+                }
+                if thresholds[0] < 1 || thresholds.windows(2).any(|w| w[1] <= w[0]) {
+                    return Err(AppError::BadRequest(
+                        "level_curve_table must be positive and strictly increasing".to_string(),
+                    ));
+                }
+                Ok(LevelCurve::Custom(thresholds))
+            }
+            other => Err(AppError::BadRequest(format!(
+                "Invalid level_curve '{other}'. Must be one of: linear, quadratic, custom"
+            ))),
         }
-        remaining -= needed;
-        level += 1;
     }
 
-    level
+    /// The curve for a guild, or the default if the guild has no config row.
+    pub async fn for_guild(pool: &SqlitePool, guild_id: &str) -> AppResult<Self> {
+        let row = sqlx::query_as::<_, (String, i64, Option<String>)>(
+            "SELECT level_curve, level_curve_step, level_curve_table FROM guilds WHERE id = ?",
+        )
+        .bind(guild_id)
+        .fetch_optional(pool)
+        .await?;
+
+        match row {
+            Some((kind, step, table)) => Self::parse(&kind, step, table.as_deref()),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Total XP needed to reach `level`, or `None` if the level is unreachable:
+    /// past the end of a custom table, or costing more than an `i64` holds.
+    pub fn total_xp_for_level(&self, level: i64) -> Option<i64> {
+        if level <= 0 {
+            return Some(0);
+        }
+        match self {
+            LevelCurve::Linear { step } => {
+                let n = i128::from(level);
+                fit((n * (n + 1) / 2).checked_mul(i128::from(*step)))
+            }
+            LevelCurve::Quadratic => {
+                // Sum of 5n² + 50n + 100 for n in 1..=level
+                // = 5·n(n+1)(2n+1)/6 + 50·n(n+1)/2 + 100n
+                let n = i128::from(level);
+                let triangle = n * (n + 1) / 2;
+                let total = (|| {
+                    let squares = triangle.checked_mul(2 * n + 1)?.checked_mul(5)? / 3;
+                    squares.checked_add(triangle.checked_mul(50)?)?.checked_add(100 * n)
+                })();
+                fit(total)
+            }
+            LevelCurve::Custom(thresholds) => thresholds.get(level as usize - 1).copied(),
+        }
+    }
+
+    /// The level a member with `xp` total XP has reached.
+    pub fn level_for_xp(&self, xp: i64) -> i64 {
+        if let LevelCurve::Custom(thresholds) = self {
+            return thresholds.partition_point(|&needed| needed <= xp) as i64;
+        }
+
+        // Every level costs at least 1 XP, so the answer lies in 0..=xp.
+        let (mut low, mut high) = (0, xp.max(0));
+        while low < high {
+            let mid = high - (high - low) / 2;
+            if self.total_xp_for_level(mid).is_some_and(|needed| needed <= xp) {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        low
+    }
 }
 
-/// XP required to go from `level - 1` to `level`.
-fn xp_for_level(level: i64) -> i64 {
-    5 * level * level + 50 * level + 100
+/// Narrow a wide XP total, dropping one that overflowed or exceeds `i64`.
+fn fit(total: Option<i128>) -> Option<i64> {
+    total.and_then(|total| i64::try_from(total).ok())
 }
 
 /// Fetch an existing user row, or create one if it does not exist.
@@ -60,34 +168,49 @@ pub async fn get_or_create_user(pool: &SqlitePool, user_id: &str) -> AppResult<U
     Ok(user)
 }
 
-/// Add XP to a user, respecting the guild's cooldown setting.
+/// Fetch a member's XP row for a guild, if they have earned any there.
+pub async fn get_member(pool: &SqlitePool, guild_id: &str, user_id: &str) -> AppResult<Option<MemberLevel>> {
+    let member = sqlx::query_as::<_, MemberLevel>(
+        "SELECT * FROM member_levels WHERE guild_id = ? AND user_id = ?",
+    )
+    .bind(guild_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(member)
+}
+
+/// Add message XP to a member, using the guild's `xp_min`, `xp_max` and
+/// `xp_cooldown` settings (or the schema defaults if the guild has no row).
 ///
-/// 1. Fetches (or creates) the user row.
-/// 2. Checks if `updated_at` + `cooldown_seconds` has passed. If not, returns
-///    the current user without granting XP.
-/// 3. Adds a random amount of XP between `xp_min` and `xp_max`.
-/// 4. Recalculates the level. If the level changed, updates it too.
-/// 5. Returns the updated user and whether a level-up occurred.
-pub async fn add_xp(
-    pool: &SqlitePool,
-    user_id: &str,
-    xp_min: i64,
-    xp_max: i64,
-    cooldown_seconds: i64,
-) -> AppResult<AddXpResult> {
-    let user = get_or_create_user(pool, user_id).await?;
+/// 1. Checks if `last_xp_at` + `xp_cooldown` has passed. If not, returns the
+///    current member without granting XP.
+/// 2. Adds a random amount of XP between `xp_min` and `xp_max`.
+/// 3. Recalculates the level on the guild's curve and reports a level-up.
+pub async fn add_xp(pool: &SqlitePool, guild_id: &str, user_id: &str) -> AppResult<AddXpResult> {
+    let (xp_min, xp_max, cooldown_seconds) = sqlx::query_as::<_, (i64, i64, i64)>(
+        "SELECT xp_min, xp_max, xp_cooldown FROM guilds WHERE id = ?",
+    )
+    .bind(guild_id)
+    .fetch_optional(pool)
+    .await?
+    .unwrap_or((15, 25, 60));
 
-    // Cooldown check: use `updated_at` as the "last XP grant" timestamp.
+    // Cooldown check against the last message-driven grant in this guild.
     let now = chrono::Utc::now();
-    if let Ok(last_update) = chrono::NaiveDateTime::parse_from_str(&user.updated_at, "%Y-%m-%d %H:%M:%S") {
-        let last_utc = last_update.and_utc();
-        let elapsed = now.signed_duration_since(last_utc).num_seconds();
-        if elapsed < cooldown_seconds {
-            return Ok(AddXpResult {
-                user,
-                leveled_up: false,
-                xp_gained: 0,
-            });
+    if let Some(member) = get_member(pool, guild_id, user_id).await? {
+        let last_grant = member.last_xp_at.as_deref().and_then(crate::timestamp::parse);
+        if let Some(last_grant) = last_grant {
+            let elapsed = now.signed_duration_since(last_grant.and_utc()).num_seconds();
+            if elapsed < cooldown_seconds {
+                return Ok(AddXpResult {
+                    member,
+                    leveled_up: false,
+                    xp_gained: 0,
+                    roles: RoleChanges::default(),
+                });
+            }
         }
     }
 
@@ -101,43 +224,227 @@ pub async fn add_xp(
         xp_min + (seed % range) as i64
     };
 
-    let new_xp = user.xp + xp_gained;
-    let new_level = calculate_level(new_xp);
-    let leveled_up = new_level > user.level;
+    apply_xp(pool, guild_id, user_id, xp_gained, true).await
+}
 
-    sqlx::query(
-        "UPDATE users SET xp = ?, level = ?, updated_at = datetime('now') WHERE id = ?",
+/// Add a fixed amount of XP (negative to remove) with no cooldown, e.g. for
+/// bot commands and admin adjustments. XP never drops below zero.
+pub async fn grant_xp(pool: &SqlitePool, guild_id: &str, user_id: &str, amount: i64) -> AppResult<AddXpResult> {
+    if !(-MAX_XP_GRANT..=MAX_XP_GRANT).contains(&amount) {
+        return Err(AppError::BadRequest(format!(
+            "amount must be between -{MAX_XP_GRANT} and {MAX_XP_GRANT}"
+        )));
+    }
+    apply_xp(pool, guild_id, user_id, amount, false).await
+}
+
+async fn apply_xp(
+    pool: &SqlitePool,
+    guild_id: &str,
+    user_id: &str,
+    amount: i64,
+    from_message: bool,
+) -> AppResult<AddXpResult> {
+    let curve = LevelCurve::for_guild(pool, guild_id).await?;
+    let mut tx = pool.begin().await?;
+
+    let previous_level = sqlx::query_scalar::<_, i64>(
+        "SELECT level FROM member_levels WHERE guild_id = ? AND user_id = ?",
     )
-    .bind(new_xp)
-    .bind(new_level)
+    .bind(guild_id)
     .bind(user_id)
-    .execute(pool)
+    .fetch_optional(&mut *tx)
+    .await?
+    .unwrap_or(0);
+
+    let xp = sqlx::query_scalar::<_, i64>(
+        "INSERT INTO member_levels (guild_id, user_id, xp, last_xp_at) \
+         VALUES (?, ?, MIN(MAX(?, 0), ?), CASE WHEN ? THEN datetime('now') END) \
+         ON CONFLICT(guild_id, user_id) DO UPDATE SET \
+         xp = MIN(MAX(xp + ?, 0), ?), \
+         last_xp_at = COALESCE(excluded.last_xp_at, last_xp_at), \
+         updated_at = datetime('now') \
+         RETURNING xp",
+    )
+    .bind(guild_id)
+    .bind(user_id)
+    .bind(amount)
+    .bind(MAX_XP)
+    .bind(from_message)
+    .bind(amount)
+    .bind(MAX_XP)
+    .fetch_one(&mut *tx)
     .await?;
 
-    let updated = sqlx::query_as::<_, User>(
-        "SELECT id, xp, level, balance, last_daily, visible_xp, created_at, updated_at \
-         FROM users WHERE id = ?",
+    let new_level = curve.level_for_xp(xp);
+    let member = sqlx::query_as::<_, MemberLevel>(
+        "UPDATE member_levels SET level = ? WHERE guild_id = ? AND user_id = ? RETURNING *",
     )
+    .bind(new_level)
+    .bind(guild_id)
     .bind(user_id)
-    .fetch_one(pool)
+    .fetch_one(&mut *tx)
     .await?;
 
+    tx.commit().await?;
+
+    let leveled_up = new_level > previous_level;
+    let roles = if leveled_up {
+        reward_changes(pool, guild_id, previous_level, new_level).await?
+    } else {
+        RoleChanges::default()
+    };
+
     Ok(AddXpResult {
-        user: updated,
+        member,
         leveled_up,
-        xp_gained,
+        xp_gained: amount,
+        roles,
     })
 }
 
+/// Roles to add and remove when a member levels up from `from` to `to`,
+/// following the guild's `level_reward_mode`.
+///
+/// In "stack" mode every reward crossed on the way up is applied. In
+/// "replace" mode only the highest reached "add" level's roles are kept and
+/// the roles of lower "add" rewards are removed. Explicit "remove" rewards
+/// crossed on the way up apply in both modes.
+pub async fn reward_changes(pool: &SqlitePool, guild_id: &str, from: i64, to: i64) -> AppResult<RoleChanges> {
+    let mode = sqlx::query_scalar::<_, String>("SELECT level_reward_mode FROM guilds WHERE id = ?")
+        .bind(guild_id)
+        .fetch_optional(pool)
+        .await?
+        .unwrap_or_else(|| "stack".to_string());
+
+    let rewards = sqlx::query_as::<_, LevelReward>(
+        "SELECT * FROM level_rewards WHERE guild_id = ? AND level <= ? ORDER BY level ASC, id ASC",
+    )
+    .bind(guild_id)
+    .bind(to)
+    .fetch_all(pool)
+    .await?;
+
+    let crossed = |r: &&LevelReward| r.level > from;
+    let mut changes = RoleChanges::default();
+
+    for reward in rewards.iter().filter(crossed).filter(|r| r.action == "remove") {
+        changes.roles_to_remove.push(reward.role_id.clone());
+    }
+
+    if mode == "replace" {
+        let top_level = rewards.iter().filter(|r| r.action == "add").map(|r| r.level).max();
+        if let Some(top_level) = top_level.filter(|&level| level > from) {
+            for reward in rewards.iter().filter(|r| r.action == "add") {
+                if reward.level == top_level {
+                    changes.roles_to_add.push(reward.role_id.clone());
+                } else {
+                    changes.roles_to_remove.push(reward.role_id.clone());
+                }
+            }
+        }
+    } else {
+        for reward in rewards.iter().filter(crossed).filter(|r| r.action == "add") {
+            changes.roles_to_add.push(reward.role_id.clone());
+        }
+    }
+
+    // A role both granted and removed at the same step is granted
+    changes.roles_to_remove.retain(|role| !changes.roles_to_add.contains(role));
+    changes.roles_to_add.sort();
+    changes.roles_to_add.dedup();
+    changes.roles_to_remove.sort();
+    changes.roles_to_remove.dedup();
+
+    Ok(changes)
+}
+
+/// Rewrite every stored level in a guild using `curve`. Returns how many
+/// members' levels changed.
+pub async fn recompute_levels(pool: &SqlitePool, guild_id: &str, curve: &LevelCurve) -> AppResult<u64> {
+    let mut tx = pool.begin().await?;
+
+    let members = sqlx::query_as::<_, (String, i64, i64)>(
+        "SELECT user_id, xp, level FROM member_levels WHERE guild_id = ?",
+    )
+    .bind(guild_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut changed = 0;
+    for (user_id, xp, level) in members {
+        let correct = curve.level_for_xp(xp);
+        if correct == level {
+            continue;
+        }
+        sqlx::query("UPDATE member_levels SET level = ? WHERE guild_id = ? AND user_id = ?")
+            .bind(correct)
+            .bind(guild_id)
+            .bind(&user_id)
+            .execute(&mut *tx)
+            .await?;
+        changed += 1;
+    }
+
+    tx.commit().await?;
+    Ok(changed)
+}
+
+/// Flag a guild's stored levels as stale if its curve differs from
+/// `before`, so the scheduler's recompute job picks it up.
+pub async fn mark_curve_changed(pool: &SqlitePool, guild_id: &str, before: &GuildConfig) -> AppResult<()> {
+    sqlx::query(
+        "UPDATE guilds SET level_curve_version = level_curve_version + 1 \
+         WHERE id = ? AND (level_curve IS NOT ? OR level_curve_step IS NOT ? OR level_curve_table IS NOT ?)",
+    )
+    .bind(guild_id)
+    .bind(&before.level_curve)
+    .bind(before.level_curve_step)
+    .bind(&before.level_curve_table)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Recompute levels for every guild whose curve changed since its last
+/// pass. Returns the number of guilds processed.
+///
+/// The version recorded is the one read before recomputing, so a curve
+/// edited mid-pass leaves the guild stale for the next run.
+pub async fn recompute_stale_levels(pool: &SqlitePool) -> AppResult<usize> {
+    let stale = sqlx::query_as::<_, (String, i64)>(
+        "SELECT id, level_curve_version FROM guilds WHERE levels_version < level_curve_version",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    for (guild_id, version) in &stale {
+        let curve = LevelCurve::for_guild(pool, guild_id).await?;
+        let changed = recompute_levels(pool, guild_id, &curve).await?;
+        tracing::info!("Recomputed levels for guild {guild_id}: {changed} member(s) changed");
+
+        sqlx::query("UPDATE guilds SET levels_version = ? WHERE id = ?")
+            .bind(version)
+            .bind(guild_id)
+            .execute(pool)
+            .await?;
+    }
+
+    Ok(stale.len())
+}
+
 /// Result of an `add_xp` call.
 #[derive(Debug)]
 pub struct AddXpResult {
-    pub user: User,
+    pub member: MemberLevel,
     pub leveled_up: bool,
     pub xp_gained: i64,
+    /// Reward roles to apply, empty unless the member levelled up.
+    pub roles: RoleChanges,
 }
 
-/// Fetch a single user's leveling data.
+/// Fetch a single user's global profile row.
 pub async fn get_user(pool: &SqlitePool, user_id: &str) -> AppResult<Option<User>> {
     let user = sqlx::query_as::<_, User>(
         "SELECT id, xp, level, balance, last_daily, visible_xp, created_at, updated_at \
@@ -150,45 +457,137 @@ pub async fn get_user(pool: &SqlitePool, user_id: &str) -> AppResult<Option<User
     Ok(user)
 }
 
-/// Get the XP leaderboard, optionally filtered to a set of guild member IDs.
+/// A member's 1-based XP rank within a guild, or `None` if they have no XP
+/// row there. Ties share the better rank.
+pub async fn get_rank(pool: &SqlitePool, guild_id: &str, user_id: &str) -> AppResult<Option<i64>> {
+    let rank = sqlx::query_scalar::<_, i64>(
+        "SELECT 1 + (SELECT COUNT(*) FROM member_levels o \
+                     WHERE o.guild_id = m.guild_id AND o.xp > m.xp) \
+         FROM member_levels m WHERE m.guild_id = ? AND m.user_id = ?",
+    )
+    .bind(guild_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(rank)
+}
+
+/// Get a guild's XP leaderboard, skipping users who hid their XP.
 ///
-/// * `member_ids` - If `Some`, only users whose ID is in this list are included.
-///                  If `None`, all users are returned.
-/// * `limit`      - Maximum rows to return.
+/// Ranks are computed over all visible members before `offset` and `limit`
+/// are applied, so later pages keep their absolute positions.
 pub async fn get_leaderboard(
     pool: &SqlitePool,
-    member_ids: Option<&[String]>,
+    guild_id: &str,
     limit: i64,
-) -> AppResult<Vec<User>> {
-    let users = if let Some(ids) = member_ids {
-        if ids.is_empty() {
-            return Ok(Vec::new());
-        }
+    offset: i64,
+) -> AppResult<Vec<LeaderboardEntry>> {
+    let entries = sqlx::query_as::<_, LeaderboardEntry>(
+        "SELECT RANK() OVER (ORDER BY m.xp DESC) AS rank, m.user_id, m.xp, m.level \
+         FROM member_levels m LEFT JOIN users u ON u.id = m.user_id \
+         WHERE m.guild_id = ? AND COALESCE(u.visible_xp, 1) = 1 \
+         ORDER BY m.xp DESC, m.user_id ASC LIMIT ? OFFSET ?",
+    )
+    .bind(guild_id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        // Build a comma-separated list of placeholders.
-        let placeholders: String = ids.iter().map(|_| "?").collect::<Vec<_>>().join(", ");
-        let sql = format!(
-            "SELECT id, xp, level, balance, last_daily, visible_xp, created_at, updated_at \
-             FROM users WHERE visible_xp = 1 AND id IN ({placeholders}) \
-             ORDER BY xp DESC LIMIT ?",
+    #[test]
+    fn parse_accepts_valid_curves() {
+        assert_eq!(LevelCurve::parse("linear", 50, None).unwrap(), LevelCurve::Linear { step: 50 });
+        assert_eq!(LevelCurve::parse("quadratic", 0, None).unwrap(), LevelCurve::Quadratic);
+        assert_eq!(
+            LevelCurve::parse("custom", 0, Some("[10, 30, 60]")).unwrap(),
+            LevelCurve::Custom(vec![10, 30, 60])
         );
+    }
+
+    #[test]
+    fn parse_rejects_invalid_curves() {
+        assert!(LevelCurve::parse("linear", 0, None).is_err());
+        assert!(LevelCurve::parse("exponential", 100, None).is_err());
+        assert!(LevelCurve::parse("custom", 0, None).is_err());
+        assert!(LevelCurve::parse("custom", 0, Some("not json")).is_err());
+        assert!(LevelCurve::parse("custom", 0, Some("[]")).is_err());
+        assert!(LevelCurve::parse("custom", 0, Some("[0, 10]")).is_err());
+        assert!(LevelCurve::parse("custom", 0, Some("[10, 10]")).is_err());
+        let too_long = serde_json::to_string(&(1..=MAX_CUSTOM_LEVELS as i64 + 1).collect::<Vec<_>>()).unwrap();
+        assert!(LevelCurve::parse("custom", 0, Some(&too_long)).is_err());
+    }
+
+    #[test]
+    fn linear_totals() {
+        let curve = LevelCurve::Linear { step: 100 };
+        assert_eq!(curve.total_xp_for_level(-1), Some(0));
+        assert_eq!(curve.total_xp_for_level(0), Some(0));
+        assert_eq!(curve.total_xp_for_level(1), Some(100));
+        assert_eq!(curve.total_xp_for_level(2), Some(300));
+        assert_eq!(curve.total_xp_for_level(10), Some(5500));
+    }
 
-        let mut query = sqlx::query_as::<_, User>(&sql);
-        for id in ids {
-            query = query.bind(id);
+    #[test]
+    fn quadratic_totals_match_per_level_costs() {
+        let curve = LevelCurve::Quadratic;
+        let mut total = 0;
+        for n in 1..=200 {
+            total += 5 * n * n + 50 * n + 100;
+            assert_eq!(curve.total_xp_for_level(n), Some(total), "level {n}");
         }
-        query = query.bind(limit);
+    }
 
-        query.fetch_all(pool).await?
-    } else {
-        sqlx::query_as::<_, User>(
-            "SELECT id, xp, level, balance, last_daily, visible_xp, created_at, updated_at \
-             FROM users WHERE visible_xp = 1 ORDER BY xp DESC LIMIT ?",
-        )
-        .bind(limit)
-        .fetch_all(pool)
-        .await?
-    };
+    #[test]
+    fn custom_totals_end_with_the_table() {
+        let curve = LevelCurve::Custom(vec![10, 30, 60]);
+        assert_eq!(curve.total_xp_for_level(1), Some(10));
+        assert_eq!(curve.total_xp_for_level(3), Some(60));
+        assert_eq!(curve.total_xp_for_level(4), None);
+    }
+
+    #[test]
+    fn large_levels_do_not_overflow() {
+        // 5n(n+1)(2n+1) alone exceeds i64::MAX here
+        assert_eq!(LevelCurve::Quadratic.total_xp_for_level(1_500_000), Some(5_625_061_875_188_750_000));
+        for curve in [LevelCurve::Linear { step: 1 }, LevelCurve::Linear { step: i64::MAX }, LevelCurve::Quadratic] {
+            assert_eq!(curve.total_xp_for_level(i64::MAX), None);
+        }
+    }
 
-    Ok(users)
+    #[test]
+    fn level_for_xp_boundaries() {
+        let curves = [
+            LevelCurve::Linear { step: 1 },
+            LevelCurve::Linear { step: 100 },
+            LevelCurve::Quadratic,
+            LevelCurve::Custom(vec![10, 30, 60]),
+        ];
+        for curve in &curves {
+            assert_eq!(curve.level_for_xp(-5), 0);
+            assert_eq!(curve.level_for_xp(0), 0);
+            for level in 1..=3 {
+                let needed = curve.total_xp_for_level(level).unwrap();
+                assert_eq!(curve.level_for_xp(needed - 1), level - 1);
+                assert_eq!(curve.level_for_xp(needed), level);
+            }
+        }
+        assert_eq!(LevelCurve::Custom(vec![10, 30, 60]).level_for_xp(i64::MAX), 3);
+    }
+
+    #[test]
+    fn level_for_xp_handles_large_totals() {
+        // Level n on a step-1 linear curve needs n(n+1)/2 XP
+        let curve = LevelCurve::Linear { step: 1 };
+        assert_eq!(curve.level_for_xp(MAX_XP), 1_414_213);
+        assert_eq!(curve.level_for_xp(i64::MAX), 4_294_967_295);
+        assert_eq!(LevelCurve::Quadratic.level_for_xp(5_625_061_875_188_750_000), 1_500_000);
+    }
 }
@@ -1,7 +1,24 @@
+pub mod analytics;
+pub mod dashboard_log;
+pub mod economy;
 pub mod giveaway;
 pub mod leveling;
 pub mod moderation;
+pub mod pending_action;
+pub mod reminder;
 pub mod scheduler;
+pub mod search;
+pub mod sla;
+pub mod stats;
 pub mod suggestion;
 pub mod ticket;
+pub mod ticket_form;
+pub mod ticket_inactivity;
+pub mod ticket_message;
+pub mod ticket_note;
+pub mod ticket_routing;
+pub mod ticket_tag;
 pub mod transcript;
+pub mod transcript_archive;
+pub mod transcript_export;
+pub mod transcript_share;
@@ -0,0 +1,146 @@
+use sqlx::{SqliteConnection, SqlitePool};
+
+use crate::error::{AppError, AppResult};
+use crate::models::{PendingAction, Ticket};
+
+/// Claims per action before it is marked `failed`.
+pub const MAX_ATTEMPTS: i64 = 5;
+
+/// Default and bounds for how long a bot claim lasts, in seconds.
+pub const DEFAULT_LEASE_SECS: i64 = 60;
+const MIN_LEASE_SECS: i64 = 10;
+const MAX_LEASE_SECS: i64 = 600;
+
+/// Queue an action for the bot. Takes a connection so callers can enqueue
+/// inside the transaction that caused the action.
+pub async fn enqueue(
+    conn: &mut SqliteConnection,
+    guild_id: &str,
+    user_id: &str,
+    action_type: &str,
+    temp_punishment_id: Option<i64>,
+) -> AppResult<PendingAction> {
+    let action = sqlx::query_as::<_, PendingAction>(
+        "INSERT INTO pending_actions (guild_id, user_id, action_type, temp_punishment_id) \
+         VALUES (?, ?, ?, ?) RETURNING *",
+    )
+    .bind(guild_id)
+    .bind(user_id)
+    .bind(action_type)
+    .bind(temp_punishment_id)
+    .fetch_one(conn)
+    .await?;
+
+    Ok(action)
+}
+
+/// Queue an action about a ticket, such as an SLA alert. `payload` is
+/// passed through to the bot as JSON.
+pub async fn enqueue_for_ticket(
+    conn: &mut SqliteConnection,
+    ticket: &Ticket,
+    action_type: &str,
+    payload: &serde_json::Value,
+) -> AppResult<PendingAction> {
+    let action = sqlx::query_as::<_, PendingAction>(
+        "INSERT INTO pending_actions (guild_id, user_id, action_type, ticket_id, payload) \
+         VALUES (?, ?, ?, ?, ?) RETURNING *",
+    )
+    .bind(&ticket.guild_id)
+    .bind(&ticket.user_id)
+    .bind(action_type)
+    .bind(ticket.id)
+    .bind(payload.to_string())
+    .fetch_one(conn)
+    .await?;
+
+    Ok(action)
+}
+
+/// Atomically lease up to `limit` pending actions to the bot, oldest first.
+pub async fn claim(pool: &SqlitePool, limit: i64, lease_secs: i64) -> AppResult<Vec<PendingAction>> {
+    let lease = format!("+{} seconds", lease_secs.clamp(MIN_LEASE_SECS, MAX_LEASE_SECS));
+
+    let mut claimed = sqlx::query_as::<_, PendingAction>(
+        "UPDATE pending_actions SET status = 'claimed', attempts = attempts + 1, \
+         lease_expires_at = datetime('now', ?) \
+         WHERE id IN ( \
+             SELECT id FROM pending_actions WHERE status = 'pending' \
+             ORDER BY created_at ASC, id ASC LIMIT ? \
+         ) RETURNING *",
+    )
+    .bind(&lease)
+    .bind(limit.clamp(1, 100))
+    .fetch_all(pool)
+    .await?;
+
+    claimed.sort_by_key(|a| a.id);
+    Ok(claimed)
+}
+
+/// Mark a claimed action as done.
+pub async fn ack(pool: &SqlitePool, action_id: i64) -> AppResult<PendingAction> {
+    let action = sqlx::query_as::<_, PendingAction>(
+        "UPDATE pending_actions SET status = 'done', lease_expires_at = NULL, \
+         completed_at = datetime('now') WHERE id = ? AND status = 'claimed' RETURNING *",
+    )
+    .bind(action_id)
+    .fetch_optional(pool)
+    .await?;
+
+    match action {
+        Some(action) => Ok(action),
+        None => Err(not_claimed(pool, action_id).await),
+    }
+}
+
+/// Report that the bot could not perform a claimed action. It goes back to
+/// `pending` for another attempt, or to `failed` after `MAX_ATTEMPTS`.
+pub async fn fail(pool: &SqlitePool, action_id: i64, error: &str) -> AppResult<PendingAction> {
+    let action = sqlx::query_as::<_, PendingAction>(
+        "UPDATE pending_actions SET \
+         status = CASE WHEN attempts >= ? THEN 'failed' ELSE 'pending' END, \
+         lease_expires_at = NULL, last_error = ? \
+         WHERE id = ? AND status = 'claimed' RETURNING *",
+    )
+    .bind(MAX_ATTEMPTS)
+    .bind(error)
+    .bind(action_id)
+    .fetch_optional(pool)
+    .await?;
+
+    match action {
+        Some(action) => Ok(action),
+        None => Err(not_claimed(pool, action_id).await),
+    }
+}
+
+/// Return claims whose lease expired to the queue (or fail them after
+/// `MAX_ATTEMPTS`). Returns how many actions became pending again.
+pub async fn requeue_expired(pool: &SqlitePool) -> AppResult<u64> {
+    let requeued = sqlx::query(
+        "UPDATE pending_actions SET \
+         status = CASE WHEN attempts >= ? THEN 'failed' ELSE 'pending' END, \
+         lease_expires_at = NULL, last_error = 'Claim lease expired' \
+         WHERE status = 'claimed' AND lease_expires_at <= datetime('now')",
+    )
+    .bind(MAX_ATTEMPTS)
+    .execute(pool)
+    .await?
+    .rows_affected();
+
+    Ok(requeued)
+}
+
+async fn not_claimed(pool: &SqlitePool, action_id: i64) -> AppError {
+    let exists = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM pending_actions WHERE id = ?")
+        .bind(action_id)
+        .fetch_one(pool)
+        .await;
+
+    match exists {
+        Ok(0) => AppError::NotFound(format!("Pending action {action_id} not found")),
+        Ok(_) => AppError::BadRequest(format!("Pending action {action_id} is not claimed")),
+        Err(e) => e.into(),
+    }
+}
@@ -0,0 +1,259 @@
+use chrono::{Duration, NaiveDateTime};
+use croner::Cron;
+use sqlx::SqlitePool;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{CreateReminder, Reminder};
+use crate::timestamp;
+
+/// Delivery attempts per occurrence before a reminder is marked `failed`.
+pub const MAX_ATTEMPTS: i64 = 5;
+
+/// Default and bounds for how long a bot claim lasts, in seconds.
+pub const DEFAULT_LEASE_SECS: i64 = 60;
+const MIN_LEASE_SECS: i64 = 10;
+const MAX_LEASE_SECS: i64 = 600;
+
+/// Insert a new reminder, validating its schedule.
+///
+/// `remind_at` may be in SQLite format or RFC 3339 and is stored normalised.
+/// Recurring reminders must set `recurrence` to "daily", "weekly", or "cron";
+/// the latter also needs a 5-field `cron_expr` evaluated in UTC.
+pub async fn create(pool: &SqlitePool, data: CreateReminder) -> AppResult<Reminder> {
+    let remind_at = timestamp::normalize(&data.remind_at, "00:00:00").ok_or_else(|| {
+        AppError::BadRequest(format!("Invalid remind_at '{}'", data.remind_at))
+    })?;
+
+    match data.recurrence.as_deref() {
+        None | Some("daily") | Some("weekly") => {}
+        Some("cron") => {
+            let expr = data.cron_expr.as_deref().ok_or_else(|| {
+                AppError::BadRequest("cron_expr is required for cron reminders".to_string())
+            })?;
+            parse_cron(expr)?;
+        }
+        Some(other) => {
+            return Err(AppError::BadRequest(format!(
+                "Invalid recurrence '{other}'. Must be one of: daily, weekly, cron"
+            )));
+        }
+    }
+
+    let cron_expr = match data.recurrence.as_deref() {
+        Some("cron") => data.cron_expr.as_deref(),
+        _ => None,
+    };
+
+    let reminder = sqlx::query_as::<_, Reminder>(
+        "INSERT INTO reminders (user_id, guild_id, channel_id, message, remind_at, recurrence, cron_expr) \
+         VALUES (?, ?, ?, ?, ?, ?, ?) RETURNING *",
+    )
+    .bind(&data.user_id)
+    .bind(&data.guild_id)
+    .bind(&data.channel_id)
+    .bind(&data.message)
+    .bind(&remind_at)
+    .bind(&data.recurrence)
+    .bind(cron_expr)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(reminder)
+}
+
+/// Fetch a single reminder by ID.
+pub async fn get(pool: &SqlitePool, reminder_id: i64) -> AppResult<Reminder> {
+    let reminder = sqlx::query_as::<_, Reminder>("SELECT * FROM reminders WHERE id = ?")
+        .bind(reminder_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Reminder {reminder_id} not found")))?;
+
+    Ok(reminder)
+}
+
+/// A user's reminders that are still to be delivered, soonest first.
+pub async fn list_for_user(pool: &SqlitePool, user_id: &str) -> AppResult<Vec<Reminder>> {
+    let reminders = sqlx::query_as::<_, Reminder>(
+        "SELECT * FROM reminders WHERE user_id = ? AND status IN ('pending', 'due', 'claimed') \
+         ORDER BY remind_at ASC",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(reminders)
+}
+
+/// Atomically lease up to `limit` due reminders to the bot.
+///
+/// Claimed rows move to `claimed` with `lease_expires_at` set `lease_secs`
+/// into the future. If the bot neither acks nor fails them before then, the
+/// scheduler makes them due again.
+pub async fn claim(pool: &SqlitePool, limit: i64, lease_secs: i64) -> AppResult<Vec<Reminder>> {
+    let lease = format!("+{} seconds", lease_secs.clamp(MIN_LEASE_SECS, MAX_LEASE_SECS));
+
+    let mut claimed = sqlx::query_as::<_, Reminder>(
+        "UPDATE reminders SET status = 'claimed', attempts = attempts + 1, \
+         lease_expires_at = datetime('now', ?) \
+         WHERE id IN ( \
+             SELECT id FROM reminders \
+             WHERE status = 'due' OR (status = 'pending' AND remind_at <= datetime('now')) \
+             ORDER BY remind_at ASC LIMIT ? \
+         ) RETURNING *",
+    )
+    .bind(&lease)
+    .bind(limit.clamp(1, 100))
+    .fetch_all(pool)
+    .await?;
+
+    claimed.sort_by(|a, b| a.remind_at.cmp(&b.remind_at));
+    Ok(claimed)
+}
+
+/// Acknowledge delivery of a claimed reminder.
+///
+/// One-off reminders become `delivered`; recurring ones are rescheduled to
+/// their next occurrence and return to `pending`.
+pub async fn ack(pool: &SqlitePool, reminder_id: i64) -> AppResult<Reminder> {
+    let reminder = get(pool, reminder_id).await?;
+    if reminder.status != "claimed" {
+        return Err(AppError::BadRequest(format!(
+            "Reminder {reminder_id} is not claimed"
+        )));
+    }
+
+    let result = match next_occurrence(&reminder, timestamp::now())? {
+        Some(next) => {
+            sqlx::query(
+                "UPDATE reminders SET status = 'pending', remind_at = ?, attempts = 0, \
+                 lease_expires_at = NULL, last_error = NULL, delivered_at = datetime('now') \
+                 WHERE id = ? AND status = 'claimed'",
+            )
+            .bind(timestamp::format(next))
+            .bind(reminder_id)
+            .execute(pool)
+            .await?
+        }
+        None => {
+            sqlx::query(
+                "UPDATE reminders SET status = 'delivered', lease_expires_at = NULL, \
+                 delivered_at = datetime('now') WHERE id = ? AND status = 'claimed'",
+            )
+            .bind(reminder_id)
+            .execute(pool)
+            .await?
+        }
+    };
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::BadRequest(format!(
+            "Reminder {reminder_id} is not claimed"
+        )));
+    }
+
+    get(pool, reminder_id).await
+}
+
+/// Report a failed delivery of a claimed reminder so it can be retried.
+pub async fn fail(pool: &SqlitePool, reminder_id: i64, error: &str) -> AppResult<Reminder> {
+    let reminder = get(pool, reminder_id).await?;
+    if reminder.status != "claimed" {
+        return Err(AppError::BadRequest(format!(
+            "Reminder {reminder_id} is not claimed"
+        )));
+    }
+
+    retry_or_give_up(pool, &reminder, error).await?;
+    get(pool, reminder_id).await
+}
+
+/// Delete a reminder, stopping any future occurrences.
+pub async fn cancel(pool: &SqlitePool, reminder_id: i64) -> AppResult<()> {
+    let result = sqlx::query("DELETE FROM reminders WHERE id = ?")
+        .bind(reminder_id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Reminder {reminder_id} not found")));
+    }
+
+    Ok(())
+}
+
+/// Make a claimed reminder due again, or give up after `MAX_ATTEMPTS`.
+///
+/// A recurring reminder that runs out of attempts skips to its next
+/// occurrence instead of being marked `failed`.
+pub async fn retry_or_give_up(pool: &SqlitePool, reminder: &Reminder, error: &str) -> AppResult<()> {
+    if reminder.attempts < MAX_ATTEMPTS {
+        sqlx::query(
+            "UPDATE reminders SET status = 'due', lease_expires_at = NULL, last_error = ? \
+             WHERE id = ? AND status = 'claimed'",
+        )
+        .bind(error)
+        .bind(reminder.id)
+        .execute(pool)
+        .await?;
+        return Ok(());
+    }
+
+    match next_occurrence(reminder, timestamp::now())? {
+        Some(next) => {
+            sqlx::query(
+                "UPDATE reminders SET status = 'pending', remind_at = ?, attempts = 0, \
+                 lease_expires_at = NULL, last_error = ? WHERE id = ? AND status = 'claimed'",
+            )
+            .bind(timestamp::format(next))
+            .bind(error)
+            .bind(reminder.id)
+            .execute(pool)
+            .await?;
+        }
+        None => {
+            sqlx::query(
+                "UPDATE reminders SET status = 'failed', lease_expires_at = NULL, last_error = ? \
+                 WHERE id = ? AND status = 'claimed'",
+            )
+            .bind(error)
+            .bind(reminder.id)
+            .execute(pool)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The next time a recurring reminder should fire after `now`, or `None`
+/// for one-off reminders. Missed occurrences are skipped, not replayed.
+pub fn next_occurrence(reminder: &Reminder, now: NaiveDateTime) -> AppResult<Option<NaiveDateTime>> {
+    let base = timestamp::parse(&reminder.remind_at).unwrap_or(now);
+
+    let step = match reminder.recurrence.as_deref() {
+        Some("daily") => Duration::days(1),
+        Some("weekly") => Duration::weeks(1),
+        Some("cron") => {
+            let expr = reminder.cron_expr.as_deref().unwrap_or_default();
+            let start = base.max(now).and_utc();
+            let next = parse_cron(expr)?
+                .find_next_occurrence(&start, false)
+                .map_err(|e| AppError::BadRequest(format!("Invalid cron expression '{expr}': {e}")))?;
+            return Ok(Some(next.naive_utc()));
+        }
+        _ => return Ok(None),
+    };
+
+    let mut next = base + step;
+    while next <= now {
+        next += step;
+    }
+    Ok(Some(next))
+}
+
+fn parse_cron(expr: &str) -> AppResult<Cron> {
+    Cron::new(expr)
+        .parse()
+        .map_err(|e| AppError::BadRequest(format!("Invalid cron expression '{expr}': {e}")))
+}
@@ -3,23 +3,37 @@ use tokio::time::{interval, Duration};
 
 use crate::error::AppResult;
 use crate::models::{Giveaway, Reminder, TempPunishment};
+use crate::services::{pending_action, reminder};
+use crate::state::{AppState, WsEvent};
 
 /// Main scheduler loop - runs every 30 seconds checking for due items.
-pub async fn run_scheduler(pool: &SqlitePool) {
+pub async fn run_scheduler(state: &AppState) {
+    let pool = &state.db;
     let mut ticker = interval(Duration::from_secs(30));
 
     loop {
         ticker.tick().await;
 
-        // Check expired temp punishments
+        // Lift expired temp punishments and wake connected bots
         match check_temp_punishments(pool).await {
             Ok(expired) if !expired.is_empty() => {
-                tracing::info!("Scheduler: {} expired temp punishment(s) processed", expired.len());
+                tracing::info!("Scheduler: {} expired temp punishment(s) queued for lifting", expired.len());
+                state.bot_actions.notify_waiters();
             }
             Err(e) => tracing::error!("Scheduler: temp punishment check failed: {e}"),
             _ => {}
         }
 
+        // Return bot actions whose claim lapsed to the outbox
+        match pending_action::requeue_expired(pool).await {
+            Ok(requeued) if requeued > 0 => {
+                tracing::info!("Scheduler: {requeued} pending action(s) requeued after lease expiry");
+                state.bot_actions.notify_waiters();
+            }
+            Err(e) => tracing::error!("Scheduler: pending action requeue failed: {e}"),
+            _ => {}
+        }
+
         // Check giveaways that need ending
         match check_giveaways(pool).await {
             Ok(due) if !due.is_empty() => {
@@ -34,36 +48,149 @@ pub async fn run_scheduler(pool: &SqlitePool) {
             _ => {}
         }
 
-        // Check due reminders
+        // Queue due reminders for the bot and requeue expired claims
         match check_reminders(pool).await {
-            Ok(due) if !due.is_empty() => {
-                tracing::info!("Scheduler: {} reminder(s) due (bot will deliver)", due.len());
-                // Note: actual delivery is done by the bot via polling /api/v1/bot/reminders
+            Ok(due) if due > 0 => {
+                tracing::info!("Scheduler: {due} reminder(s) queued (bot claims via /api/v1/bot/reminders/claim)");
             }
             Err(e) => tracing::error!("Scheduler: reminder check failed: {e}"),
             _ => {}
         }
+
+        // Warn about tickets nearing an SLA deadline and flag breaches
+        match crate::services::sla::check_deadlines(pool).await {
+            Ok(alerts) if !alerts.is_empty() => {
+                tracing::info!("Scheduler: {} SLA alert(s) raised", alerts.len());
+                for alert in alerts {
+                    let _ = state.ws_tx.send(WsEvent::SlaAlert {
+                        guild_id: alert.guild_id,
+                        ticket_id: alert.ticket_id,
+                        target: alert.target,
+                        level: alert.level,
+                        due_at: alert.due_at,
+                    });
+                }
+                state.bot_actions.notify_waiters();
+            }
+            Err(e) => tracing::error!("Scheduler: SLA check failed: {e}"),
+            _ => {}
+        }
+
+        // Warn idle tickets, then close those still idle after the warning
+        match crate::services::ticket_inactivity::check(pool).await {
+            Ok(run) if !run.warned.is_empty() || !run.closed.is_empty() => {
+                tracing::info!(
+                    "Scheduler: {} idle ticket(s) warned, {} closed",
+                    run.warned.len(),
+                    run.closed.len()
+                );
+                for ticket in run.closed {
+                    let _ = state.ws_tx.send(WsEvent::TicketUpdate {
+                        guild_id: ticket.guild_id,
+                        ticket_id: ticket.id,
+                        status: ticket.status.as_str().to_string(),
+                    });
+                }
+                state.bot_actions.notify_waiters();
+            }
+            Err(e) => tracing::error!("Scheduler: inactivity check failed: {e}"),
+            _ => {}
+        }
+
+        // Lift temporary ticket blacklists that have run out
+        match crate::services::ticket::lift_expired_blacklists(pool).await {
+            Ok(lifted) if !lifted.is_empty() => {
+                tracing::info!("Scheduler: {} ticket blacklist(s) expired", lifted.len());
+            }
+            Err(e) => tracing::error!("Scheduler: blacklist expiry failed: {e}"),
+            _ => {}
+        }
+
+        // Recompute member levels in guilds whose level curve changed
+        match crate::services::leveling::recompute_stale_levels(pool).await {
+            Ok(count) if count > 0 => {
+                tracing::info!("Scheduler: levels recomputed for {count} guild(s)");
+            }
+            Err(e) => tracing::error!("Scheduler: level recompute failed: {e}"),
+            _ => {}
+        }
+
+        // Add transcripts missing from the search index
+        match crate::services::search::index_pending_transcripts(pool).await {
+            Ok(count) if count > 0 => {
+                tracing::info!("Scheduler: {count} transcript(s) added to the search index");
+            }
+            Err(e) => tracing::error!("Scheduler: transcript indexing failed: {e}"),
+            _ => {}
+        }
+
+        // Archive or delete transcripts past their guild's retention period
+        let archive_dir = std::path::Path::new(&state.config.transcript_archive_dir);
+        match crate::services::transcript_archive::enforce_retention(pool, archive_dir).await {
+            Ok(run) if run.archived > 0 || run.deleted > 0 => {
+                tracing::info!(
+                    "Scheduler: transcript retention archived {} and deleted {}",
+                    run.archived,
+                    run.deleted
+                );
+            }
+            Err(e) => tracing::error!("Scheduler: transcript retention failed: {e}"),
+            _ => {}
+        }
+
+        // Push live stats to guilds with dashboard subscribers
+        push_guild_stats(state).await;
+    }
+}
+
+/// Broadcast the `guild_stats` aggregate for every guild that currently has
+/// at least one WebSocket subscriber.
+pub async fn push_guild_stats(state: &AppState) {
+    for guild_id in state.subscribed_guilds() {
+        match crate::services::stats::guild_stats(&state.db, &guild_id).await {
+            Ok(data) => {
+                let _ = state.ws_tx.send(WsEvent::Stats { guild_id, data });
+            }
+            Err(e) => tracing::error!("Scheduler: stats for guild {guild_id} failed: {e}"),
+        }
     }
 }
 
-/// Check for expired temporary punishments (bans/mutes).
+/// Lift expired temporary punishments (bans/mutes).
 ///
-/// Returns all rows whose `expires_at` is in the past, then deletes them so
-/// they are not processed again.
+/// Each expired row is stamped with `lifted_at` and an unban/unmute is
+/// queued in `pending_actions` for the bot, in one transaction so a lift is
+/// never recorded without its action. Returns the lifted punishments.
 pub async fn check_temp_punishments(pool: &SqlitePool) -> AppResult<Vec<TempPunishment>> {
+    let mut tx = pool.begin().await?;
+
     let expired = sqlx::query_as::<_, TempPunishment>(
-        "SELECT id, guild_id, user_id, punishment_type, expires_at, created_at \
-         FROM temp_punishments WHERE expires_at <= datetime('now')",
+        "UPDATE temp_punishments SET lifted_at = datetime('now') \
+         WHERE lifted_at IS NULL AND expires_at <= datetime('now') RETURNING *",
     )
-    .fetch_all(pool)
+    .fetch_all(&mut *tx)
     .await?;
 
-    if !expired.is_empty() {
-        sqlx::query("DELETE FROM temp_punishments WHERE expires_at <= datetime('now')")
-            .execute(pool)
-            .await?;
+    for punishment in &expired {
+        let action_type = match punishment.punishment_type.as_str() {
+            "ban" => "unban",
+            "mute" => "unmute",
+            other => {
+                tracing::warn!("Scheduler: unknown punishment type '{other}' on #{}", punishment.id);
+                continue;
+            }
+        };
+        pending_action::enqueue(
+            &mut tx,
+            &punishment.guild_id,
+            &punishment.user_id,
+            action_type,
+            Some(punishment.id),
+        )
+        .await?;
     }
 
+    tx.commit().await?;
     Ok(expired)
 }
 
@@ -84,25 +211,31 @@ pub async fn check_giveaways(pool: &SqlitePool) -> AppResult<Vec<Giveaway>> {
     Ok(due)
 }
 
-/// Check for reminders that are due.
+/// Move reminders whose `remind_at` has passed from `pending` to `due`, and
+/// recover claims whose lease expired without an ack or fail.
 ///
-/// Returns all reminder rows whose `remind_at` is in the past, then deletes
-/// them so they are not fired again.
-pub async fn check_reminders(pool: &SqlitePool) -> AppResult<Vec<Reminder>> {
-    let due = sqlx::query_as::<_, Reminder>(
-        "SELECT id, user_id, guild_id, channel_id, message, remind_at, created_at \
-         FROM reminders WHERE remind_at <= datetime('now')",
+/// Returns the number of reminders newly queued for delivery.
+pub async fn check_reminders(pool: &SqlitePool) -> AppResult<u64> {
+    let queued = sqlx::query(
+        "UPDATE reminders SET status = 'due' \
+         WHERE status = 'pending' AND remind_at <= datetime('now')",
+    )
+    .execute(pool)
+    .await?
+    .rows_affected();
+
+    let expired = sqlx::query_as::<_, Reminder>(
+        "SELECT * FROM reminders \
+         WHERE status = 'claimed' AND lease_expires_at <= datetime('now')",
     )
     .fetch_all(pool)
     .await?;
 
-    if !due.is_empty() {
-        sqlx::query("DELETE FROM reminders WHERE remind_at <= datetime('now')")
-            .execute(pool)
-            .await?;
+    for reminder in &expired {
+        reminder::retry_or_give_up(pool, reminder, "Claim lease expired").await?;
     }
 
-    Ok(due)
+    Ok(queued)
 }
 
 /// Insert a new temporary punishment (ban or mute with expiry).
@@ -125,7 +258,7 @@ pub async fn add_temp_punishment(
     .await?;
 
     let punishment = sqlx::query_as::<_, TempPunishment>(
-        "SELECT id, guild_id, user_id, punishment_type, expires_at, created_at \
+        "SELECT id, guild_id, user_id, punishment_type, expires_at, created_at, lifted_at \
          FROM temp_punishments WHERE id = last_insert_rowid()",
     )
     .fetch_one(pool)
@@ -133,34 +266,3 @@ pub async fn add_temp_punishment(
 
     Ok(punishment)
 }
-
-/// Insert a new reminder.
-pub async fn add_reminder(
-    pool: &SqlitePool,
-    user_id: &str,
-    guild_id: &str,
-    channel_id: &str,
-    message: &str,
-    remind_at: &str,
-) -> AppResult<Reminder> {
-    sqlx::query(
-        "INSERT INTO reminders (user_id, guild_id, channel_id, message, remind_at) \
-         VALUES (?, ?, ?, ?, ?)",
-    )
-    .bind(user_id)
-    .bind(guild_id)
-    .bind(channel_id)
-    .bind(message)
-    .bind(remind_at)
-    .execute(pool)
-    .await?;
-
-    let reminder = sqlx::query_as::<_, Reminder>(
-        "SELECT id, user_id, guild_id, channel_id, message, remind_at, created_at \
-         FROM reminders WHERE id = last_insert_rowid()",
-    )
-    .fetch_one(pool)
-    .await?;
-
-    Ok(reminder)
-}
@@ -0,0 +1,252 @@
+use sqlx::{SqliteConnection, SqlitePool};
+
+use crate::error::{AppError, AppResult};
+use crate::models::{SearchFilters, SearchHit, SearchKind, TicketMessage, Transcript};
+use crate::services::{transcript, transcript_archive};
+use crate::timestamp;
+
+/// Transcripts indexed per scheduler tick by [`index_pending_transcripts`].
+const INDEX_BATCH: i64 = 50;
+
+/// Search a guild's transcripts, ticket subjects and ticket messages.
+///
+/// `query` is split into words which must all match; a trailing `*` on a
+/// word matches it as a prefix. Hits of all kinds are ranked together,
+/// best first. Deleted messages are never returned.
+pub async fn search(
+    pool: &SqlitePool,
+    guild_id: &str,
+    query: &str,
+    filters: &SearchFilters,
+    limit: i64,
+    offset: i64,
+) -> AppResult<Vec<SearchHit>> {
+    let fts_query = match_expression(query)
+        .ok_or_else(|| AppError::BadRequest("Search query must not be empty".to_string()))?;
+    let (from, to) = timestamp::parse_range(filters.from.as_deref(), filters.to.as_deref())?;
+
+    // Each kind is ranked on its own; fetching enough of each to cover the
+    // requested page and merging keeps the overall order.
+    let window = limit + offset;
+    let mut hits = Vec::new();
+
+    for kind in SearchKind::ALL {
+        if filters.kind.is_some_and(|k| k != kind) {
+            continue;
+        }
+
+        let sql = match kind {
+            SearchKind::Transcript => format!(
+                "SELECT 'transcript' AS kind, tr.ticket_id, tr.ticket_number, tr.id AS transcript_id, \
+                 NULL AS message_id, tr.subject, tr.user_id, tr.category, {}, tr.created_at \
+                 FROM transcripts_fts JOIN transcripts tr ON tr.search_rowid = transcripts_fts.rowid \
+                 WHERE transcripts_fts MATCH ? AND tr.guild_id = ? AND {} \
+                 ORDER BY rank LIMIT ?",
+                snippet_columns("transcripts_fts"),
+                filter_clause("tr", "tr"),
+            ),
+            SearchKind::Ticket => format!(
+                "SELECT 'ticket' AS kind, t.id AS ticket_id, t.number AS ticket_number, {}, \
+                 NULL AS message_id, t.subject, t.user_id, t.category, {}, t.created_at \
+                 FROM tickets_fts JOIN tickets t ON t.id = tickets_fts.rowid \
+                 WHERE tickets_fts MATCH ? AND t.guild_id = ? AND {} \
+                 ORDER BY rank LIMIT ?",
+                LATEST_TRANSCRIPT,
+                snippet_columns("tickets_fts"),
+                filter_clause("t", "t"),
+            ),
+            SearchKind::Message => format!(
+                "SELECT 'message' AS kind, t.id AS ticket_id, t.number AS ticket_number, {}, \
+                 m.message_id, t.subject, t.user_id, t.category, {}, m.created_at \
+                 FROM ticket_messages_fts \
+                 JOIN ticket_messages m ON m.id = ticket_messages_fts.rowid \
+                 JOIN tickets t ON t.id = m.ticket_id \
+                 WHERE ticket_messages_fts MATCH ? AND m.guild_id = ? AND m.deleted_at IS NULL AND {} \
+                 ORDER BY rank LIMIT ?",
+                LATEST_TRANSCRIPT,
+                snippet_columns("ticket_messages_fts"),
+                filter_clause("t", "m"),
+            ),
+        };
+
+        let found = sqlx::query_as::<_, SearchHit>(&sql)
+            .bind(&fts_query)
+            .bind(guild_id)
+            .bind(&filters.user_id)
+            .bind(&filters.user_id)
+            .bind(&filters.category)
+            .bind(&filters.category)
+            .bind(&filters.closed_by)
+            .bind(&filters.closed_by)
+            .bind(&from)
+            .bind(&from)
+            .bind(&to)
+            .bind(&to)
+            .bind(window)
+            .fetch_all(pool)
+            .await?;
+        hits.extend(found);
+    }
+
+    hits.sort_by(|a, b| a.rank.total_cmp(&b.rank));
+    Ok(hits
+        .into_iter()
+        .skip(offset as usize)
+        .take(limit as usize)
+        .map(|mut hit| {
+            hit.snippet = highlight(&hit.snippet);
+            hit
+        })
+        .collect())
+}
+
+/// Add a transcript to the search index, replacing any earlier entry.
+///
+/// The indexed body is the text of `messages`, or the transcript's HTML with
+/// the markup stripped when it has none (transcripts uploaded by the bot
+/// before messages were ingested).
+pub async fn index_transcript(
+    conn: &mut SqliteConnection,
+    transcript: &Transcript,
+    messages: &[TicketMessage],
+) -> AppResult<()> {
+    let body = if messages.is_empty() {
+        strip_html(&transcript_archive::load_html(&mut *conn, transcript).await?)
+    } else {
+        messages
+            .iter()
+            .map(|m| format!("{}: {}", m.author_name, m.content))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    sqlx::query(
+        "DELETE FROM transcripts_fts WHERE rowid = (SELECT search_rowid FROM transcripts WHERE id = ?)",
+    )
+    .bind(&transcript.id)
+    .execute(&mut *conn)
+    .await?;
+
+    // FTS5 tables don't support RETURNING, so take the rowid from the insert
+    let rowid = sqlx::query("INSERT INTO transcripts_fts (subject, body) VALUES (?, ?)")
+        .bind(&transcript.subject)
+        .bind(&body)
+        .execute(&mut *conn)
+        .await?
+        .last_insert_rowid();
+
+    sqlx::query("UPDATE transcripts SET search_rowid = ? WHERE id = ?")
+        .bind(rowid)
+        .bind(&transcript.id)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Index transcripts that are not in the search index yet, oldest first.
+/// Returns how many were indexed.
+pub async fn index_pending_transcripts(pool: &SqlitePool) -> AppResult<u64> {
+    let pending = sqlx::query_as::<_, Transcript>(
+        "SELECT * FROM transcripts WHERE search_rowid IS NULL ORDER BY created_at ASC LIMIT ?",
+    )
+    .bind(INDEX_BATCH)
+    .fetch_all(pool)
+    .await?;
+
+    for t in &pending {
+        let messages = transcript::messages_for(pool, t).await?;
+        let mut tx = pool.begin().await?;
+        index_transcript(&mut tx, t, &messages).await?;
+        tx.commit().await?;
+    }
+
+    Ok(pending.len() as u64)
+}
+
+// ── Query building ──────────────────────────────────────────────────────────
+
+/// Marks around matched terms in raw snippets, swapped for `<mark>` once
+/// the snippet has been escaped.
+const MATCH_START: char = '\u{2}';
+const MATCH_END: char = '\u{3}';
+
+/// Latest transcript of the ticket aliased `t`.
+const LATEST_TRANSCRIPT: &str = "(SELECT id FROM transcripts WHERE ticket_id = t.id \
+                                 ORDER BY created_at DESC LIMIT 1) AS transcript_id";
+
+fn snippet_columns(table: &str) -> String {
+    format!("snippet({table}, -1, char(2), char(3), '…', 16) AS snippet, bm25({table}) AS rank")
+}
+
+/// Optional filters, bound in order as user, category, closed_by, from, to
+/// (each twice). Ticket fields come from `ticket`, the date from `dated`.
+fn filter_clause(ticket: &str, dated: &str) -> String {
+    format!(
+        "(? IS NULL OR {ticket}.user_id = ?) AND (? IS NULL OR {ticket}.category = ?) \
+         AND (? IS NULL OR {ticket}.closed_by = ?) \
+         AND (? IS NULL OR {dated}.created_at >= ?) AND (? IS NULL OR {dated}.created_at <= ?)"
+    )
+}
+
+/// Turn user input into an FTS5 expression of quoted words, so operators
+/// and punctuation in the input are matched literally instead of failing
+/// to parse. `None` when there is nothing to search for.
+fn match_expression(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .filter_map(|word| {
+            let (word, prefix) = match word.strip_suffix('*') {
+                Some(stem) => (stem, "*"),
+                None => (word, ""),
+            };
+            (!word.is_empty()).then(|| format!("\"{}\"{prefix}", word.replace('"', "\"\"")))
+        })
+        .collect();
+
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+/// Escape a raw snippet and wrap its matches in `<mark>`.
+fn highlight(snippet: &str) -> String {
+    transcript::escape_html(snippet)
+        .replace(MATCH_START, "<mark>")
+        .replace(MATCH_END, "</mark>")
+}
+
+/// Text content of an HTML document: tags dropped, common entities decoded
+/// and whitespace collapsed.
+fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let lower = html.to_ascii_lowercase();
+    let mut in_tag = false;
+    let mut skipping = false;
+
+    for (i, ch) in html.char_indices() {
+        if in_tag {
+            in_tag = ch != '>';
+            continue;
+        }
+        if ch == '<' {
+            let tag = &lower[i..];
+            if tag.starts_with("<style") || tag.starts_with("<script") {
+                skipping = true;
+            } else if tag.starts_with("</style") || tag.starts_with("</script") {
+                skipping = false;
+            }
+            in_tag = true;
+            text.push(' ');
+        } else if !skipping {
+            text.push(ch);
+        }
+    }
+
+    let text = text
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&");
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
@@ -0,0 +1,319 @@
+use std::collections::BTreeMap;
+
+use serde_json::json;
+use sqlx::{SqliteConnection, SqlitePool};
+
+use crate::error::{AppError, AppResult};
+use crate::models::{SetSlaTarget, SlaAlert, SlaBreach, SlaReport, SlaSummary, SlaTarget, Ticket, TicketStatus};
+use crate::services::{pending_action, ticket};
+use crate::timestamp;
+
+/// Share of a deadline's window that must pass before a warning is raised.
+const WARN_AT: f64 = 0.8;
+
+/// Longest accepted target, in minutes (90 days).
+const MAX_TARGET_MINUTES: i64 = 90 * 24 * 60;
+
+/// Deadlines the scheduler watches: target name, the column that meets it,
+/// and the deadline column.
+const DEADLINES: [(&str, &str, &str); 2] = [
+    ("first_response", "first_response_at", "first_response_due_at"),
+    ("resolution", "closed_at", "resolution_due_at"),
+];
+
+// ── Targets ──────────────────────────────────────────────────────────────────
+
+/// A guild's SLA targets, most urgent priority first.
+pub async fn get_targets(pool: &SqlitePool, guild_id: &str) -> AppResult<Vec<SlaTarget>> {
+    let mut targets = sqlx::query_as::<_, SlaTarget>("SELECT * FROM ticket_sla_targets WHERE guild_id = ?")
+        .bind(guild_id)
+        .fetch_all(pool)
+        .await?;
+
+    targets.sort_by_key(|t| std::cmp::Reverse(priority_rank(&t.priority)));
+    Ok(targets)
+}
+
+/// Set targets for the given priorities; priorities left out are unchanged.
+///
+/// New deadlines only apply to tickets opened (or re-prioritised) afterwards.
+pub async fn set_targets(pool: &SqlitePool, guild_id: &str, targets: &[SetSlaTarget]) -> AppResult<Vec<SlaTarget>> {
+    for target in targets {
+        if !ticket::PRIORITIES.contains(&target.priority.as_str()) {
+            return Err(AppError::BadRequest(format!(
+                "Invalid priority '{}'. Must be one of: {}",
+                target.priority,
+                ticket::PRIORITIES.join(", ")
+            )));
+        }
+        for minutes in [target.first_response_minutes, target.resolution_minutes].into_iter().flatten() {
+            if !(1..=MAX_TARGET_MINUTES).contains(&minutes) {
+                return Err(AppError::BadRequest(format!(
+                    "SLA targets must be between 1 and {MAX_TARGET_MINUTES} minutes"
+                )));
+            }
+        }
+    }
+
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("INSERT OR IGNORE INTO guilds (id) VALUES (?)")
+        .bind(guild_id)
+        .execute(&mut *tx)
+        .await?;
+
+    for target in targets {
+        if target.first_response_minutes.is_none() && target.resolution_minutes.is_none() {
+            sqlx::query("DELETE FROM ticket_sla_targets WHERE guild_id = ? AND priority = ?")
+                .bind(guild_id)
+                .bind(&target.priority)
+                .execute(&mut *tx)
+                .await?;
+            continue;
+        }
+
+        sqlx::query(
+            "INSERT INTO ticket_sla_targets (guild_id, priority, first_response_minutes, resolution_minutes) \
+             VALUES (?, ?, ?, ?) \
+             ON CONFLICT(guild_id, priority) DO UPDATE SET \
+             first_response_minutes = excluded.first_response_minutes, \
+             resolution_minutes = excluded.resolution_minutes, updated_at = datetime('now')",
+        )
+        .bind(guild_id)
+        .bind(&target.priority)
+        .bind(target.first_response_minutes)
+        .bind(target.resolution_minutes)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    get_targets(pool, guild_id).await
+}
+
+// ── Ticket deadlines ─────────────────────────────────────────────────────────
+
+/// Set a ticket's deadlines from its guild's targets for its priority,
+/// counted from when the ticket was opened.
+pub async fn apply_deadlines(conn: &mut SqliteConnection, ticket_id: i64) -> AppResult<Ticket> {
+    let ticket = sqlx::query_as::<_, Ticket>(
+        "UPDATE tickets SET \
+         first_response_due_at = ( \
+             SELECT datetime(tickets.created_at, '+' || s.first_response_minutes || ' minutes') \
+             FROM ticket_sla_targets s WHERE s.guild_id = tickets.guild_id AND s.priority = tickets.priority \
+         ), \
+         resolution_due_at = ( \
+             SELECT datetime(tickets.created_at, '+' || s.resolution_minutes || ' minutes') \
+             FROM ticket_sla_targets s WHERE s.guild_id = tickets.guild_id AND s.priority = tickets.priority \
+         ) \
+         WHERE id = ? RETURNING *",
+    )
+    .bind(ticket_id)
+    .fetch_optional(conn)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("Ticket {ticket_id} not found")))?;
+
+    Ok(ticket)
+}
+
+/// Record a staff reply. Only the earliest one is kept, so messages can be
+/// ingested out of order.
+pub async fn record_first_response(
+    conn: &mut SqliteConnection,
+    ticket_id: i64,
+    author_id: &str,
+    sent_at: &str,
+) -> AppResult<()> {
+    sqlx::query(
+        "UPDATE tickets SET first_response_at = ?, first_response_by = ? \
+         WHERE id = ? AND (first_response_at IS NULL OR first_response_at > ?)",
+    )
+    .bind(sent_at)
+    .bind(author_id)
+    .bind(ticket_id)
+    .bind(sent_at)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Raise warnings for active tickets past `WARN_AT` of a deadline's window
+/// and breaches for those past the deadline. Each alert is recorded once
+/// per deadline and queued for the bot as an `sla_warning` or `sla_breach`
+/// action. Returns the new alerts.
+pub async fn check_deadlines(pool: &SqlitePool) -> AppResult<Vec<SlaAlert>> {
+    let now = timestamp::format(timestamp::now());
+    let mut raised = Vec::new();
+    let active = TicketStatus::active_sql();
+
+    for (target, met, due) in DEADLINES {
+        let at_risk = sqlx::query_as::<_, Ticket>(&format!(
+            "SELECT * FROM tickets WHERE status IN {active} \
+             AND {met} IS NULL AND {due} IS NOT NULL \
+             AND julianday(?) >= julianday(created_at) + (julianday({due}) - julianday(created_at)) * ? \
+             AND NOT EXISTS ( \
+                 SELECT 1 FROM ticket_sla_alerts a WHERE a.ticket_id = tickets.id AND a.target = ? \
+                 AND a.due_at = tickets.{due} AND a.level = CASE WHEN tickets.{due} <= ? THEN 'breach' ELSE 'warning' END \
+             )"
+        ))
+        .bind(&now)
+        .bind(WARN_AT)
+        .bind(target)
+        .bind(&now)
+        .fetch_all(pool)
+        .await?;
+
+        for t in &at_risk {
+            let due_at = match target {
+                "first_response" => t.first_response_due_at.clone(),
+                _ => t.resolution_due_at.clone(),
+            }
+            .unwrap_or_default();
+            let level = if due_at <= now { "breach" } else { "warning" };
+
+            let mut tx = pool.begin().await?;
+            let alert = sqlx::query_as::<_, SlaAlert>(
+                "INSERT INTO ticket_sla_alerts (ticket_id, guild_id, target, level, due_at) \
+                 VALUES (?, ?, ?, ?, ?) ON CONFLICT DO NOTHING RETURNING *",
+            )
+            .bind(t.id)
+            .bind(&t.guild_id)
+            .bind(target)
+            .bind(level)
+            .bind(&due_at)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+            if let Some(alert) = alert {
+                let payload = json!({
+                    "channel_id": t.channel_id,
+                    "ticket_number": t.number,
+                    "priority": t.priority,
+                    "claimed_by": t.claimed_by,
+                    "target": target,
+                    "due_at": due_at,
+                });
+                pending_action::enqueue_for_ticket(&mut tx, t, &format!("sla_{level}"), &payload).await?;
+                raised.push(alert);
+            }
+            tx.commit().await?;
+        }
+    }
+
+    Ok(raised)
+}
+
+// ── Reporting ────────────────────────────────────────────────────────────────
+
+/// SLA results for a guild's tickets that had deadlines, opened within
+/// `from`..=`to` (normalized timestamps) and optionally of one priority:
+/// per-priority totals and every missed deadline, most overdue first.
+pub async fn report(
+    pool: &SqlitePool,
+    guild_id: &str,
+    from: Option<&str>,
+    to: Option<&str>,
+    priority: Option<&str>,
+) -> AppResult<SlaReport> {
+    let tickets = sqlx::query_as::<_, Ticket>(
+        "SELECT * FROM tickets WHERE guild_id = ? \
+         AND (first_response_due_at IS NOT NULL OR resolution_due_at IS NOT NULL) \
+         AND (? IS NULL OR created_at >= ?) AND (? IS NULL OR created_at <= ?) \
+         AND (? IS NULL OR priority = ?)",
+    )
+    .bind(guild_id)
+    .bind(from)
+    .bind(from)
+    .bind(to)
+    .bind(to)
+    .bind(priority)
+    .bind(priority)
+    .fetch_all(pool)
+    .await?;
+
+    let now = timestamp::now();
+    let mut tallies: BTreeMap<std::cmp::Reverse<usize>, Tally> = BTreeMap::new();
+    let mut breaches = Vec::new();
+
+    for t in &tickets {
+        let tally = tallies
+            .entry(std::cmp::Reverse(priority_rank(&t.priority)))
+            .or_insert_with(|| Tally {
+                summary: SlaSummary { priority: t.priority.clone(), ..Default::default() },
+                ..Default::default()
+            });
+        tally.summary.tickets += 1;
+
+        let checks = [
+            ("first_response", &t.first_response_due_at, &t.first_response_at),
+            ("resolution", &t.resolution_due_at, &t.closed_at),
+        ];
+        for (target, due_at, met_at) in checks {
+            let Some(due) = due_at.as_deref().and_then(timestamp::parse) else {
+                continue;
+            };
+            let met = met_at.as_deref().and_then(timestamp::parse);
+            let (met_count, breached_count) = match target {
+                "first_response" => (&mut tally.summary.first_response_met, &mut tally.summary.first_response_breached),
+                _ => (&mut tally.summary.resolution_met, &mut tally.summary.resolution_breached),
+            };
+
+            match met {
+                Some(met) if met <= due => *met_count += 1,
+                _ if met.unwrap_or(now) > due => {
+                    *breached_count += 1;
+                    breaches.push(SlaBreach {
+                        ticket_id: t.id,
+                        ticket_number: t.number,
+                        priority: t.priority.clone(),
+                        status: t.status,
+                        claimed_by: t.claimed_by.clone(),
+                        target: target.to_string(),
+                        due_at: timestamp::format(due),
+                        met_at: met_at.clone(),
+                        overdue_minutes: (met.unwrap_or(now) - due).num_minutes(),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(minutes) = timestamp::minutes_since(&t.created_at, t.first_response_at.as_deref()) {
+            tally.response_times.push(minutes);
+        }
+        if let Some(minutes) = timestamp::minutes_since(&t.created_at, t.closed_at.as_deref()) {
+            tally.resolution_times.push(minutes);
+        }
+    }
+
+    breaches.sort_by_key(|b| std::cmp::Reverse(b.overdue_minutes));
+    let summary = tallies
+        .into_values()
+        .map(|mut tally| {
+            tally.summary.avg_first_response_minutes = average(&tally.response_times);
+            tally.summary.avg_resolution_minutes = average(&tally.resolution_times);
+            tally.summary
+        })
+        .collect();
+
+    Ok(SlaReport { summary, breaches })
+}
+
+/// Per-priority accumulator for [`report`].
+#[derive(Default)]
+struct Tally {
+    summary: SlaSummary,
+    /// Minutes to first response / resolution, for the averages.
+    response_times: Vec<i64>,
+    resolution_times: Vec<i64>,
+}
+
+/// Position in `ticket::PRIORITIES`, higher meaning more urgent.
+fn priority_rank(priority: &str) -> usize {
+    ticket::PRIORITIES.iter().position(|p| *p == priority).unwrap_or(0)
+}
+
+fn average(values: &[i64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<i64>() as f64 / values.len() as f64)
+}
@@ -0,0 +1,50 @@
+use serde_json::json;
+use sqlx::SqlitePool;
+
+use crate::error::AppResult;
+use crate::models::TicketStatus;
+
+/// Aggregate counters shown on the dashboard overview for a guild.
+pub async fn guild_stats(pool: &SqlitePool, guild_id: &str) -> AppResult<serde_json::Value> {
+    let open_tickets: (i64,) = sqlx::query_as(&format!(
+        "SELECT COUNT(*) FROM tickets WHERE guild_id = ? AND status IN {}",
+        TicketStatus::active_sql()
+    ))
+    .bind(guild_id)
+    .fetch_one(pool)
+    .await?;
+
+    let total_tickets: (i64,) =
+        sqlx::query_as("SELECT COUNT(*) FROM tickets WHERE guild_id = ?")
+            .bind(guild_id)
+            .fetch_one(pool)
+            .await?;
+
+    let total_warns: (i64,) =
+        sqlx::query_as("SELECT COUNT(*) FROM warns WHERE guild_id = ?")
+            .bind(guild_id)
+            .fetch_one(pool)
+            .await?;
+
+    let active_giveaways: (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM giveaways WHERE guild_id = ? AND ended = 0",
+    )
+    .bind(guild_id)
+    .fetch_one(pool)
+    .await?;
+
+    let total_suggestions: (i64,) =
+        sqlx::query_as("SELECT COUNT(*) FROM suggestions WHERE guild_id = ?")
+            .bind(guild_id)
+            .fetch_one(pool)
+            .await?;
+
+    Ok(json!({
+        "guild_id": guild_id,
+        "open_tickets": open_tickets.0,
+        "total_tickets": total_tickets.0,
+        "total_warns": total_warns.0,
+        "active_giveaways": active_giveaways.0,
+        "total_suggestions": total_suggestions.0,
+    }))
+}
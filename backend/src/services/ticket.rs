@@ -1,14 +1,34 @@
-use sqlx::SqlitePool;
+use sqlx::{SqliteConnection, SqlitePool};
 
 use crate::error::{AppError, AppResult};
-use crate::models::{Ticket, TicketBlacklist, TicketCategory};
+use crate::models::{
+    GuildConfig, Ticket, TicketAnswerInput, TicketBlacklist, TicketBlacklistEvent, TicketCategory, TicketDenial, TicketDetail, TicketEvent,
+    TicketStatus, Transcript, TranscriptNames,
+};
+use crate::services::{sla, ticket_form, ticket_routing, transcript};
+use crate::timestamp;
+
+/// Valid ticket priorities, least urgent first.
+pub const PRIORITIES: [&str; 4] = ["low", "normal", "high", "urgent"];
+
+/// Actor recorded for changes the backend makes on its own, such as
+/// inactivity closes, auto-assignment and expired blacklist entries.
+pub const SYSTEM_ACTOR: &str = "system";
 
 // ── Ticket CRUD ──────────────────────────────────────────────────────────────
 
 /// Create a new ticket.
 ///
-/// Atomically increments the guild's `ticket_counter` and inserts a new ticket
-/// row using the new counter value as the ticket number.
+/// The guild's `ticket_counter` is bumped with `UPDATE ... RETURNING` and the
+/// ticket inserted in the same transaction, so concurrent opens always get
+/// distinct numbers. The counter is first raised past any existing ticket
+/// number in case it was lowered from the dashboard. `answers` are checked
+/// against the category's form and stored with the ticket, which is then
+/// assigned per the routing policy.
+///
+/// The open policy ([`can_open`]) runs in the same transaction; a denial is
+/// returned as [`AppError::TicketDenied`]. `role_ids` are the member's roles.
+#[allow(clippy::too_many_arguments)]
 pub async fn create_ticket(
     pool: &SqlitePool,
     guild_id: &str,
@@ -16,23 +36,36 @@ pub async fn create_ticket(
     user_id: &str,
     category: Option<&str>,
     subject: Option<&str>,
-) -> AppResult<Ticket> {
-    // Increment the guild ticket counter and get the new value.
-    sqlx::query("UPDATE guilds SET ticket_counter = ticket_counter + 1, updated_at = datetime('now') WHERE id = ?")
+    answers: &[TicketAnswerInput],
+    role_ids: &[String],
+) -> AppResult<TicketDetail> {
+    let mut tx = pool.begin().await?;
+
+    // Writing first takes the database's write lock, so concurrent opens
+    // by the same user can't both pass the policy
+    sqlx::query("INSERT OR IGNORE INTO guilds (id) VALUES (?)")
         .bind(guild_id)
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
 
-    let counter_row: (i64,) =
-        sqlx::query_as("SELECT ticket_counter FROM guilds WHERE id = ?")
-            .bind(guild_id)
-            .fetch_one(pool)
-            .await?;
-    let number = counter_row.0;
+    if let Some(denial) = can_open(&mut tx, guild_id, user_id, category, role_ids).await? {
+        return Err(AppError::TicketDenied(denial));
+    }
 
-    sqlx::query(
+    let number = sqlx::query_scalar::<_, i64>(
+        "UPDATE guilds SET ticket_counter = MAX( \
+             ticket_counter, \
+             (SELECT COALESCE(MAX(number), 0) FROM tickets WHERE guild_id = guilds.id) \
+         ) + 1, updated_at = datetime('now') \
+         WHERE id = ? RETURNING ticket_counter",
+    )
+    .bind(guild_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let ticket = sqlx::query_as::<_, Ticket>(
         "INSERT INTO tickets (number, channel_id, user_id, guild_id, category, subject) \
-         VALUES (?, ?, ?, ?, ?, ?)",
+         VALUES (?, ?, ?, ?, ?, ?) RETURNING *",
     )
     .bind(number)
     .bind(channel_id)
@@ -40,55 +73,146 @@ pub async fn create_ticket(
     .bind(guild_id)
     .bind(category)
     .bind(subject)
-    .execute(pool)
-    .await?;
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(ref db_err) if db_err.message().contains("UNIQUE") => {
+            AppError::BadRequest(format!("A ticket already exists for channel {channel_id}"))
+        }
+        other => AppError::Database(other),
+    })?;
+
+    let ticket = sla::apply_deadlines(&mut tx, ticket.id).await?;
+    let answers = ticket_form::record_answers(&mut tx, &ticket, answers).await?;
+    record_event(&mut tx, &ticket, "created", None, Some(TicketStatus::Open.as_str()), user_id, None).await?;
+    let ticket = ticket_routing::auto_assign(&mut tx, ticket).await?;
+
+    tx.commit().await?;
+    Ok(TicketDetail { ticket, answers })
+}
 
-    let ticket = sqlx::query_as::<_, Ticket>(
-        "SELECT id, number, channel_id, user_id, guild_id, category, subject, \
-         status, priority, claimed_by, closed_by, closed_at, review, review_rating, \
-         last_activity, created_at \
-         FROM tickets WHERE id = last_insert_rowid()",
-    )
-    .fetch_one(pool)
-    .await?;
+/// Move a ticket to a new status, enforcing the allowed transitions and
+/// recording the change in `ticket_events`.
+///
+/// Closing stamps `closed_by`/`closed_at` and renders a transcript from the
+/// ticket's messages; moving a closed ticket back to an active state (a
+/// reopen) clears them.
+pub async fn transition(
+    pool: &SqlitePool,
+    ticket_id: i64,
+    next: TicketStatus,
+    actor_id: &str,
+    note: Option<&str>,
+) -> AppResult<Ticket> {
+    let mut tx = pool.begin().await?;
+    let ticket = apply_transition(&mut tx, ticket_id, next, actor_id, note).await?;
+    if next == TicketStatus::Closed {
+        transcript::generate(&mut tx, &ticket, &TranscriptNames::default()).await?;
+    }
 
+    tx.commit().await?;
     Ok(ticket)
 }
 
-/// Close a ticket by setting its status to `closed`.
+/// Close a ticket and render its transcript in the same transaction.
+///
+/// `names` supplies display names the bot knows; missing ones fall back to
+/// what the ticket's messages recorded.
 pub async fn close_ticket(
     pool: &SqlitePool,
     ticket_id: i64,
     closed_by: &str,
+    names: &TranscriptNames,
+) -> AppResult<(Ticket, Transcript)> {
+    let mut tx = pool.begin().await?;
+    let ticket = apply_transition(&mut tx, ticket_id, TicketStatus::Closed, closed_by, None).await?;
+    let transcript = transcript::generate(&mut tx, &ticket, names).await?;
+
+    tx.commit().await?;
+    Ok((ticket, transcript))
+}
+
+async fn apply_transition(
+    conn: &mut SqliteConnection,
+    ticket_id: i64,
+    next: TicketStatus,
+    actor_id: &str,
+    note: Option<&str>,
 ) -> AppResult<Ticket> {
-    let result = sqlx::query(
-        "UPDATE tickets SET status = 'closed', closed_by = ?, closed_at = datetime('now'), \
-         last_activity = datetime('now') WHERE id = ?",
+    let ticket = fetch_ticket(conn, ticket_id).await?;
+
+    if !ticket.status.can_transition_to(next) {
+        return Err(AppError::BadRequest(format!(
+            "Cannot move ticket {ticket_id} from {} to {next}",
+            ticket.status
+        )));
+    }
+
+    // Guard on the current status so a concurrent transition cannot be lost
+    let updated = sqlx::query_as::<_, Ticket>(
+        "UPDATE tickets SET status = ?, \
+         closed_by = CASE WHEN ? = 'closed' THEN ? WHEN ? THEN NULL ELSE closed_by END, \
+         closed_at = CASE WHEN ? = 'closed' THEN datetime('now') WHEN ? THEN NULL ELSE closed_at END, \
+         last_activity = datetime('now'), inactivity_warned_at = NULL \
+         WHERE id = ? AND status = ? RETURNING *",
     )
-    .bind(closed_by)
+    .bind(next)
+    .bind(next)
+    .bind(actor_id)
+    .bind(next.is_active())
+    .bind(next)
+    .bind(next.is_active())
     .bind(ticket_id)
-    .execute(pool)
+    .bind(ticket.status)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| AppError::BadRequest(format!("Ticket {ticket_id} was changed concurrently, retry")))?;
+
+    record_event(
+        conn,
+        &updated,
+        "status",
+        Some(ticket.status.as_str()),
+        Some(next.as_str()),
+        actor_id,
+        note,
+    )
     .await?;
 
-    if result.rows_affected() == 0 {
-        return Err(AppError::NotFound(format!("Ticket {ticket_id} not found")));
-    }
+    Ok(updated)
+}
 
-    get_ticket(pool, ticket_id).await
+/// Reopen a closed or reviewed ticket.
+pub async fn reopen_ticket(pool: &SqlitePool, ticket_id: i64, actor_id: &str, note: Option<&str>) -> AppResult<Ticket> {
+    transition(pool, ticket_id, TicketStatus::Open, actor_id, note).await
 }
 
 /// Fetch a single ticket by ID.
 pub async fn get_ticket(pool: &SqlitePool, ticket_id: i64) -> AppResult<Ticket> {
+    let mut conn = pool.acquire().await?;
+    fetch_ticket(&mut conn, ticket_id).await
+}
+
+/// The latest ticket opened in a Discord channel, whatever its status.
+pub async fn find_by_channel(pool: &SqlitePool, channel_id: &str) -> AppResult<Option<Ticket>> {
     let ticket = sqlx::query_as::<_, Ticket>(
-        "SELECT id, number, channel_id, user_id, guild_id, category, subject, \
-         status, priority, claimed_by, closed_by, closed_at, review, review_rating, \
-         last_activity, created_at \
-         FROM tickets WHERE id = ?",
+        "SELECT * FROM tickets WHERE channel_id = ? ORDER BY id DESC LIMIT 1",
     )
-    .bind(ticket_id)
+    .bind(channel_id)
     .fetch_optional(pool)
-    .await?
-    .ok_or_else(|| AppError::NotFound(format!("Ticket {ticket_id} not found")))?;
+    .await?;
+
+    Ok(ticket)
+}
+
+/// Fetch a ticket by ID, 404ing unless it belongs to `guild_id`.
+pub async fn get_guild_ticket(pool: &SqlitePool, guild_id: &str, ticket_id: i64) -> AppResult<Ticket> {
+    let ticket = sqlx::query_as::<_, Ticket>("SELECT * FROM tickets WHERE guild_id = ? AND id = ?")
+        .bind(guild_id)
+        .bind(ticket_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Ticket {ticket_id} not found")))?;
 
     Ok(ticket)
 }
@@ -96,87 +220,186 @@ pub async fn get_ticket(pool: &SqlitePool, ticket_id: i64) -> AppResult<Ticket>
 /// List tickets for a guild with optional status filter and pagination.
 ///
 /// * `status` - If `Some`, only tickets with this status are returned.
+/// * `tags`   - Only tickets carrying all of these tag names.
 /// * `limit`  - Maximum number of rows (default 50).
 /// * `offset` - Number of rows to skip (default 0).
 pub async fn list_tickets(
     pool: &SqlitePool,
     guild_id: &str,
-    status: Option<&str>,
+    status: Option<TicketStatus>,
+    tags: &[String],
     limit: i64,
     offset: i64,
 ) -> AppResult<Vec<Ticket>> {
-    let tickets = if let Some(s) = status {
-        sqlx::query_as::<_, Ticket>(
-            "SELECT id, number, channel_id, user_id, guild_id, category, subject, \
-             status, priority, claimed_by, closed_by, closed_at, review, review_rating, \
-             last_activity, created_at \
-             FROM tickets WHERE guild_id = ? AND status = ? \
-             ORDER BY created_at DESC LIMIT ? OFFSET ?",
-        )
-        .bind(guild_id)
-        .bind(s)
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(pool)
-        .await?
-    } else {
-        sqlx::query_as::<_, Ticket>(
-            "SELECT id, number, channel_id, user_id, guild_id, category, subject, \
-             status, priority, claimed_by, closed_by, closed_at, review, review_rating, \
-             last_activity, created_at \
-             FROM tickets WHERE guild_id = ? \
-             ORDER BY created_at DESC LIMIT ? OFFSET ?",
-        )
-        .bind(guild_id)
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(pool)
-        .await?
-    };
+    let tags = tags_filter(tags);
+    let tickets = sqlx::query_as::<_, Ticket>(&format!(
+        "SELECT * FROM tickets WHERE guild_id = ? AND (? IS NULL OR status = ?) AND {HAS_TAGS} \
+         ORDER BY created_at DESC LIMIT ? OFFSET ?"
+    ))
+    .bind(guild_id)
+    .bind(status)
+    .bind(status)
+    .bind(&tags)
+    .bind(&tags)
+    .bind(&tags)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
 
     Ok(tickets)
 }
 
+/// Count of the tickets [`list_tickets`] pages through.
+pub async fn count_tickets(
+    pool: &SqlitePool,
+    guild_id: &str,
+    status: Option<TicketStatus>,
+    tags: &[String],
+) -> AppResult<i64> {
+    let tags = tags_filter(tags);
+    let total = sqlx::query_scalar(&format!(
+        "SELECT COUNT(*) FROM tickets WHERE guild_id = ? AND (? IS NULL OR status = ?) AND {HAS_TAGS}"
+    ))
+    .bind(guild_id)
+    .bind(status)
+    .bind(status)
+    .bind(&tags)
+    .bind(&tags)
+    .bind(&tags)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(total)
+}
+
+/// Matches tickets carrying every tag named in a JSON array bound three
+/// times; a NULL binding matches everything.
+const HAS_TAGS: &str = "(? IS NULL OR ( \
+     SELECT COUNT(*) FROM ticket_tag_links l JOIN ticket_tags t ON t.id = l.tag_id \
+     WHERE l.ticket_id = tickets.id AND t.name IN (SELECT value FROM json_each(?)) \
+ ) = json_array_length(?))";
+
+/// Tag names trimmed, folded the way `COLLATE NOCASE` folds them and
+/// deduplicated, so the count compared in [`HAS_TAGS`] is of distinct tags.
+fn tags_filter(tags: &[String]) -> Option<String> {
+    let mut names: Vec<String> = tags
+        .iter()
+        .map(|t| t.trim().to_ascii_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect();
+    names.sort();
+    names.dedup();
+    (!names.is_empty()).then(|| serde_json::Value::from(names).to_string())
+}
+
 /// Update the priority of a ticket.
 pub async fn update_priority(
     pool: &SqlitePool,
     ticket_id: i64,
     priority: &str,
+    actor_id: &str,
 ) -> AppResult<Ticket> {
-    let result = sqlx::query(
-        "UPDATE tickets SET priority = ?, last_activity = datetime('now') WHERE id = ?",
+    if !PRIORITIES.contains(&priority) {
+        return Err(AppError::BadRequest(format!(
+            "Invalid priority '{priority}'. Must be one of: {}",
+            PRIORITIES.join(", ")
+        )));
+    }
+
+    let mut tx = pool.begin().await?;
+    let before = fetch_ticket(&mut tx, ticket_id).await?;
+
+    let mut ticket = sqlx::query_as::<_, Ticket>(
+        "UPDATE tickets SET priority = ?, last_activity = datetime('now'), inactivity_warned_at = NULL \
+         WHERE id = ? RETURNING *",
     )
     .bind(priority)
     .bind(ticket_id)
-    .execute(pool)
+    .fetch_one(&mut *tx)
     .await?;
 
-    if result.rows_affected() == 0 {
-        return Err(AppError::NotFound(format!("Ticket {ticket_id} not found")));
+    if before.priority != ticket.priority {
+        ticket = sla::apply_deadlines(&mut tx, ticket_id).await?;
+        record_event(&mut tx, &ticket, "priority", Some(&before.priority), Some(priority), actor_id, None).await?;
     }
 
-    get_ticket(pool, ticket_id).await
+    tx.commit().await?;
+    Ok(ticket)
 }
 
-/// Claim a ticket (assign a staff member).
+/// Claim a ticket (assign a staff member). Fails if someone already holds it.
 pub async fn claim_ticket(
     pool: &SqlitePool,
     ticket_id: i64,
     claimer_id: &str,
 ) -> AppResult<Ticket> {
-    let result = sqlx::query(
-        "UPDATE tickets SET claimed_by = ?, last_activity = datetime('now') WHERE id = ?",
+    let mut tx = pool.begin().await?;
+    fetch_ticket(&mut tx, ticket_id).await?;
+
+    let ticket = sqlx::query_as::<_, Ticket>(
+        "UPDATE tickets SET claimed_by = ?, last_activity = datetime('now'), inactivity_warned_at = NULL \
+         WHERE id = ? AND claimed_by IS NULL RETURNING *",
     )
     .bind(claimer_id)
     .bind(ticket_id)
-    .execute(pool)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::BadRequest(format!("Ticket {ticket_id} is already claimed")))?;
+
+    record_event(&mut tx, &ticket, "claim", None, Some(claimer_id), claimer_id, None).await?;
+
+    tx.commit().await?;
+    Ok(ticket)
+}
+
+/// A ticket's history, oldest first.
+pub async fn get_history(pool: &SqlitePool, ticket_id: i64) -> AppResult<Vec<TicketEvent>> {
+    let events = sqlx::query_as::<_, TicketEvent>(
+        "SELECT * FROM ticket_events WHERE ticket_id = ? ORDER BY id ASC",
+    )
+    .bind(ticket_id)
+    .fetch_all(pool)
     .await?;
 
-    if result.rows_affected() == 0 {
-        return Err(AppError::NotFound(format!("Ticket {ticket_id} not found")));
-    }
+    Ok(events)
+}
 
-    get_ticket(pool, ticket_id).await
+async fn fetch_ticket(conn: &mut SqliteConnection, ticket_id: i64) -> AppResult<Ticket> {
+    let ticket = sqlx::query_as::<_, Ticket>("SELECT * FROM tickets WHERE id = ?")
+        .bind(ticket_id)
+        .fetch_optional(conn)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Ticket {ticket_id} not found")))?;
+
+    Ok(ticket)
+}
+
+/// Append an entry to a ticket's history.
+pub async fn record_event(
+    conn: &mut SqliteConnection,
+    ticket: &Ticket,
+    event_type: &str,
+    from_value: Option<&str>,
+    to_value: Option<&str>,
+    actor_id: &str,
+    note: Option<&str>,
+) -> AppResult<()> {
+    sqlx::query(
+        "INSERT INTO ticket_events (ticket_id, guild_id, event_type, from_value, to_value, actor_id, note) \
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(ticket.id)
+    .bind(&ticket.guild_id)
+    .bind(event_type)
+    .bind(from_value)
+    .bind(to_value)
+    .bind(actor_id)
+    .bind(note)
+    .execute(conn)
+    .await?;
+
+    Ok(())
 }
 
 // ── Ticket Categories ────────────────────────────────────────────────────────
@@ -184,8 +407,7 @@ pub async fn claim_ticket(
 /// List all ticket categories for a guild.
 pub async fn get_categories(pool: &SqlitePool, guild_id: &str) -> AppResult<Vec<TicketCategory>> {
     let categories = sqlx::query_as::<_, TicketCategory>(
-        "SELECT id, guild_id, name, emoji, description, created_at \
-         FROM ticket_categories WHERE guild_id = ? ORDER BY name ASC",
+        "SELECT * FROM ticket_categories WHERE guild_id = ? ORDER BY name ASC",
     )
     .bind(guild_id)
     .fetch_all(pool)
@@ -213,8 +435,7 @@ pub async fn add_category(
     .await?;
 
     let category = sqlx::query_as::<_, TicketCategory>(
-        "SELECT id, guild_id, name, emoji, description, created_at \
-         FROM ticket_categories WHERE id = last_insert_rowid()",
+        "SELECT * FROM ticket_categories WHERE id = last_insert_rowid()",
     )
     .fetch_one(pool)
     .await?;
@@ -244,8 +465,140 @@ pub async fn delete_category(
     Ok(())
 }
 
+// ── Open Policy ──────────────────────────────────────────────────────────────
+
+/// Whether `user_id` may open a ticket in `category`, returning the first
+/// rule that refuses: blacklist, required roles, the guild's and then the
+/// category's limit on active tickets, and the cooldown after their last
+/// close. `role_ids` are the member's roles as the bot sees them.
+pub async fn can_open(
+    conn: &mut SqliteConnection,
+    guild_id: &str,
+    user_id: &str,
+    category: Option<&str>,
+    role_ids: &[String],
+) -> AppResult<Option<TicketDenial>> {
+    let blacklisted = sqlx::query_as::<_, TicketBlacklist>(&format!(
+        "SELECT * FROM ticket_blacklists WHERE guild_id = ? AND user_id = ? AND {BLACKLIST_ACTIVE}"
+    ))
+    .bind(guild_id)
+    .bind(user_id)
+    .fetch_optional(&mut *conn)
+    .await?;
+    if let Some(entry) = blacklisted {
+        return Ok(Some(TicketDenial::Blacklisted { note: entry.reason }));
+    }
+
+    let Some(guild) = sqlx::query_as::<_, GuildConfig>("SELECT * FROM guilds WHERE id = ?")
+        .bind(guild_id)
+        .fetch_optional(&mut *conn)
+        .await?
+    else {
+        return Ok(None);
+    };
+    let category = match category {
+        Some(name) => {
+            sqlx::query_as::<_, TicketCategory>("SELECT * FROM ticket_categories WHERE guild_id = ? AND name = ?")
+                .bind(guild_id)
+                .bind(name)
+                .fetch_optional(&mut *conn)
+                .await?
+        }
+        None => None,
+    };
+
+    let required = [guild.ticket_required_role, category.as_ref().and_then(|c| c.required_role.clone())];
+    if let Some(role_id) = required.into_iter().flatten().find(|r| !role_ids.contains(r)) {
+        return Ok(Some(TicketDenial::MissingRole { role_id }));
+    }
+
+    if let Some(limit) = guild.ticket_max_open {
+        let open = active_count(conn, guild_id, user_id, None).await?;
+        if open >= limit {
+            return Ok(Some(TicketDenial::TooManyOpen { limit }));
+        }
+    }
+    if let Some((name, limit)) = category.as_ref().and_then(|c| Some((&c.name, c.max_open?))) {
+        let open = active_count(conn, guild_id, user_id, Some(name)).await?;
+        if open >= limit {
+            return Ok(Some(TicketDenial::CategoryLimit { category: name.clone(), limit }));
+        }
+    }
+
+    if let Some(minutes) = guild.ticket_cooldown_minutes {
+        let retry_at = sqlx::query_scalar::<_, Option<String>>(
+            "SELECT datetime(MAX(closed_at), '+' || ? || ' minutes') FROM tickets \
+             WHERE guild_id = ? AND user_id = ? AND closed_at IS NOT NULL",
+        )
+        .bind(minutes)
+        .bind(guild_id)
+        .bind(user_id)
+        .fetch_one(&mut *conn)
+        .await?;
+        if let Some(retry_at) = retry_at.filter(|r| timestamp::parse(r).is_some_and(|r| r > timestamp::now())) {
+            return Ok(Some(TicketDenial::Cooldown { retry_at }));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Set a category's open rules; `None` leaves a rule off.
+pub async fn set_category_limits(
+    pool: &SqlitePool,
+    guild_id: &str,
+    category_id: i64,
+    max_open: Option<i64>,
+    required_role: Option<&str>,
+) -> AppResult<TicketCategory> {
+    if max_open.is_some_and(|m| m < 1) {
+        return Err(AppError::BadRequest("max_open must be a positive integer or null".to_string()));
+    }
+
+    sqlx::query_as(
+        "UPDATE ticket_categories SET max_open = ?, required_role = ? \
+         WHERE id = ? AND guild_id = ? RETURNING *",
+    )
+    .bind(max_open)
+    .bind(required_role.filter(|r| !r.is_empty()))
+    .bind(category_id)
+    .bind(guild_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("Category {category_id} not found")))
+}
+
+/// The user's active tickets, optionally only those in `category`.
+async fn active_count(
+    conn: &mut SqliteConnection,
+    guild_id: &str,
+    user_id: &str,
+    category: Option<&str>,
+) -> AppResult<i64> {
+    let count = sqlx::query_scalar(&format!(
+        "SELECT COUNT(*) FROM tickets WHERE guild_id = ? AND user_id = ? \
+         AND status IN {} AND (? IS NULL OR category = ?)",
+        TicketStatus::active_sql()
+    ))
+    .bind(guild_id)
+    .bind(user_id)
+    .bind(category)
+    .bind(category)
+    .fetch_one(conn)
+    .await?;
+
+    Ok(count)
+}
+
 // ── Ticket Blacklist ─────────────────────────────────────────────────────────
 
+/// Entries in force: permanent, or not yet past `expires_at`. Expired ones
+/// stop counting before the scheduler gets to them.
+const BLACKLIST_ACTIVE: &str = "(expires_at IS NULL OR expires_at > datetime('now'))";
+/// Blacklist search, bound four times: user, moderator, or reason (any case).
+const BLACKLIST_SEARCH: &str = "(? IS NULL OR instr(user_id, ?) > 0 OR instr(added_by, ?) > 0 \
+     OR instr(lower(reason), lower(?)) > 0)";
+
 /// Check whether a user is blacklisted from tickets in a guild.
 /// Returns `Some(entry)` if blacklisted, `None` otherwise.
 pub async fn check_blacklist(
@@ -253,10 +606,9 @@ pub async fn check_blacklist(
     guild_id: &str,
     user_id: &str,
 ) -> AppResult<Option<TicketBlacklist>> {
-    let entry = sqlx::query_as::<_, TicketBlacklist>(
-        "SELECT id, guild_id, user_id, reason, added_by, created_at \
-         FROM ticket_blacklists WHERE guild_id = ? AND user_id = ?",
-    )
+    let entry = sqlx::query_as::<_, TicketBlacklist>(&format!(
+        "SELECT * FROM ticket_blacklists WHERE guild_id = ? AND user_id = ? AND {BLACKLIST_ACTIVE}"
+    ))
     .bind(guild_id)
     .bind(user_id)
     .fetch_optional(pool)
@@ -265,53 +617,204 @@ pub async fn check_blacklist(
     Ok(entry)
 }
 
-/// Add a user to the ticket blacklist.
+/// A page of a guild's blacklist, newest first. `search` matches user IDs,
+/// the moderator who added the entry, or the reason.
+pub async fn list_blacklist(
+    pool: &SqlitePool,
+    guild_id: &str,
+    search: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> AppResult<Vec<TicketBlacklist>> {
+    let entries = sqlx::query_as::<_, TicketBlacklist>(&format!(
+        "SELECT * FROM ticket_blacklists WHERE guild_id = ? AND {BLACKLIST_SEARCH} \
+         ORDER BY created_at DESC, id DESC LIMIT ? OFFSET ?"
+    ))
+    .bind(guild_id)
+    .bind(search)
+    .bind(search)
+    .bind(search)
+    .bind(search)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(entries)
+}
+
+/// Total number of entries matching [`list_blacklist`]'s filter.
+pub async fn count_blacklist(pool: &SqlitePool, guild_id: &str, search: Option<&str>) -> AppResult<i64> {
+    let total = sqlx::query_scalar(&format!(
+        "SELECT COUNT(*) FROM ticket_blacklists WHERE guild_id = ? AND {BLACKLIST_SEARCH}"
+    ))
+    .bind(guild_id)
+    .bind(search)
+    .bind(search)
+    .bind(search)
+    .bind(search)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(total)
+}
+
+/// Add a user to the ticket blacklist, until `expires_at` (normalized) if
+/// given.
 pub async fn add_blacklist(
     pool: &SqlitePool,
     guild_id: &str,
     user_id: &str,
     reason: Option<&str>,
+    expires_at: Option<&str>,
     added_by: &str,
 ) -> AppResult<TicketBlacklist> {
-    sqlx::query(
-        "INSERT INTO ticket_blacklists (guild_id, user_id, reason, added_by) \
-         VALUES (?, ?, ?, ?)",
+    let mut tx = pool.begin().await?;
+
+    // An entry that has lapsed but not been lifted yet is lifted first
+    let lapsed = sqlx::query_as::<_, TicketBlacklist>(&format!(
+        "SELECT * FROM ticket_blacklists WHERE guild_id = ? AND user_id = ? AND NOT {BLACKLIST_ACTIVE}"
+    ))
+    .bind(guild_id)
+    .bind(user_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    if let Some(entry) = lapsed {
+        lift_blacklist(&mut tx, &entry, "expire", None, SYSTEM_ACTOR).await?;
+    }
+
+    let entry = sqlx::query_as::<_, TicketBlacklist>(
+        "INSERT INTO ticket_blacklists (guild_id, user_id, reason, added_by, expires_at) \
+         VALUES (?, ?, ?, ?, ?) RETURNING *",
     )
     .bind(guild_id)
     .bind(user_id)
     .bind(reason)
     .bind(added_by)
-    .execute(pool)
+    .bind(expires_at)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(ref db_err) if db_err.message().contains("UNIQUE") => {
+            AppError::BadRequest("User is already blacklisted".to_string())
+        }
+        other => AppError::Database(other),
+    })?;
+
+    record_blacklist_event(&mut tx, &entry, "add", reason, added_by, expires_at).await?;
+
+    tx.commit().await?;
+    Ok(entry)
+}
+
+/// Remove an entry from the ticket blacklist, keeping who removed it and
+/// why in the history.
+pub async fn remove_blacklist(
+    pool: &SqlitePool,
+    guild_id: &str,
+    entry_id: i64,
+    removed_by: &str,
+    reason: Option<&str>,
+) -> AppResult<TicketBlacklist> {
+    let mut tx = pool.begin().await?;
+    let entry = sqlx::query_as::<_, TicketBlacklist>("SELECT * FROM ticket_blacklists WHERE id = ? AND guild_id = ?")
+        .bind(entry_id)
+        .bind(guild_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Blacklist entry {entry_id} not found")))?;
+
+    lift_blacklist(&mut tx, &entry, "remove", reason, removed_by).await?;
+
+    tx.commit().await?;
+    Ok(entry)
+}
+
+/// Lift every blacklist entry past its `expires_at`. Returns the lifted
+/// entries.
+pub async fn lift_expired_blacklists(pool: &SqlitePool) -> AppResult<Vec<TicketBlacklist>> {
+    let mut tx = pool.begin().await?;
+    let expired = sqlx::query_as::<_, TicketBlacklist>(&format!(
+        "SELECT * FROM ticket_blacklists WHERE NOT {BLACKLIST_ACTIVE} ORDER BY expires_at ASC"
+    ))
+    .fetch_all(&mut *tx)
     .await?;
 
-    let entry = sqlx::query_as::<_, TicketBlacklist>(
-        "SELECT id, guild_id, user_id, reason, added_by, created_at \
-         FROM ticket_blacklists WHERE id = last_insert_rowid()",
+    for entry in &expired {
+        lift_blacklist(&mut tx, entry, "expire", None, SYSTEM_ACTOR).await?;
+    }
+
+    tx.commit().await?;
+    Ok(expired)
+}
+
+/// A guild's blacklist history, newest first, optionally for one user.
+pub async fn blacklist_history(
+    pool: &SqlitePool,
+    guild_id: &str,
+    user_id: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> AppResult<(Vec<TicketBlacklistEvent>, i64)> {
+    let events = sqlx::query_as::<_, TicketBlacklistEvent>(
+        "SELECT * FROM ticket_blacklist_events WHERE guild_id = ? AND (? IS NULL OR user_id = ?) \
+         ORDER BY created_at DESC, id DESC LIMIT ? OFFSET ?",
+    )
+    .bind(guild_id)
+    .bind(user_id)
+    .bind(user_id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+
+    let total = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM ticket_blacklist_events WHERE guild_id = ? AND (? IS NULL OR user_id = ?)",
     )
+    .bind(guild_id)
+    .bind(user_id)
+    .bind(user_id)
     .fetch_one(pool)
     .await?;
 
-    Ok(entry)
+    Ok((events, total))
 }
 
-/// Remove a user from the ticket blacklist.
-pub async fn remove_blacklist(
-    pool: &SqlitePool,
-    guild_id: &str,
-    user_id: &str,
+async fn lift_blacklist(
+    conn: &mut SqliteConnection,
+    entry: &TicketBlacklist,
+    action: &str,
+    reason: Option<&str>,
+    actor_id: &str,
 ) -> AppResult<()> {
-    let result =
-        sqlx::query("DELETE FROM ticket_blacklists WHERE guild_id = ? AND user_id = ?")
-            .bind(guild_id)
-            .bind(user_id)
-            .execute(pool)
-            .await?;
+    sqlx::query("DELETE FROM ticket_blacklists WHERE id = ?")
+        .bind(entry.id)
+        .execute(&mut *conn)
+        .await?;
 
-    if result.rows_affected() == 0 {
-        return Err(AppError::NotFound(format!(
-            "User {user_id} is not blacklisted in guild {guild_id}"
-        )));
-    }
+    record_blacklist_event(conn, entry, action, reason, actor_id, None).await
+}
+
+async fn record_blacklist_event(
+    conn: &mut SqliteConnection,
+    entry: &TicketBlacklist,
+    action: &str,
+    reason: Option<&str>,
+    actor_id: &str,
+    expires_at: Option<&str>,
+) -> AppResult<()> {
+    sqlx::query(
+        "INSERT INTO ticket_blacklist_events (guild_id, user_id, action, reason, actor_id, expires_at) \
+         VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&entry.guild_id)
+    .bind(&entry.user_id)
+    .bind(action)
+    .bind(reason)
+    .bind(actor_id)
+    .bind(expires_at)
+    .execute(conn)
+    .await?;
 
     Ok(())
 }
@@ -0,0 +1,210 @@
+use std::collections::HashSet;
+
+use sqlx::{SqliteConnection, SqlitePool};
+
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    SetTicketFormQuestion, Ticket, TicketAnswerInput, TicketFormAnswer, TicketFormQuestion,
+};
+
+/// Discord modals hold at most five text inputs.
+pub const MAX_QUESTIONS: usize = 5;
+/// Discord's limits on text input labels, placeholders and values.
+const MAX_LABEL: usize = 45;
+const MAX_PLACEHOLDER: usize = 100;
+const MAX_LENGTH: i64 = 4000;
+
+/// A category's form questions in order.
+pub async fn get_form(pool: &SqlitePool, guild_id: &str, category_id: i64) -> AppResult<Vec<TicketFormQuestion>> {
+    let conn = &mut *pool.acquire().await?;
+    ensure_category(conn, guild_id, category_id).await?;
+    questions(conn, category_id).await
+}
+
+/// Replace a category's form. An empty list removes the form, leaving the
+/// guild's single subject field. Answers to removed questions are kept.
+///
+/// Questions are updated in place by position, so a question keeps its ID
+/// across edits and modals opened before the save still submit valid IDs.
+pub async fn set_form(
+    pool: &SqlitePool,
+    guild_id: &str,
+    category_id: i64,
+    form: &[SetTicketFormQuestion],
+) -> AppResult<Vec<TicketFormQuestion>> {
+    validate_form(form)?;
+
+    let mut tx = pool.begin().await?;
+    ensure_category(&mut tx, guild_id, category_id).await?;
+
+    for (position, q) in form.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO ticket_form_questions \
+             (category_id, position, label, style, required, min_length, max_length, placeholder) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?) \
+             ON CONFLICT(category_id, position) DO UPDATE SET \
+             label = excluded.label, style = excluded.style, required = excluded.required, \
+             min_length = excluded.min_length, max_length = excluded.max_length, \
+             placeholder = excluded.placeholder",
+        )
+        .bind(category_id)
+        .bind(position as i64)
+        .bind(q.label.trim())
+        .bind(q.style)
+        .bind(q.required)
+        .bind(q.min_length)
+        .bind(q.max_length)
+        .bind(q.placeholder.as_deref().map(str::trim).filter(|p| !p.is_empty()))
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query("DELETE FROM ticket_form_questions WHERE category_id = ? AND position >= ?")
+        .bind(category_id)
+        .bind(form.len() as i64)
+        .execute(&mut *tx)
+        .await?;
+
+    let saved = questions(&mut tx, category_id).await?;
+    tx.commit().await?;
+    Ok(saved)
+}
+
+/// Check and store the answers given when `ticket` was opened against its
+/// category's form. Blank optional answers are skipped.
+pub async fn record_answers(
+    conn: &mut SqliteConnection,
+    ticket: &Ticket,
+    answers: &[TicketAnswerInput],
+) -> AppResult<Vec<TicketFormAnswer>> {
+    let form = match &ticket.category {
+        Some(category) => {
+            sqlx::query_as::<_, TicketFormQuestion>(
+                "SELECT q.* FROM ticket_form_questions q \
+                 JOIN ticket_categories c ON c.id = q.category_id \
+                 WHERE c.guild_id = ? AND c.name = ? ORDER BY q.position ASC",
+            )
+            .bind(&ticket.guild_id)
+            .bind(category)
+            .fetch_all(&mut *conn)
+            .await?
+        }
+        None => Vec::new(),
+    };
+
+    let mut seen = HashSet::new();
+    for answer in answers {
+        if !form.iter().any(|q| q.id == answer.question_id) {
+            return Err(AppError::BadRequest(format!("Unknown question {}", answer.question_id)));
+        }
+        if !seen.insert(answer.question_id) {
+            return Err(AppError::BadRequest(format!("Question {} answered twice", answer.question_id)));
+        }
+    }
+
+    let mut stored = Vec::new();
+    for q in &form {
+        let value = answers
+            .iter()
+            .find(|a| a.question_id == q.id)
+            .map(|a| a.value.trim())
+            .unwrap_or_default();
+        if value.is_empty() {
+            if q.required {
+                return Err(AppError::BadRequest(format!("'{}' is required", q.label)));
+            }
+            continue;
+        }
+
+        let len = value.chars().count() as i64;
+        let max = q.max_length.unwrap_or(MAX_LENGTH);
+        if len > max || q.min_length.is_some_and(|min| len < min) {
+            return Err(AppError::BadRequest(format!(
+                "'{}' must be {} to {max} characters",
+                q.label,
+                q.min_length.unwrap_or(1)
+            )));
+        }
+
+        let answer = sqlx::query_as::<_, TicketFormAnswer>(
+            "INSERT INTO ticket_form_answers (ticket_id, question_id, position, label, value) \
+             VALUES (?, ?, ?, ?, ?) RETURNING *",
+        )
+        .bind(ticket.id)
+        .bind(q.id)
+        .bind(q.position)
+        .bind(&q.label)
+        .bind(value)
+        .fetch_one(&mut *conn)
+        .await?;
+        stored.push(answer);
+    }
+
+    Ok(stored)
+}
+
+/// Answers given when a ticket was opened, in form order.
+pub async fn get_answers(pool: &SqlitePool, ticket_id: i64) -> AppResult<Vec<TicketFormAnswer>> {
+    let answers = sqlx::query_as(
+        "SELECT * FROM ticket_form_answers WHERE ticket_id = ? ORDER BY position ASC",
+    )
+    .bind(ticket_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(answers)
+}
+
+async fn questions(conn: &mut SqliteConnection, category_id: i64) -> AppResult<Vec<TicketFormQuestion>> {
+    let questions = sqlx::query_as(
+        "SELECT * FROM ticket_form_questions WHERE category_id = ? ORDER BY position ASC",
+    )
+    .bind(category_id)
+    .fetch_all(conn)
+    .await?;
+
+    Ok(questions)
+}
+
+async fn ensure_category(conn: &mut SqliteConnection, guild_id: &str, category_id: i64) -> AppResult<()> {
+    sqlx::query_scalar::<_, i64>("SELECT id FROM ticket_categories WHERE id = ? AND guild_id = ?")
+        .bind(category_id)
+        .bind(guild_id)
+        .fetch_optional(conn)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Category {category_id} not found")))?;
+
+    Ok(())
+}
+
+/// Enforce Discord's modal limits so the bot can always render the form.
+fn validate_form(form: &[SetTicketFormQuestion]) -> AppResult<()> {
+    if form.len() > MAX_QUESTIONS {
+        return Err(AppError::BadRequest(format!("A form has at most {MAX_QUESTIONS} questions")));
+    }
+
+    for (i, q) in form.iter().enumerate() {
+        let n = i + 1;
+        let label = q.label.trim().chars().count();
+        if label == 0 || label > MAX_LABEL {
+            return Err(AppError::BadRequest(format!("Question {n}: label must be 1 to {MAX_LABEL} characters")));
+        }
+        if q.placeholder.as_deref().is_some_and(|p| p.trim().chars().count() > MAX_PLACEHOLDER) {
+            return Err(AppError::BadRequest(format!(
+                "Question {n}: placeholder must be at most {MAX_PLACEHOLDER} characters"
+            )));
+        }
+        if q.min_length.is_some_and(|min| !(0..=MAX_LENGTH).contains(&min))
+            || q.max_length.is_some_and(|max| !(1..=MAX_LENGTH).contains(&max))
+        {
+            return Err(AppError::BadRequest(format!("Question {n}: lengths must be within 0 to {MAX_LENGTH}")));
+        }
+        if let (Some(min), Some(max)) = (q.min_length, q.max_length)
+            && min > max
+        {
+            return Err(AppError::BadRequest(format!("Question {n}: min_length is above max_length")));
+        }
+    }
+
+    Ok(())
+}
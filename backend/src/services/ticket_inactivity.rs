@@ -0,0 +1,189 @@
+use serde_json::json;
+use sqlx::SqlitePool;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{Ticket, TicketStatus, TranscriptNames};
+use crate::services::pending_action;
+use crate::services::ticket::{self, SYSTEM_ACTOR};
+
+/// Active tickets, except those parked on hold, that haven't opted out,
+/// joined with the rule that applies to them: the category's override if
+/// set, the guild's otherwise.
+fn ruled_tickets() -> String {
+    let statuses = TicketStatus::ALL.into_iter().filter(|s| s.is_active() && *s != TicketStatus::OnHold);
+    format!(
+        "FROM tickets t \
+         JOIN guilds g ON g.id = t.guild_id \
+         LEFT JOIN ticket_categories c ON c.guild_id = t.guild_id AND c.name = t.category \
+         WHERE t.status IN {} AND t.auto_close = 1",
+        TicketStatus::sql_list(statuses)
+    )
+}
+const WARN_HOURS: &str = "COALESCE(c.inactivity_warn_hours, g.ticket_inactivity_warn_hours)";
+const CLOSE_HOURS: &str = "COALESCE(c.inactivity_close_hours, g.ticket_inactivity_close_hours)";
+
+#[derive(sqlx::FromRow)]
+struct Idle {
+    #[sqlx(flatten)]
+    ticket: Ticket,
+    close_hours: Option<i64>,
+}
+
+/// What one inactivity pass did.
+#[derive(Debug, Default)]
+pub struct InactivityRun {
+    pub warned: Vec<Ticket>,
+    pub closed: Vec<Ticket>,
+}
+
+/// Warn tickets idle past their rule's warn hours, then close those still
+/// idle `close_hours` after the warning with [`SYSTEM_ACTOR`] as closer.
+/// Warnings and closes are queued for the bot as `inactivity_warning` and
+/// `inactivity_close` actions. Any activity clears the warning, so a reply
+/// in the same second as it still counts.
+pub async fn check(pool: &SqlitePool) -> AppResult<InactivityRun> {
+    let mut run = InactivityRun::default();
+    let ruled = ruled_tickets();
+
+    let idle = sqlx::query_as::<_, Idle>(&format!(
+        "SELECT t.*, {CLOSE_HOURS} AS close_hours {ruled} \
+         AND {WARN_HOURS} > 0 \
+         AND t.inactivity_warned_at IS NULL \
+         AND julianday(t.last_activity) <= julianday('now') - {WARN_HOURS} / 24.0"
+    ))
+    .fetch_all(pool)
+    .await?;
+
+    for Idle { ticket: t, close_hours } in idle {
+        let mut tx = pool.begin().await?;
+        // Guarded on last_activity so a message that just landed wins
+        let warned = sqlx::query_as::<_, Ticket>(
+            "UPDATE tickets SET inactivity_warned_at = datetime('now') \
+             WHERE id = ? AND last_activity = ? RETURNING *",
+        )
+        .bind(t.id)
+        .bind(&t.last_activity)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(warned) = warned else { continue };
+        let close_at = match close_hours.filter(|h| *h > 0) {
+            Some(hours) => {
+                sqlx::query_scalar::<_, String>("SELECT datetime(?, '+' || ? || ' hours')")
+                    .bind(&warned.inactivity_warned_at)
+                    .bind(hours)
+                    .fetch_one(&mut *tx)
+                    .await
+                    .map(Some)?
+            }
+            None => None,
+        };
+        let payload = json!({
+            "channel_id": warned.channel_id,
+            "ticket_number": warned.number,
+            "last_activity": warned.last_activity,
+            "close_at": close_at,
+        });
+        pending_action::enqueue_for_ticket(&mut tx, &warned, "inactivity_warning", &payload).await?;
+        ticket::record_event(&mut tx, &warned, "inactivity_warning", None, close_at.as_deref(), SYSTEM_ACTOR, None)
+            .await?;
+        tx.commit().await?;
+        run.warned.push(warned);
+    }
+
+    let expired = sqlx::query_as::<_, Ticket>(&format!(
+        "SELECT t.* {ruled} \
+         AND {WARN_HOURS} > 0 AND {CLOSE_HOURS} > 0 \
+         AND t.inactivity_warned_at IS NOT NULL \
+         AND julianday(t.inactivity_warned_at) <= julianday('now') - {CLOSE_HOURS} / 24.0"
+    ))
+    .fetch_all(pool)
+    .await?;
+
+    let names = TranscriptNames {
+        closed_by_name: Some("Inactivity auto-close".to_string()),
+        ..Default::default()
+    };
+    for t in expired {
+        let (closed, transcript) = match ticket::close_ticket(pool, t.id, SYSTEM_ACTOR, &names).await {
+            Ok(done) => done,
+            Err(e) => {
+                tracing::error!("Inactivity close of ticket {} failed: {e}", t.id);
+                continue;
+            }
+        };
+        let payload = json!({
+            "channel_id": closed.channel_id,
+            "ticket_number": closed.number,
+            "transcript_id": transcript.id,
+        });
+        pending_action::enqueue_for_ticket(&mut *pool.acquire().await?, &closed, "inactivity_close", &payload)
+            .await?;
+        run.closed.push(closed);
+    }
+
+    Ok(run)
+}
+
+/// Opt a ticket in or out of inactivity warnings and auto-close. Turning it
+/// back on starts from a clean slate: any earlier warning is dropped.
+pub async fn set_auto_close(
+    pool: &SqlitePool,
+    guild_id: &str,
+    ticket_id: i64,
+    enabled: bool,
+    actor_id: &str,
+) -> AppResult<Ticket> {
+    let mut tx = pool.begin().await?;
+    let current = sqlx::query_as::<_, Ticket>("SELECT * FROM tickets WHERE id = ? AND guild_id = ?")
+        .bind(ticket_id)
+        .bind(guild_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Ticket {ticket_id} not found")))?;
+
+    if current.auto_close == enabled {
+        return Ok(current);
+    }
+
+    let updated = sqlx::query_as::<_, Ticket>(
+        "UPDATE tickets SET auto_close = ?, inactivity_warned_at = NULL WHERE id = ? RETURNING *",
+    )
+    .bind(enabled)
+    .bind(ticket_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let (from, to) = if enabled { ("off", "on") } else { ("on", "off") };
+    ticket::record_event(&mut tx, &updated, "auto_close", Some(from), Some(to), actor_id, None).await?;
+    tx.commit().await?;
+    Ok(updated)
+}
+
+/// Set a category's inactivity overrides. `None` falls back to the guild's
+/// rule and 0 turns auto-close off for the category.
+pub async fn set_category_rule(
+    pool: &SqlitePool,
+    guild_id: &str,
+    category_id: i64,
+    warn_hours: Option<i64>,
+    close_hours: Option<i64>,
+) -> AppResult<crate::models::TicketCategory> {
+    for (name, hours) in [("warn_hours", warn_hours), ("close_hours", close_hours)] {
+        if hours.is_some_and(|h| h < 0) {
+            return Err(AppError::BadRequest(format!("{name} must be 0 or more")));
+        }
+    }
+
+    sqlx::query_as(
+        "UPDATE ticket_categories SET inactivity_warn_hours = ?, inactivity_close_hours = ? \
+         WHERE id = ? AND guild_id = ? RETURNING *",
+    )
+    .bind(warn_hours)
+    .bind(close_hours)
+    .bind(category_id)
+    .bind(guild_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("Category {category_id} not found")))
+}
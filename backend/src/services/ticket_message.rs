@@ -0,0 +1,149 @@
+use sqlx::SqlitePool;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{EditTicketMessage, IngestTicketMessage, Ticket, TicketMessage};
+use crate::services::{sla, ticket};
+use crate::timestamp;
+
+/// Store messages the bot saw in a ticket channel.
+///
+/// The ticket is the latest one opened in `channel_id`. Messages already
+/// stored (by Discord message ID) are overwritten with the new copy, so
+/// backfilling a channel is safe. Returns the ticket and how many messages
+/// were written, or `None` if no ticket was ever opened in the channel.
+pub async fn ingest(
+    pool: &SqlitePool,
+    channel_id: &str,
+    messages: &[IngestTicketMessage],
+) -> AppResult<Option<(Ticket, u64)>> {
+    let Some(ticket) = ticket::find_by_channel(pool, channel_id).await? else {
+        return Ok(None);
+    };
+
+    let mut tx = pool.begin().await?;
+    let mut written = 0;
+    let mut from_people = false;
+
+    for msg in messages {
+        let created_at = match &msg.created_at {
+            Some(value) => timestamp::normalize(value, "00:00:00").ok_or_else(|| {
+                AppError::BadRequest(format!("Invalid created_at '{value}'"))
+            })?,
+            None => timestamp::format(timestamp::now()),
+        };
+        let edited_at = match &msg.edited_at {
+            Some(value) => Some(timestamp::normalize(value, "00:00:00").ok_or_else(|| {
+                AppError::BadRequest(format!("Invalid edited_at '{value}'"))
+            })?),
+            None => None,
+        };
+
+        let inserted = sqlx::query(
+            "INSERT INTO ticket_messages \
+             (ticket_id, guild_id, message_id, author_id, author_name, author_avatar, author_bot, \
+              content, attachments, embeds, edited_at, created_at) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) \
+             ON CONFLICT(message_id) DO UPDATE SET \
+             author_name = excluded.author_name, author_avatar = excluded.author_avatar, \
+             content = excluded.content, attachments = excluded.attachments, \
+             embeds = excluded.embeds, edited_at = COALESCE(excluded.edited_at, edited_at)",
+        )
+        .bind(ticket.id)
+        .bind(&ticket.guild_id)
+        .bind(&msg.message_id)
+        .bind(&msg.author_id)
+        .bind(&msg.author_name)
+        .bind(&msg.author_avatar)
+        .bind(msg.author_bot)
+        .bind(&msg.content)
+        .bind(serde_json::to_string(&msg.attachments).unwrap_or_else(|_| "[]".to_string()))
+        .bind(serde_json::to_string(&msg.embeds).unwrap_or_else(|_| "[]".to_string()))
+        .bind(&edited_at)
+        .bind(&created_at)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        written += inserted;
+        from_people |= inserted > 0 && !msg.author_bot;
+
+        // Anyone but the owner and bots posting in the ticket counts as staff
+        if !msg.author_bot && msg.author_id != ticket.user_id {
+            sla::record_first_response(&mut tx, ticket.id, &msg.author_id, &created_at).await?;
+        }
+    }
+
+    // Bot posts (inactivity warnings included) don't keep a ticket alive
+    if from_people && ticket.status.is_active() {
+        sqlx::query(
+            "UPDATE tickets SET last_activity = datetime('now'), inactivity_warned_at = NULL WHERE id = ?",
+        )
+        .bind(ticket.id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(Some((ticket, written)))
+}
+
+/// Apply an edit to a stored message. Fields left out keep their value.
+pub async fn edit(pool: &SqlitePool, message_id: &str, data: EditTicketMessage) -> AppResult<TicketMessage> {
+    let edited_at = match &data.edited_at {
+        Some(value) => timestamp::normalize(value, "00:00:00")
+            .ok_or_else(|| AppError::BadRequest(format!("Invalid edited_at '{value}'")))?,
+        None => timestamp::format(timestamp::now()),
+    };
+    let attachments = data
+        .attachments
+        .as_ref()
+        .map(|a| serde_json::to_string(a).unwrap_or_else(|_| "[]".to_string()));
+    let embeds = data
+        .embeds
+        .as_ref()
+        .map(|e| serde_json::to_string(e).unwrap_or_else(|_| "[]".to_string()));
+
+    let message = sqlx::query_as::<_, TicketMessage>(
+        "UPDATE ticket_messages SET content = COALESCE(?, content), \
+         attachments = COALESCE(?, attachments), embeds = COALESCE(?, embeds), edited_at = ? \
+         WHERE message_id = ? RETURNING *",
+    )
+    .bind(&data.content)
+    .bind(&attachments)
+    .bind(&embeds)
+    .bind(&edited_at)
+    .bind(message_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("Ticket message {message_id} not found")))?;
+
+    Ok(message)
+}
+
+/// Mark a message deleted. It is kept for the record but left out of
+/// transcripts.
+pub async fn mark_deleted(pool: &SqlitePool, message_id: &str) -> AppResult<TicketMessage> {
+    let message = sqlx::query_as::<_, TicketMessage>(
+        "UPDATE ticket_messages SET deleted_at = COALESCE(deleted_at, datetime('now')) \
+         WHERE message_id = ? RETURNING *",
+    )
+    .bind(message_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("Ticket message {message_id} not found")))?;
+
+    Ok(message)
+}
+
+/// A ticket's messages in the order they were sent.
+pub async fn list(pool: &SqlitePool, ticket_id: i64, include_deleted: bool) -> AppResult<Vec<TicketMessage>> {
+    let messages = sqlx::query_as::<_, TicketMessage>(
+        "SELECT * FROM ticket_messages WHERE ticket_id = ? AND (? OR deleted_at IS NULL) \
+         ORDER BY created_at ASC, id ASC",
+    )
+    .bind(ticket_id)
+    .bind(include_deleted)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(messages)
+}
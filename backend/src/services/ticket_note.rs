@@ -0,0 +1,77 @@
+use sqlx::SqlitePool;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{CreateTicketNote, Ticket, TicketNote, UpdateTicketNote};
+
+/// Longest note body accepted, in characters.
+const MAX_BODY: usize = 4000;
+
+/// A ticket's notes, pinned first, then oldest first.
+pub async fn list(pool: &SqlitePool, ticket_id: i64) -> AppResult<Vec<TicketNote>> {
+    let notes = sqlx::query_as(
+        "SELECT * FROM ticket_notes WHERE ticket_id = ? ORDER BY pinned DESC, created_at ASC, id ASC",
+    )
+    .bind(ticket_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(notes)
+}
+
+/// Add a note to a ticket.
+pub async fn create(pool: &SqlitePool, ticket: &Ticket, author_id: &str, data: CreateTicketNote) -> AppResult<TicketNote> {
+    let body = validate_body(&data.body)?;
+
+    let note = sqlx::query_as(
+        "INSERT INTO ticket_notes (ticket_id, guild_id, author_id, body, pinned) \
+         VALUES (?, ?, ?, ?, ?) RETURNING *",
+    )
+    .bind(ticket.id)
+    .bind(&ticket.guild_id)
+    .bind(author_id)
+    .bind(body)
+    .bind(data.pinned)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(note)
+}
+
+/// Edit a note's body or pin. Fields left out keep their value.
+pub async fn update(pool: &SqlitePool, ticket_id: i64, note_id: i64, data: UpdateTicketNote) -> AppResult<TicketNote> {
+    let body = data.body.as_deref().map(validate_body).transpose()?;
+
+    sqlx::query_as(
+        "UPDATE ticket_notes SET body = COALESCE(?, body), pinned = COALESCE(?, pinned), \
+         updated_at = datetime('now') WHERE id = ? AND ticket_id = ? RETURNING *",
+    )
+    .bind(body)
+    .bind(data.pinned)
+    .bind(note_id)
+    .bind(ticket_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("Note {note_id} not found")))
+}
+
+/// Delete a note.
+pub async fn delete(pool: &SqlitePool, ticket_id: i64, note_id: i64) -> AppResult<()> {
+    let result = sqlx::query("DELETE FROM ticket_notes WHERE id = ? AND ticket_id = ?")
+        .bind(note_id)
+        .bind(ticket_id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Note {note_id} not found")));
+    }
+    Ok(())
+}
+
+fn validate_body(body: &str) -> AppResult<&str> {
+    let body = body.trim();
+    if body.is_empty() || body.chars().count() > MAX_BODY {
+        return Err(AppError::BadRequest(format!("Note must be 1 to {MAX_BODY} characters")));
+    }
+    Ok(body)
+}
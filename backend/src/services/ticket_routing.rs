@@ -0,0 +1,221 @@
+use serde_json::json;
+use sqlx::{SqliteConnection, SqlitePool};
+
+use crate::error::{AppError, AppResult};
+use crate::models::{RoutingPolicy, Ticket, TicketCategory, TicketStaff, TicketStaffLoad, TicketStatus};
+use crate::services::pending_action;
+use crate::services::ticket::{self, SYSTEM_ACTOR};
+
+// ── Roster ───────────────────────────────────────────────────────────────────
+
+/// The guild's staff roster with each member's active ticket count.
+pub async fn list_staff(pool: &SqlitePool, guild_id: &str) -> AppResult<Vec<TicketStaffLoad>> {
+    let staff = sqlx::query_as(&format!(
+        "SELECT s.*, \
+             (SELECT COUNT(*) FROM tickets t WHERE t.guild_id = s.guild_id AND t.claimed_by = s.user_id \
+              AND t.status IN {}) AS active_tickets \
+         FROM ticket_staff s WHERE s.guild_id = ? ORDER BY s.created_at ASC",
+        TicketStatus::active_sql()
+    ))
+    .bind(guild_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(staff)
+}
+
+/// Add a member to the roster or change their availability. Members going
+/// unavailable hand their active routed tickets to someone else; those
+/// tickets are returned.
+pub async fn set_staff(
+    pool: &SqlitePool,
+    guild_id: &str,
+    user_id: &str,
+    available: bool,
+) -> AppResult<(TicketStaff, Vec<Ticket>)> {
+    let mut tx = pool.begin().await?;
+    let staff = sqlx::query_as::<_, TicketStaff>(
+        "INSERT INTO ticket_staff (guild_id, user_id, available) VALUES (?, ?, ?) \
+         ON CONFLICT(guild_id, user_id) DO UPDATE SET available = excluded.available, updated_at = datetime('now') \
+         RETURNING *",
+    )
+    .bind(guild_id)
+    .bind(user_id)
+    .bind(available)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let reassigned = if available { Vec::new() } else { reassign_from(&mut tx, guild_id, user_id).await? };
+
+    tx.commit().await?;
+    Ok((staff, reassigned))
+}
+
+/// Take a member off the roster, handing off their active routed tickets
+/// like [`set_staff`] does.
+pub async fn remove_staff(pool: &SqlitePool, guild_id: &str, user_id: &str) -> AppResult<Vec<Ticket>> {
+    let mut tx = pool.begin().await?;
+    let result = sqlx::query("DELETE FROM ticket_staff WHERE guild_id = ? AND user_id = ?")
+        .bind(guild_id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Staff member {user_id} not found")));
+    }
+
+    let reassigned = reassign_from(&mut tx, guild_id, user_id).await?;
+    tx.commit().await?;
+    Ok(reassigned)
+}
+
+/// Set a category's routing override; `None` uses the guild's policy.
+pub async fn set_category_routing(
+    pool: &SqlitePool,
+    guild_id: &str,
+    category_id: i64,
+    routing: Option<RoutingPolicy>,
+) -> AppResult<TicketCategory> {
+    sqlx::query_as("UPDATE ticket_categories SET routing = ? WHERE id = ? AND guild_id = ? RETURNING *")
+        .bind(routing)
+        .bind(category_id)
+        .bind(guild_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Category {category_id} not found")))
+}
+
+// ── Assignment ───────────────────────────────────────────────────────────────
+
+/// Assign a new ticket per its routing policy. Returns the ticket unchanged
+/// when routing is manual or no one is available.
+pub async fn auto_assign(conn: &mut SqliteConnection, t: Ticket) -> AppResult<Ticket> {
+    let policy = policy_for(conn, &t).await?;
+    let Some(assignee) = pick_assignee(conn, &t, policy, None).await? else {
+        return Ok(t);
+    };
+
+    assign(conn, &t, &assignee, None).await
+}
+
+/// Hand the active tickets `user_id` was routed elsewhere. Tickets under a
+/// manual policy stay put; routed ones with no one available go back to
+/// the unclaimed pool. Each change is queued for the bot as a
+/// `ticket_reassigned` action.
+async fn reassign_from(conn: &mut SqliteConnection, guild_id: &str, user_id: &str) -> AppResult<Vec<Ticket>> {
+    let held = sqlx::query_as::<_, Ticket>(&format!(
+        "SELECT * FROM tickets WHERE guild_id = ? AND claimed_by = ? \
+         AND status IN {} ORDER BY id ASC",
+        TicketStatus::active_sql()
+    ))
+    .bind(guild_id)
+    .bind(user_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut reassigned = Vec::new();
+    for t in held {
+        let policy = policy_for(conn, &t).await?;
+        if policy == RoutingPolicy::Manual {
+            continue;
+        }
+
+        let ticket = match pick_assignee(conn, &t, policy, Some(user_id)).await? {
+            Some(assignee) => assign(conn, &t, &assignee, Some(user_id)).await?,
+            None => {
+                let ticket = sqlx::query_as::<_, Ticket>(
+                    "UPDATE tickets SET claimed_by = NULL WHERE id = ? RETURNING *",
+                )
+                .bind(t.id)
+                .fetch_one(&mut *conn)
+                .await?;
+                ticket::record_event(conn, &ticket, "claim", Some(user_id), None, SYSTEM_ACTOR, Some("assignee unavailable"))
+                    .await?;
+                ticket
+            }
+        };
+
+        let payload = json!({
+            "channel_id": ticket.channel_id,
+            "ticket_number": ticket.number,
+            "from": user_id,
+            "to": ticket.claimed_by,
+        });
+        pending_action::enqueue_for_ticket(conn, &ticket, "ticket_reassigned", &payload).await?;
+        reassigned.push(ticket);
+    }
+
+    Ok(reassigned)
+}
+
+/// The ticket's category policy if it overrides one, the guild's otherwise.
+async fn policy_for(conn: &mut SqliteConnection, t: &Ticket) -> AppResult<RoutingPolicy> {
+    let policy = sqlx::query_scalar::<_, String>(
+        "SELECT COALESCE( \
+             (SELECT routing FROM ticket_categories WHERE guild_id = g.id AND name = ?), \
+             g.ticket_routing) \
+         FROM guilds g WHERE g.id = ?",
+    )
+    .bind(&t.category)
+    .bind(&t.guild_id)
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(policy.as_deref().and_then(RoutingPolicy::parse).unwrap_or_default())
+}
+
+/// Pick an available roster member other than the ticket's owner and
+/// `exclude`. Ties go to whoever was assigned least recently.
+async fn pick_assignee(
+    conn: &mut SqliteConnection,
+    t: &Ticket,
+    policy: RoutingPolicy,
+    exclude: Option<&str>,
+) -> AppResult<Option<String>> {
+    let order = match policy {
+        RoutingPolicy::Manual => return Ok(None),
+        RoutingPolicy::RoundRobin => "s.last_assigned_at IS NOT NULL, s.last_assigned_at ASC".to_string(),
+        RoutingPolicy::LeastOpen => format!(
+            "(SELECT COUNT(*) FROM tickets x WHERE x.guild_id = s.guild_id AND x.claimed_by = s.user_id \
+              AND x.status IN {}) ASC, \
+             s.last_assigned_at IS NOT NULL, s.last_assigned_at ASC",
+            TicketStatus::active_sql()
+        ),
+    };
+
+    let assignee = sqlx::query_scalar::<_, String>(&format!(
+        "SELECT s.user_id FROM ticket_staff s \
+         WHERE s.guild_id = ? AND s.available = 1 AND s.user_id != ? AND (? IS NULL OR s.user_id != ?) \
+         ORDER BY {order}, s.created_at ASC LIMIT 1"
+    ))
+    .bind(&t.guild_id)
+    .bind(&t.user_id)
+    .bind(exclude)
+    .bind(exclude)
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(assignee)
+}
+
+async fn assign(conn: &mut SqliteConnection, t: &Ticket, assignee: &str, from: Option<&str>) -> AppResult<Ticket> {
+    let ticket = sqlx::query_as::<_, Ticket>("UPDATE tickets SET claimed_by = ? WHERE id = ? RETURNING *")
+        .bind(assignee)
+        .bind(t.id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    // Sub-second precision keeps round-robin turns in order when tickets
+    // open in bursts
+    sqlx::query(
+        "UPDATE ticket_staff SET last_assigned_at = strftime('%Y-%m-%d %H:%M:%f', 'now') \
+         WHERE guild_id = ? AND user_id = ?",
+    )
+    .bind(&t.guild_id)
+    .bind(assignee)
+    .execute(&mut *conn)
+    .await?;
+
+    ticket::record_event(conn, &ticket, "claim", from, Some(assignee), SYSTEM_ACTOR, Some("auto-assigned")).await?;
+    Ok(ticket)
+}
@@ -0,0 +1,136 @@
+use sqlx::SqlitePool;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{SetTicketTag, TicketTag};
+
+/// Longest tag name accepted, in characters.
+const MAX_NAME: usize = 32;
+
+// ── Vocabulary ───────────────────────────────────────────────────────────────
+
+/// The guild's tags by name.
+pub async fn list(pool: &SqlitePool, guild_id: &str) -> AppResult<Vec<TicketTag>> {
+    let tags = sqlx::query_as("SELECT * FROM ticket_tags WHERE guild_id = ? ORDER BY name ASC")
+        .bind(guild_id)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(tags)
+}
+
+/// Add a tag to the guild's vocabulary.
+pub async fn create(pool: &SqlitePool, guild_id: &str, data: &SetTicketTag) -> AppResult<TicketTag> {
+    let (name, color) = validate(data)?;
+
+    sqlx::query_as("INSERT INTO ticket_tags (guild_id, name, color) VALUES (?, ?, ?) RETURNING *")
+        .bind(guild_id)
+        .bind(name)
+        .bind(color)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| duplicate_name(e, name))
+}
+
+/// Rename or recolor a tag; tickets keep it attached.
+pub async fn update(pool: &SqlitePool, guild_id: &str, tag_id: i64, data: &SetTicketTag) -> AppResult<TicketTag> {
+    let (name, color) = validate(data)?;
+
+    sqlx::query_as("UPDATE ticket_tags SET name = ?, color = ? WHERE id = ? AND guild_id = ? RETURNING *")
+        .bind(name)
+        .bind(color)
+        .bind(tag_id)
+        .bind(guild_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| duplicate_name(e, name))?
+        .ok_or_else(|| AppError::NotFound(format!("Tag {tag_id} not found")))
+}
+
+/// Remove a tag from the vocabulary and from every ticket.
+pub async fn delete(pool: &SqlitePool, guild_id: &str, tag_id: i64) -> AppResult<()> {
+    let result = sqlx::query("DELETE FROM ticket_tags WHERE id = ? AND guild_id = ?")
+        .bind(tag_id)
+        .bind(guild_id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Tag {tag_id} not found")));
+    }
+    Ok(())
+}
+
+// ── Ticket tags ──────────────────────────────────────────────────────────────
+
+/// Tags attached to a ticket, by name.
+pub async fn for_ticket(pool: &SqlitePool, ticket_id: i64) -> AppResult<Vec<TicketTag>> {
+    let tags = sqlx::query_as(
+        "SELECT t.* FROM ticket_tags t JOIN ticket_tag_links l ON l.tag_id = t.id \
+         WHERE l.ticket_id = ? ORDER BY t.name ASC",
+    )
+    .bind(ticket_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(tags)
+}
+
+/// Attach one of the guild's tags to a ticket. Attaching it twice is a
+/// no-op.
+pub async fn attach(pool: &SqlitePool, guild_id: &str, ticket_id: i64, tag_id: i64, added_by: &str) -> AppResult<()> {
+    sqlx::query_scalar::<_, i64>("SELECT id FROM ticket_tags WHERE id = ? AND guild_id = ?")
+        .bind(tag_id)
+        .bind(guild_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Tag {tag_id} not found")))?;
+
+    sqlx::query("INSERT OR IGNORE INTO ticket_tag_links (ticket_id, tag_id, added_by) VALUES (?, ?, ?)")
+        .bind(ticket_id)
+        .bind(tag_id)
+        .bind(added_by)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Detach a tag from a ticket.
+pub async fn detach(pool: &SqlitePool, ticket_id: i64, tag_id: i64) -> AppResult<()> {
+    let result = sqlx::query("DELETE FROM ticket_tag_links WHERE ticket_id = ? AND tag_id = ?")
+        .bind(ticket_id)
+        .bind(tag_id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Tag {tag_id} is not on ticket {ticket_id}")));
+    }
+    Ok(())
+}
+
+fn validate(data: &SetTicketTag) -> AppResult<(&str, Option<&str>)> {
+    let name = data.name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME || name.contains(',') {
+        return Err(AppError::BadRequest(format!(
+            "Tag name must be 1 to {MAX_NAME} characters without commas"
+        )));
+    }
+
+    let color = data.color.as_deref().map(str::trim).filter(|c| !c.is_empty());
+    let valid_color = |c: &str| c.len() == 7 && c.starts_with('#') && c[1..].chars().all(|ch| ch.is_ascii_hexdigit());
+    if color.is_some_and(|c| !valid_color(c)) {
+        return Err(AppError::BadRequest("Tag color must be #rrggbb".to_string()));
+    }
+
+    Ok((name, color))
+}
+
+fn duplicate_name(e: sqlx::Error, name: &str) -> AppError {
+    match e {
+        sqlx::Error::Database(ref db_err) if db_err.message().contains("UNIQUE") => {
+            AppError::BadRequest(format!("Tag '{name}' already exists"))
+        }
+        other => AppError::Database(other),
+    }
+}
@@ -1,106 +1,377 @@
-use sqlx::SqlitePool;
+use serde_json::Value;
+use sqlx::{SqliteConnection, SqlitePool};
 
 use crate::error::{AppError, AppResult};
-use crate::models::Transcript;
+use crate::models::{
+    MessageAttachment, Ticket, TicketMessage, Transcript, TranscriptNames, TranscriptTheme,
+};
+use crate::services::{search, transcript_archive};
+use crate::timestamp;
 
-/// Save a transcript for a closed ticket.
+/// Render and store a transcript for a just-closed ticket from its
+/// `ticket_messages`. Deleted messages are left out.
 ///
-/// The caller must supply a unique UUID `id`.
-pub async fn save_transcript(
-    pool: &SqlitePool,
-    id: &str,
-    ticket_id: i64,
-    ticket_number: i64,
-    guild_id: &str,
-    guild_name: &str,
-    user_id: &str,
-    user_name: &str,
-    closed_by: &str,
-    closed_by_name: &str,
-    subject: Option<&str>,
-    category: Option<&str>,
-    message_count: i64,
-    html: &str,
+/// Runs on the caller's connection so it shares the closing transaction.
+pub async fn generate(
+    conn: &mut SqliteConnection,
+    ticket: &Ticket,
+    names: &TranscriptNames,
 ) -> AppResult<Transcript> {
-    sqlx::query(
-        "INSERT INTO transcripts \
-         (id, ticket_id, ticket_number, guild_id, guild_name, user_id, user_name, \
-          closed_by, closed_by_name, subject, category, message_count, html) \
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    let messages = sqlx::query_as::<_, TicketMessage>(
+        "SELECT * FROM ticket_messages WHERE ticket_id = ? AND deleted_at IS NULL \
+         ORDER BY created_at ASC, id ASC",
     )
-    .bind(id)
-    .bind(ticket_id)
-    .bind(ticket_number)
-    .bind(guild_id)
-    .bind(guild_name)
-    .bind(user_id)
-    .bind(user_name)
-    .bind(closed_by)
-    .bind(closed_by_name)
-    .bind(subject)
-    .bind(category)
-    .bind(message_count)
-    .bind(html)
-    .execute(pool)
+    .bind(ticket.id)
+    .fetch_all(&mut *conn)
     .await?;
 
+    let closed_by = ticket.closed_by.clone().unwrap_or_default();
+
+    let guild_name = match &names.guild_name {
+        Some(name) => name.clone(),
+        None => sqlx::query_scalar::<_, String>(
+            "SELECT guild_name FROM transcripts WHERE guild_id = ? ORDER BY created_at DESC LIMIT 1",
+        )
+        .bind(&ticket.guild_id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .unwrap_or_else(|| ticket.guild_id.clone()),
+    };
+    let user_name = names
+        .user_name
+        .clone()
+        .unwrap_or_else(|| author_name(&messages, &ticket.user_id));
+    let closed_by_name = names
+        .closed_by_name
+        .clone()
+        .unwrap_or_else(|| author_name(&messages, &closed_by));
+
+    let header = Header {
+        ticket_number: ticket.number,
+        guild_name: &guild_name,
+        user_name: &user_name,
+        closed_by_name: &closed_by_name,
+        subject: ticket.subject.as_deref(),
+        generated_at: &timestamp::format(timestamp::now()),
+    };
+    let theme = TranscriptTheme::default();
+    let html = render(&header, &messages, theme);
+
     let transcript = sqlx::query_as::<_, Transcript>(
-        "SELECT id, ticket_id, ticket_number, guild_id, guild_name, user_id, user_name, \
-         closed_by, closed_by_name, subject, category, message_count, html, created_at \
-         FROM transcripts WHERE id = ?",
+        "INSERT INTO transcripts \
+         (id, ticket_id, ticket_number, guild_id, guild_name, user_id, user_name, \
+          closed_by, closed_by_name, subject, category, message_count, html, theme) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) RETURNING *",
     )
-    .bind(id)
-    .fetch_one(pool)
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(ticket.id)
+    .bind(ticket.number)
+    .bind(&ticket.guild_id)
+    .bind(&guild_name)
+    .bind(&ticket.user_id)
+    .bind(&user_name)
+    .bind(&closed_by)
+    .bind(&closed_by_name)
+    .bind(&ticket.subject)
+    .bind(&ticket.category)
+    .bind(messages.len() as i64)
+    .bind(&html)
+    .bind(theme)
+    .fetch_one(&mut *conn)
     .await?;
 
+    search::index_transcript(&mut *conn, &transcript, &messages).await?;
+
     Ok(transcript)
 }
 
+/// Render an existing transcript again, e.g. with another theme.
+///
+/// Transcripts with no ingested messages (see [`messages_for`]) return their
+/// stored HTML unchanged.
+pub async fn rerender(pool: &SqlitePool, transcript: &Transcript, theme: TranscriptTheme) -> AppResult<String> {
+    if theme == transcript.theme {
+        return transcript_archive::load_html(&mut *pool.acquire().await?, transcript).await;
+    }
+
+    let messages = messages_for(pool, transcript).await?;
+    if messages.is_empty() {
+        return transcript_archive::load_html(&mut *pool.acquire().await?, transcript).await;
+    }
+
+    let header = Header {
+        ticket_number: transcript.ticket_number,
+        guild_name: &transcript.guild_name,
+        user_name: &transcript.user_name,
+        closed_by_name: &transcript.closed_by_name,
+        subject: transcript.subject.as_deref(),
+        generated_at: &transcript.created_at,
+    };
+
+    Ok(render(&header, &messages, theme))
+}
+
+/// The messages a transcript covers: the ticket's undeleted messages sent up
+/// to the transcript's creation. Empty for transcripts saved before messages
+/// were ingested.
+pub async fn messages_for(pool: &SqlitePool, transcript: &Transcript) -> AppResult<Vec<TicketMessage>> {
+    let messages = sqlx::query_as::<_, TicketMessage>(
+        "SELECT * FROM ticket_messages WHERE ticket_id = ? AND deleted_at IS NULL AND created_at <= ? \
+         ORDER BY created_at ASC, id ASC",
+    )
+    .bind(transcript.ticket_id)
+    .bind(&transcript.created_at)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(messages)
+}
+
 /// Fetch a single transcript by its UUID.
 pub async fn get_transcript(pool: &SqlitePool, transcript_id: &str) -> AppResult<Transcript> {
-    let transcript = sqlx::query_as::<_, Transcript>(
-        "SELECT id, ticket_id, ticket_number, guild_id, guild_name, user_id, user_name, \
-         closed_by, closed_by_name, subject, category, message_count, html, created_at \
-         FROM transcripts WHERE id = ?",
-    )
-    .bind(transcript_id)
-    .fetch_optional(pool)
-    .await?
-    .ok_or_else(|| AppError::NotFound(format!("Transcript {transcript_id} not found")))?;
+    let transcript = sqlx::query_as::<_, Transcript>("SELECT * FROM transcripts WHERE id = ?")
+        .bind(transcript_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Transcript {transcript_id} not found")))?;
 
     Ok(transcript)
 }
 
-/// List all transcripts for a given user, optionally scoped to a guild.
-///
-/// Results are ordered newest-first.
-pub async fn list_user_transcripts(
-    pool: &SqlitePool,
-    user_id: &str,
-    guild_id: Option<&str>,
-) -> AppResult<Vec<Transcript>> {
-    let transcripts = if let Some(gid) = guild_id {
-        sqlx::query_as::<_, Transcript>(
-            "SELECT id, ticket_id, ticket_number, guild_id, guild_name, user_id, user_name, \
-             closed_by, closed_by_name, subject, category, message_count, html, created_at \
-             FROM transcripts WHERE user_id = ? AND guild_id = ? \
-             ORDER BY created_at DESC",
-        )
-        .bind(user_id)
-        .bind(gid)
-        .fetch_all(pool)
-        .await?
-    } else {
-        sqlx::query_as::<_, Transcript>(
-            "SELECT id, ticket_id, ticket_number, guild_id, guild_name, user_id, user_name, \
-             closed_by, closed_by_name, subject, category, message_count, html, created_at \
-             FROM transcripts WHERE user_id = ? \
-             ORDER BY created_at DESC",
-        )
-        .bind(user_id)
-        .fetch_all(pool)
-        .await?
+// ── Rendering ────────────────────────────────────────────────────────────────
+
+struct Header<'a> {
+    ticket_number: i64,
+    guild_name: &'a str,
+    user_name: &'a str,
+    closed_by_name: &'a str,
+    subject: Option<&'a str>,
+    generated_at: &'a str,
+}
+
+struct Palette {
+    background: &'static str,
+    header: &'static str,
+    border: &'static str,
+    hover: &'static str,
+    text: &'static str,
+    heading: &'static str,
+    muted: &'static str,
+    link: &'static str,
+    embed: &'static str,
+}
+
+fn palette(theme: TranscriptTheme) -> Palette {
+    match theme {
+        TranscriptTheme::Dark => Palette {
+            background: "#36393f",
+            header: "#2f3136",
+            border: "#202225",
+            hover: "#32353b",
+            text: "#dcddde",
+            heading: "#ffffff",
+            muted: "#72767d",
+            link: "#00aff4",
+            embed: "#2f3136",
+        },
+        TranscriptTheme::Light => Palette {
+            background: "#ffffff",
+            header: "#f2f3f5",
+            border: "#e3e5e8",
+            hover: "#f6f6f7",
+            text: "#2e3338",
+            heading: "#060607",
+            muted: "#747f8d",
+            link: "#0068e0",
+            embed: "#f2f3f5",
+        },
+    }
+}
+
+fn render(header: &Header, messages: &[TicketMessage], theme: TranscriptTheme) -> String {
+    let p = palette(theme);
+    let title = format!("Transcript - Ticket #{:04}", header.ticket_number);
+    let body: String = messages.iter().map(render_message).collect();
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="UTF-8">
+  <meta name="viewport" content="width=device-width, initial-scale=1.0">
+  <title>{title}</title>
+  <style>
+    * {{ margin:0; padding:0; box-sizing:border-box; }}
+    body {{ font-family:'Helvetica Neue',Helvetica,Arial,sans-serif; background:{bg}; color:{text}; line-height:1.5; }}
+    .header-info {{ background:{header_bg}; padding:20px; border-bottom:1px solid {border}; }}
+    .header-info h1 {{ color:{heading}; font-size:24px; margin-bottom:10px; }}
+    .header-info p {{ color:{muted}; font-size:14px; }}
+    .messages {{ padding:20px; }}
+    .message {{ display:flex; padding:8px 0; margin:4px 0; }}
+    .message:hover {{ background:{hover}; }}
+    .avatar {{ width:40px; height:40px; border-radius:50%; margin-right:16px; flex-shrink:0; }}
+    .content {{ flex:1; min-width:0; }}
+    .header {{ display:flex; align-items:center; gap:8px; margin-bottom:4px; }}
+    .author {{ font-weight:500; color:{heading}; }}
+    .author.bot {{ color:#5865f2; }}
+    .bot-tag {{ background:#5865f2; color:#fff; font-size:10px; padding:1px 4px; border-radius:3px; font-weight:600; }}
+    .timestamp, .edited {{ color:{muted}; font-size:12px; }}
+    .text {{ color:{text}; word-wrap:break-word; }}
+    .attachment {{ margin-top:8px; }}
+    .attachment img {{ max-width:400px; border-radius:8px; }}
+    a {{ color:{link}; text-decoration:none; }}
+    a:hover {{ text-decoration:underline; }}
+    .embed {{ background:{embed}; border-left:4px solid {border}; border-radius:4px; padding:8px 12px; margin-top:8px; max-width:520px; }}
+    .embed-title {{ color:{heading}; font-weight:600; margin-bottom:4px; }}
+    .embed-field {{ margin-top:6px; }}
+    .embed-field-name {{ color:{heading}; font-weight:600; font-size:14px; }}
+    .embed-footer {{ color:{muted}; font-size:12px; margin-top:6px; }}
+    .embed img {{ max-width:100%; border-radius:4px; margin-top:8px; }}
+  </style>
+</head>
+<body>
+  <div class="header-info">
+    <h1>{title}</h1>
+    <p>Server: {guild}</p>
+    <p>Opened by: {user}</p>
+    <p>Closed by: {closed_by}</p>
+    <p>Subject: {subject}</p>
+    <p>Generated: {generated} UTC</p>
+    <p>Messages: {count}</p>
+  </div>
+  <div class="messages">{body}</div>
+</body>
+</html>"#,
+        bg = p.background,
+        header_bg = p.header,
+        border = p.border,
+        hover = p.hover,
+        text = p.text,
+        heading = p.heading,
+        muted = p.muted,
+        link = p.link,
+        embed = p.embed,
+        guild = escape_html(header.guild_name),
+        user = escape_html(header.user_name),
+        closed_by = escape_html(header.closed_by_name),
+        subject = escape_html(header.subject.unwrap_or("None")),
+        generated = escape_html(header.generated_at),
+        count = messages.len(),
+    )
+}
+
+fn render_message(msg: &TicketMessage) -> String {
+    let author = escape_html(&msg.author_name);
+    let avatar = msg
+        .author_avatar
+        .as_deref()
+        .and_then(safe_url)
+        .map(|url| format!(r#"<img class="avatar" src="{url}" alt="{author}">"#))
+        .unwrap_or_else(|| r#"<div class="avatar"></div>"#.to_string());
+    let bot_class = if msg.author_bot { " bot" } else { "" };
+    let bot_tag = if msg.author_bot { r#"<span class="bot-tag">BOT</span>"# } else { "" };
+    let edited = if msg.edited_at.is_some() { r#"<span class="edited">(edited)</span>"# } else { "" };
+
+    let attachments: Vec<MessageAttachment> = serde_json::from_str(&msg.attachments).unwrap_or_default();
+    let attachments: String = attachments.iter().map(render_attachment).collect();
+    let embeds: Vec<Value> = serde_json::from_str(&msg.embeds).unwrap_or_default();
+    let embeds: String = embeds.iter().map(render_embed).collect();
+
+    format!(
+        r#"
+    <div class="message">
+      {avatar}
+      <div class="content">
+        <div class="header">
+          <span class="author{bot_class}">{author}</span>
+          {bot_tag}
+          <span class="timestamp">{time}</span>
+          {edited}
+        </div>
+        <div class="text">{content}</div>
+        {attachments}{embeds}
+      </div>
+    </div>"#,
+        time = escape_html(&msg.created_at),
+        content = escape_text(&msg.content),
+    )
+}
+
+fn render_attachment(att: &MessageAttachment) -> String {
+    let Some(url) = safe_url(&att.url) else {
+        return String::new();
     };
+    let name = escape_html(&att.name);
+
+    if att.content_type.as_deref().is_some_and(|t| t.starts_with("image/")) {
+        format!(r#"<div class="attachment"><img src="{url}" alt="{name}"></div>"#)
+    } else {
+        format!(r#"<div class="attachment"><a href="{url}" target="_blank" rel="noopener">Attachment: {name}</a></div>"#)
+    }
+}
+
+fn render_embed(embed: &Value) -> String {
+    let mut out = String::new();
+
+    let color = embed["color"]
+        .as_u64()
+        .map(|c| format!(r#" style="border-left-color:#{:06x}""#, c & 0xffffff))
+        .unwrap_or_default();
+
+    if let Some(title) = embed["title"].as_str() {
+        let title = escape_html(title);
+        match embed["url"].as_str().and_then(safe_url) {
+            Some(url) => out.push_str(&format!(r#"<div class="embed-title"><a href="{url}">{title}</a></div>"#)),
+            None => out.push_str(&format!(r#"<div class="embed-title">{title}</div>"#)),
+        }
+    }
+    if let Some(description) = embed["description"].as_str() {
+        out.push_str(&format!(r#"<div class="text">{}</div>"#, escape_text(description)));
+    }
+    for field in embed["fields"].as_array().into_iter().flatten() {
+        out.push_str(&format!(
+            r#"<div class="embed-field"><div class="embed-field-name">{}</div><div class="text">{}</div></div>"#,
+            escape_html(field["name"].as_str().unwrap_or_default()),
+            escape_text(field["value"].as_str().unwrap_or_default()),
+        ));
+    }
+    if let Some(url) = embed["image"]["url"].as_str().and_then(safe_url) {
+        out.push_str(&format!(r#"<img src="{url}" alt="">"#));
+    }
+    if let Some(footer) = embed["footer"]["text"].as_str() {
+        out.push_str(&format!(r#"<div class="embed-footer">{}</div>"#, escape_html(footer)));
+    }
+
+    if out.is_empty() {
+        return out;
+    }
+    format!(r#"<div class="embed"{color}>{out}</div>"#)
+}
+
+/// Most recent display name recorded for `user_id`, or the ID itself.
+fn author_name(messages: &[TicketMessage], user_id: &str) -> String {
+    messages
+        .iter()
+        .rev()
+        .find(|m| m.author_id == user_id)
+        .map(|m| m.author_name.clone())
+        .unwrap_or_else(|| user_id.to_string())
+}
+
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Escape message text and keep its line breaks.
+fn escape_text(text: &str) -> String {
+    escape_html(text).replace('\n', "<br>")
+}
 
-    Ok(transcripts)
+/// Only http(s) URLs make it into the page, escaped for an attribute.
+fn safe_url(url: &str) -> Option<String> {
+    (url.starts_with("https://") || url.starts_with("http://")).then(|| escape_html(url))
 }
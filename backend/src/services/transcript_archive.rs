@@ -0,0 +1,275 @@
+use std::path::Path;
+
+use chrono::Duration;
+use sqlx::{SqliteConnection, SqlitePool};
+
+use crate::error::{AppError, AppResult};
+use crate::models::{Transcript, TranscriptStorage};
+use crate::timestamp;
+
+/// Archiving happens once per transcript, so favour ratio over speed.
+const ZSTD_LEVEL: i32 = 19;
+/// Transcripts archived or deleted per guild on each scheduler tick.
+const RETENTION_BATCH: i64 = 100;
+
+/// What one retention pass did.
+#[derive(Debug, Default)]
+pub struct RetentionRun {
+    pub archived: u64,
+    pub deleted: u64,
+}
+
+/// A transcript's HTML, decompressed if it has been archived.
+pub async fn load_html(conn: &mut SqliteConnection, transcript: &Transcript) -> AppResult<String> {
+    let compressed = match transcript.storage {
+        TranscriptStorage::Html => return Ok(transcript.html.clone()),
+        TranscriptStorage::Zstd => {
+            sqlx::query_scalar::<_, Option<Vec<u8>>>("SELECT html_zstd FROM transcripts WHERE id = ?")
+                .bind(&transcript.id)
+                .fetch_one(&mut *conn)
+                .await?
+                .unwrap_or_default()
+        }
+        TranscriptStorage::File => {
+            let path = transcript.archive_path.as_deref().unwrap_or_default();
+            tokio::fs::read(path).await.map_err(|e| {
+                AppError::Internal(anyhow::anyhow!("Failed to read archived transcript {path}: {e}"))
+            })?
+        }
+    };
+
+    let html = zstd::decode_all(compressed.as_slice()).map_err(|e| {
+        AppError::Internal(anyhow::anyhow!("Failed to decompress transcript {}: {e}", transcript.id))
+    })?;
+    String::from_utf8(html).map_err(|e| {
+        AppError::Internal(anyhow::anyhow!("Archived transcript {} is not UTF-8: {e}", transcript.id))
+    })
+}
+
+/// Compress a transcript's HTML with zstd and move it out of the `html`
+/// column, into `html_zstd` (`Zstd`) or a file under `archive_dir`
+/// (`File`), then drop the raw messages it was rendered from. Transcripts
+/// already archived, or a target of `Html`, are returned unchanged.
+pub async fn archive(
+    pool: &SqlitePool,
+    transcript: &Transcript,
+    storage: TranscriptStorage,
+    archive_dir: &Path,
+) -> AppResult<Transcript> {
+    if transcript.storage != TranscriptStorage::Html || storage == TranscriptStorage::Html {
+        return Ok(transcript.clone());
+    }
+
+    let html = transcript.html.clone();
+    let compressed = tokio::task::spawn_blocking(move || zstd::encode_all(html.as_bytes(), ZSTD_LEVEL))
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Transcript compression failed: {e}")))?
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Transcript compression failed: {e}")))?;
+
+    let archived = match storage {
+        TranscriptStorage::File => {
+            let dir = archive_dir.join(&transcript.guild_id);
+            let path = dir.join(format!("{}.html.zst", transcript.id));
+            let write_err = |e: std::io::Error| {
+                AppError::Internal(anyhow::anyhow!("Failed to write {}: {e}", path.display()))
+            };
+            tokio::fs::create_dir_all(&dir).await.map_err(write_err)?;
+            tokio::fs::write(&path, &compressed).await.map_err(write_err)?;
+
+            sqlx::query_as::<_, Transcript>(
+                "UPDATE transcripts SET storage = 'file', archive_path = ?, html = '', \
+                 archived_at = datetime('now') WHERE id = ? AND storage = 'html' RETURNING *",
+            )
+            .bind(path.to_string_lossy().as_ref())
+            .bind(&transcript.id)
+            .fetch_optional(pool)
+            .await?
+        }
+        _ => {
+            sqlx::query_as::<_, Transcript>(
+                "UPDATE transcripts SET storage = 'zstd', html_zstd = ?, html = '', \
+                 archived_at = datetime('now') WHERE id = ? AND storage = 'html' RETURNING *",
+            )
+            .bind(&compressed)
+            .bind(&transcript.id)
+            .fetch_optional(pool)
+            .await?
+        }
+    };
+
+    // Archived concurrently: keep whatever won
+    match archived {
+        Some(archived) => {
+            purge_messages(&mut *pool.acquire().await?, transcript).await?;
+            Ok(archived)
+        }
+        None => crate::services::transcript::get_transcript(pool, &transcript.id).await,
+    }
+}
+
+/// Delete a transcript with the raw messages it was rendered from, and its
+/// archive file if it has one.
+pub async fn delete(pool: &SqlitePool, transcript: &Transcript) -> AppResult<()> {
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM transcripts WHERE id = ?")
+        .bind(&transcript.id)
+        .execute(&mut *tx)
+        .await?;
+    purge_messages(&mut tx, transcript).await?;
+    tx.commit().await?;
+
+    if let Some(path) = &transcript.archive_path {
+        match tokio::fs::remove_file(path).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => tracing::warn!("Failed to remove archived transcript {path}: {e}"),
+        }
+    }
+
+    Ok(())
+}
+
+/// Delete the ticket messages a transcript covers. The FTS triggers drop
+/// them from search; messages posted after a reopen are kept for the next
+/// transcript.
+async fn purge_messages(conn: &mut SqliteConnection, transcript: &Transcript) -> AppResult<u64> {
+    let purged = sqlx::query("DELETE FROM ticket_messages WHERE ticket_id = ? AND created_at <= ?")
+        .bind(transcript.ticket_id)
+        .bind(&transcript.created_at)
+        .execute(conn)
+        .await?
+        .rows_affected();
+    Ok(purged)
+}
+
+/// Apply every guild's retention settings: transcripts older than
+/// `transcript_retention_days` are archived or deleted, per
+/// `transcript_retention_action`. A transcript that fails is logged and
+/// retried on the next pass.
+pub async fn enforce_retention(pool: &SqlitePool, archive_dir: &Path) -> AppResult<RetentionRun> {
+    let guilds = sqlx::query_as::<_, (String, i64, String, String)>(
+        "SELECT id, transcript_retention_days, transcript_retention_action, transcript_archive_storage \
+         FROM guilds WHERE transcript_retention_days IS NOT NULL",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut run = RetentionRun::default();
+
+    for (guild_id, days, action, storage) in guilds {
+        let delete_due = action == "delete";
+        let storage = match storage.as_str() {
+            "directory" => TranscriptStorage::File,
+            _ => TranscriptStorage::Zstd,
+        };
+        let cutoff = timestamp::format(timestamp::now() - Duration::days(days));
+
+        let due = sqlx::query_as::<_, Transcript>(
+            "SELECT * FROM transcripts WHERE guild_id = ? AND created_at < ? AND (? OR storage = 'html') \
+             ORDER BY created_at ASC LIMIT ?",
+        )
+        .bind(&guild_id)
+        .bind(&cutoff)
+        .bind(delete_due)
+        .bind(RETENTION_BATCH)
+        .fetch_all(pool)
+        .await?;
+
+        for t in &due {
+            let result = if delete_due {
+                delete(pool, t).await.map(|_| run.deleted += 1)
+            } else {
+                archive(pool, t, storage, archive_dir).await.map(|_| run.archived += 1)
+            };
+            if let Err(e) = result {
+                tracing::error!("Retention for transcript {} in guild {guild_id} failed: {e}", t.id);
+            }
+        }
+    }
+
+    Ok(run)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{SearchFilters, SearchKind};
+    use crate::services::search;
+
+    async fn seed_ticket(pool: &SqlitePool, guild_id: &str, action: &str, ticket_id: i64) {
+        sqlx::query(
+            "INSERT INTO guilds (id, transcript_retention_days, transcript_retention_action) \
+             VALUES (?, 30, ?)",
+        )
+        .bind(guild_id)
+        .bind(action)
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO tickets (id, number, channel_id, user_id, guild_id, status) \
+             VALUES (?, 1, ?, 'u1', ?, 'closed')",
+        )
+        .bind(ticket_id)
+        .bind(format!("c{ticket_id}"))
+        .bind(guild_id)
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO ticket_messages (ticket_id, guild_id, message_id, author_id, author_name, content, created_at) \
+             VALUES (?, ?, ?, 'u1', 'user', 'my card number is secret', datetime('now', '-40 days'))",
+        )
+        .bind(ticket_id)
+        .bind(guild_id)
+        .bind(format!("m{ticket_id}"))
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO transcripts (id, ticket_id, ticket_number, guild_id, guild_name, user_id, user_name, \
+             closed_by, closed_by_name, message_count, html, created_at) \
+             VALUES (?, ?, 1, ?, 'guild', 'u1', 'user', 'u2', 'staff', 1, '<p>secret</p>', datetime('now', '-35 days'))",
+        )
+        .bind(format!("t{ticket_id}"))
+        .bind(ticket_id)
+        .bind(guild_id)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn message_state(pool: &SqlitePool, guild_id: &str) -> (i64, usize) {
+        let rows = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM ticket_messages WHERE guild_id = ?")
+            .bind(guild_id)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        let filters = SearchFilters { kind: Some(SearchKind::Message), ..Default::default() };
+        let hits = search::search(pool, guild_id, "secret", &filters, 10, 0).await.unwrap();
+        (rows, hits.len())
+    }
+
+    #[tokio::test]
+    async fn retention_removes_raw_messages_from_table_and_search() {
+        let dir = std::env::temp_dir().join(format!("retention-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let pool = crate::db::pool::create_pool(&format!("sqlite:{}", dir.join("test.db").display()))
+            .await
+            .unwrap();
+
+        seed_ticket(&pool, "g-delete", "delete", 1).await;
+        seed_ticket(&pool, "g-archive", "archive", 2).await;
+        assert_eq!(message_state(&pool, "g-delete").await, (1, 1));
+        assert_eq!(message_state(&pool, "g-archive").await, (1, 1));
+
+        let run = enforce_retention(&pool, &dir).await.unwrap();
+        assert_eq!((run.deleted, run.archived), (1, 1));
+
+        assert_eq!(message_state(&pool, "g-delete").await, (0, 0));
+        assert_eq!(message_state(&pool, "g-archive").await, (0, 0));
+
+        pool.close().await;
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
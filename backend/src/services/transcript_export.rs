@@ -0,0 +1,461 @@
+use std::io::{Cursor, Write};
+
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+use zip::write::SimpleFileOptions;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{MessageAttachment, TicketMessage, TicketNote, Transcript, TranscriptTheme};
+use crate::services::{ticket_note, transcript, transcript_archive};
+
+/// Most transcripts a single ZIP export may contain.
+pub const MAX_BULK_EXPORT: i64 = 1000;
+
+/// Output format for a transcript download.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Html,
+    Json,
+    Markdown,
+    Text,
+    Pdf,
+}
+
+impl ExportFormat {
+    /// Parse a `?format=` value.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "html" => Some(ExportFormat::Html),
+            "json" => Some(ExportFormat::Json),
+            "md" | "markdown" => Some(ExportFormat::Markdown),
+            "txt" | "text" => Some(ExportFormat::Text),
+            "pdf" => Some(ExportFormat::Pdf),
+            _ => None,
+        }
+    }
+
+    /// Pick the first supported type from an `Accept` header, ignoring
+    /// q-values. Anything unrecognised (including `*/*`) means HTML.
+    pub fn from_accept(accept: &str) -> Self {
+        accept
+            .split(',')
+            .filter_map(|part| match part.split(';').next().unwrap_or_default().trim() {
+                "text/html" => Some(ExportFormat::Html),
+                "application/json" => Some(ExportFormat::Json),
+                "text/markdown" => Some(ExportFormat::Markdown),
+                "text/plain" => Some(ExportFormat::Text),
+                "application/pdf" => Some(ExportFormat::Pdf),
+                _ => None,
+            })
+            .next()
+            .unwrap_or(ExportFormat::Html)
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ExportFormat::Html => "text/html; charset=utf-8",
+            ExportFormat::Json => "application/json",
+            ExportFormat::Markdown => "text/markdown; charset=utf-8",
+            ExportFormat::Text => "text/plain; charset=utf-8",
+            ExportFormat::Pdf => "application/pdf",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Html => "html",
+            ExportFormat::Json => "json",
+            ExportFormat::Markdown => "md",
+            ExportFormat::Text => "txt",
+            ExportFormat::Pdf => "pdf",
+        }
+    }
+}
+
+/// Download name for a transcript, e.g. `transcript-ticket-0042-1a2b3c4d.md`.
+pub fn file_name(transcript: &Transcript, format: ExportFormat) -> String {
+    let short_id: String = transcript.id.chars().take(8).collect();
+    format!(
+        "transcript-ticket-{:04}-{short_id}.{}",
+        transcript.ticket_number,
+        format.extension()
+    )
+}
+
+/// Render a transcript in `format`. `theme` only applies to HTML.
+///
+/// Staff exports (`for_staff`) also carry the ticket's internal notes; the
+/// stored transcript and what ticket owners or share links get never do.
+pub async fn export(
+    pool: &SqlitePool,
+    transcript: &Transcript,
+    format: ExportFormat,
+    theme: Option<TranscriptTheme>,
+    for_staff: bool,
+) -> AppResult<Vec<u8>> {
+    let notes = if for_staff { ticket_note::list(pool, transcript.ticket_id).await? } else { Vec::new() };
+
+    if format == ExportFormat::Html {
+        let html = match theme {
+            Some(theme) => transcript::rerender(pool, transcript, theme).await?,
+            None => transcript_archive::load_html(&mut *pool.acquire().await?, transcript).await?,
+        };
+        return Ok(with_notes_html(html, &notes).into_bytes());
+    }
+
+    let messages = transcript::messages_for(pool, transcript).await?;
+    Ok(render(transcript, &messages, &notes, format))
+}
+
+/// Bundle transcripts into one ZIP, each rendered in `format` with staff
+/// notes, and an `index.json` listing them.
+pub async fn export_zip(pool: &SqlitePool, transcripts: &[Transcript], format: ExportFormat) -> AppResult<Vec<u8>> {
+    let mut files = Vec::with_capacity(transcripts.len() + 1);
+    let mut index = Vec::with_capacity(transcripts.len());
+
+    for t in transcripts {
+        let name = file_name(t, format);
+        let body = export(pool, t, format, None, true).await?;
+        index.push(json!({ "file": name, "transcript": metadata(t) }));
+        files.push((name, body));
+    }
+    files.push(("index.json".to_string(), pretty_json(&Value::Array(index))));
+
+    // Deflating can take a while for large exports, so keep it off the runtime
+    tokio::task::spawn_blocking(move || write_zip(files))
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("ZIP export failed: {e}")))?
+}
+
+fn write_zip(files: Vec<(String, Vec<u8>)>) -> AppResult<Vec<u8>> {
+    let zip_err = |e: zip::result::ZipError| AppError::Internal(anyhow::anyhow!("ZIP export failed: {e}"));
+
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    for (name, body) in files {
+        zip.start_file(name, options).map_err(zip_err)?;
+        zip.write_all(&body)
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("ZIP export failed: {e}")))?;
+    }
+
+    Ok(zip.finish().map_err(zip_err)?.into_inner())
+}
+
+fn render(transcript: &Transcript, messages: &[TicketMessage], notes: &[TicketNote], format: ExportFormat) -> Vec<u8> {
+    match format {
+        ExportFormat::Html => with_notes_html(transcript.html.clone(), notes).into_bytes(),
+        ExportFormat::Json => {
+            let mut doc = json!({
+                "transcript": metadata(transcript),
+                "messages": messages.iter().map(message_json).collect::<Vec<_>>(),
+            });
+            if !notes.is_empty() {
+                doc["notes"] = json!(notes);
+            }
+            pretty_json(&doc)
+        }
+        ExportFormat::Markdown => to_markdown(transcript, messages, notes).into_bytes(),
+        ExportFormat::Text => to_text(transcript, messages, notes).join("\n").into_bytes(),
+        ExportFormat::Pdf => to_pdf(&to_text(transcript, messages, notes)),
+    }
+}
+
+/// Append a staff notes section to a rendered transcript.
+fn with_notes_html(html: String, notes: &[TicketNote]) -> String {
+    if notes.is_empty() {
+        return html;
+    }
+
+    let items: String = notes
+        .iter()
+        .map(|n| {
+            format!(
+                r#"<div style="margin-top:8px;"><strong>{author}</strong>{pinned} <span style="opacity:0.7;font-size:12px;">{at} UTC</span><div style="white-space:pre-wrap;">{body}</div></div>"#,
+                author = transcript::escape_html(&n.author_id),
+                pinned = if n.pinned { " [pinned]" } else { "" },
+                at = transcript::escape_html(&n.created_at),
+                body = transcript::escape_html(&n.body),
+            )
+        })
+        .collect();
+    let section = format!(
+        r#"<div class="staff-notes" style="margin:20px;padding:12px 16px;border-left:4px solid #faa61a;border-radius:4px;"><h2 style="font-size:16px;">Staff notes</h2>{items}</div>"#
+    );
+
+    match html.rfind("</body>") {
+        Some(at) => format!("{}{section}\n{}", &html[..at], &html[at..]),
+        None => html + &section,
+    }
+}
+
+/// Transcript fields without the HTML body.
+fn metadata(t: &Transcript) -> Value {
+    json!({
+        "id": t.id,
+        "ticket_id": t.ticket_id,
+        "ticket_number": t.ticket_number,
+        "guild_id": t.guild_id,
+        "guild_name": t.guild_name,
+        "user_id": t.user_id,
+        "user_name": t.user_name,
+        "closed_by": t.closed_by,
+        "closed_by_name": t.closed_by_name,
+        "subject": t.subject,
+        "category": t.category,
+        "message_count": t.message_count,
+        "created_at": t.created_at,
+    })
+}
+
+fn message_json(m: &TicketMessage) -> Value {
+    json!({
+        "message_id": m.message_id,
+        "author_id": m.author_id,
+        "author_name": m.author_name,
+        "author_bot": m.author_bot,
+        "content": m.content,
+        "attachments": attachments(m),
+        "embeds": embeds(m),
+        "created_at": m.created_at,
+        "edited_at": m.edited_at,
+    })
+}
+
+fn pretty_json(value: &Value) -> Vec<u8> {
+    serde_json::to_vec_pretty(value).unwrap_or_default()
+}
+
+fn attachments(m: &TicketMessage) -> Vec<MessageAttachment> {
+    serde_json::from_str(&m.attachments).unwrap_or_default()
+}
+
+fn embeds(m: &TicketMessage) -> Vec<Value> {
+    serde_json::from_str(&m.embeds).unwrap_or_default()
+}
+
+/// An embed as a single line: title and description, whichever are set.
+fn embed_summary(embed: &Value) -> Option<String> {
+    let parts: Vec<&str> = [embed["title"].as_str(), embed["description"].as_str()]
+        .into_iter()
+        .flatten()
+        .collect();
+    (!parts.is_empty()).then(|| parts.join(" - "))
+}
+
+fn header_lines(t: &Transcript) -> Vec<String> {
+    vec![
+        format!("Server: {}", t.guild_name),
+        format!("Opened by: {} ({})", t.user_name, t.user_id),
+        format!("Closed by: {} ({})", t.closed_by_name, t.closed_by),
+        format!("Subject: {}", t.subject.as_deref().unwrap_or("None")),
+        format!("Category: {}", t.category.as_deref().unwrap_or("None")),
+        format!("Closed: {} UTC", t.created_at),
+        format!("Messages: {}", t.message_count),
+    ]
+}
+
+fn to_markdown(t: &Transcript, messages: &[TicketMessage], notes: &[TicketNote]) -> String {
+    let mut out = format!("# Transcript - Ticket #{:04}\n\n", t.ticket_number);
+    for line in header_lines(t) {
+        out.push_str(&format!("- {line}\n"));
+    }
+    out.push_str("\n---\n");
+
+    for m in messages {
+        let bot = if m.author_bot { " [BOT]" } else { "" };
+        let edited = if m.edited_at.is_some() { " (edited)" } else { "" };
+        out.push_str(&format!("\n**{}**{bot} - {} UTC{edited}\n\n", m.author_name, m.created_at));
+        if !m.content.is_empty() {
+            out.push_str(&m.content);
+            out.push_str("\n\n");
+        }
+        for att in attachments(m) {
+            out.push_str(&format!("- [{}]({})\n", att.name, att.url));
+        }
+        for embed in embeds(m) {
+            if let Some(summary) = embed_summary(&embed) {
+                out.push_str(&format!("> {}\n", summary.replace('\n', "\n> ")));
+            }
+        }
+    }
+
+    if !notes.is_empty() {
+        out.push_str("\n---\n\n## Staff notes\n");
+        for n in notes {
+            let pinned = if n.pinned { " [pinned]" } else { "" };
+            out.push_str(&format!("\n**{}**{pinned} - {} UTC\n\n{}\n", n.author_id, n.created_at, n.body));
+        }
+    }
+
+    out
+}
+
+fn to_text(t: &Transcript, messages: &[TicketMessage], notes: &[TicketNote]) -> Vec<String> {
+    let mut lines = vec![format!("Transcript - Ticket #{:04}", t.ticket_number)];
+    lines.extend(header_lines(t));
+    lines.push(String::new());
+
+    for m in messages {
+        let bot = if m.author_bot { " [BOT]" } else { "" };
+        let edited = if m.edited_at.is_some() { " (edited)" } else { "" };
+        let mut content = m.content.lines();
+        lines.push(format!(
+            "[{}] {}{bot}: {}{edited}",
+            m.created_at,
+            m.author_name,
+            content.next().unwrap_or_default()
+        ));
+        lines.extend(content.map(|line| format!("    {line}")));
+        for att in attachments(m) {
+            lines.push(format!("    [attachment] {} {}", att.name, att.url));
+        }
+        for embed in embeds(m) {
+            if let Some(summary) = embed_summary(&embed) {
+                for line in summary.lines() {
+                    lines.push(format!("    [embed] {line}"));
+                }
+            }
+        }
+    }
+
+    if !notes.is_empty() {
+        lines.push(String::new());
+        lines.push("Staff notes".to_string());
+        for n in notes {
+            let pinned = if n.pinned { " [pinned]" } else { "" };
+            let mut body = n.body.lines();
+            lines.push(format!("[{}] {}{pinned}: {}", n.created_at, n.author_id, body.next().unwrap_or_default()));
+            lines.extend(body.map(|line| format!("    {line}")));
+        }
+    }
+
+    lines
+}
+
+// ── PDF ──────────────────────────────────────────────────────────────────────
+
+const PAGE_WIDTH: i32 = 595; // A4, in points
+const PAGE_HEIGHT: i32 = 842;
+const MARGIN: i32 = 50;
+const FONT_SIZE: i32 = 10;
+const LEADING: i32 = 14;
+/// Characters per line; Helvetica at 10pt averages about 5pt per glyph.
+const WRAP_AT: usize = 95;
+
+/// A minimal self-contained PDF of plain text lines, set in the built-in
+/// Helvetica font. Characters outside Latin-1 are replaced with `?`.
+fn to_pdf(lines: &[String]) -> Vec<u8> {
+    let wrapped: Vec<String> = lines.iter().flat_map(|line| wrap(line, WRAP_AT)).collect();
+    let per_page = ((PAGE_HEIGHT - 2 * MARGIN) / LEADING) as usize;
+    let pages: Vec<&[String]> = if wrapped.is_empty() {
+        vec![&[]]
+    } else {
+        wrapped.chunks(per_page).collect()
+    };
+
+    // Objects 1-3 are the catalog, page tree and font; each page then takes
+    // two: the page and its content stream.
+    let mut objects: Vec<Vec<u8>> = Vec::with_capacity(3 + pages.len() * 2);
+    let kids: Vec<String> = (0..pages.len()).map(|i| format!("{} 0 R", 4 + i * 2)).collect();
+    objects.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+    objects.push(format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), pages.len()).into_bytes());
+    objects.push(b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_vec());
+
+    for (i, page) in pages.iter().enumerate() {
+        let mut stream = format!(
+            "BT /F1 {FONT_SIZE} Tf {LEADING} TL {MARGIN} {} Td\n",
+            PAGE_HEIGHT - MARGIN - FONT_SIZE
+        )
+        .into_bytes();
+        for line in page.iter() {
+            stream.push(b'(');
+            stream.extend(pdf_string(line));
+            stream.extend(b") Tj T*\n");
+        }
+        stream.extend(b"ET");
+
+        objects.push(
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+                 /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+                5 + i * 2
+            )
+            .into_bytes(),
+        );
+        let mut content = format!("<< /Length {} >>\nstream\n", stream.len()).into_bytes();
+        content.extend(stream);
+        content.extend(b"\nendstream");
+        objects.push(content);
+    }
+
+    let mut out = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(out.len());
+        out.extend(format!("{} 0 obj\n", i + 1).into_bytes());
+        out.extend(object);
+        out.extend(b"\nendobj\n");
+    }
+
+    let xref_at = out.len();
+    out.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).into_bytes());
+    for offset in offsets {
+        out.extend(format!("{offset:010} 00000 n \n").into_bytes());
+    }
+    out.extend(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref_at}\n%%EOF\n",
+            objects.len() + 1
+        )
+        .into_bytes(),
+    );
+
+    out
+}
+
+/// Break a line into chunks of at most `width` characters, preferring spaces.
+fn wrap(line: &str, width: usize) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut current = String::new();
+
+    for word in line.split(' ') {
+        let word_len = word.chars().count();
+        let current_len = current.chars().count();
+        if current_len > 0 && current_len + 1 + word_len > width {
+            out.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+
+        // Hard-split words longer than a line
+        while current.chars().count() > width {
+            let head: String = current.chars().take(width).collect();
+            current = current.chars().skip(width).collect();
+            out.push(head);
+        }
+    }
+    out.push(current);
+
+    out
+}
+
+/// Encode text for a PDF string literal in WinAnsi (Latin-1 compatible).
+fn pdf_string(text: &str) -> Vec<u8> {
+    let mut out = Vec::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                out.push(b'\\');
+                out.push(c as u8);
+            }
+            ' '..='~' => out.push(c as u8),
+            '\u{a0}'..='\u{ff}' => out.extend(format!("\\{:03o}", c as u32).into_bytes()),
+            '\t' => out.extend(b"    "),
+            _ => out.push(b'?'),
+        }
+    }
+    out
+}
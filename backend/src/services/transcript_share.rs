@@ -0,0 +1,142 @@
+use chrono::Duration;
+use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation};
+use sqlx::SqlitePool;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{Transcript, TranscriptShareLink};
+use crate::services::transcript;
+use crate::timestamp;
+
+/// Lifetime of a share link when none is given, and the longest allowed.
+pub const DEFAULT_EXPIRY_HOURS: i64 = 72;
+pub const MAX_EXPIRY_HOURS: i64 = 24 * 30;
+
+/// Claims of a share link token.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct ShareClaims {
+    /// Transcript ID.
+    sub: String,
+    /// Share link ID.
+    jti: String,
+    exp: i64,
+}
+
+/// Create a share link for a transcript and sign its token with `secret`.
+/// Returns the stored link and the token.
+pub async fn create(
+    pool: &SqlitePool,
+    secret: &str,
+    transcript: &Transcript,
+    created_by: &str,
+    expires_in_hours: Option<i64>,
+) -> AppResult<(TranscriptShareLink, String)> {
+    let hours = expires_in_hours.unwrap_or(DEFAULT_EXPIRY_HOURS);
+    if !(1..=MAX_EXPIRY_HOURS).contains(&hours) {
+        return Err(AppError::BadRequest(format!(
+            "expires_in_hours must be between 1 and {MAX_EXPIRY_HOURS}"
+        )));
+    }
+
+    let expires_at = timestamp::now() + Duration::hours(hours);
+    let link = sqlx::query_as::<_, TranscriptShareLink>(
+        "INSERT INTO transcript_share_links (id, transcript_id, guild_id, created_by, expires_at) \
+         VALUES (?, ?, ?, ?, ?) RETURNING *",
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(&transcript.id)
+    .bind(&transcript.guild_id)
+    .bind(created_by)
+    .bind(timestamp::format(expires_at))
+    .fetch_one(pool)
+    .await?;
+
+    let claims = ShareClaims {
+        sub: transcript.id.clone(),
+        jti: link.id.clone(),
+        exp: expires_at.and_utc().timestamp(),
+    };
+    let token = jsonwebtoken::encode(&Header::default(), &claims, &EncodingKey::from_secret(secret.as_bytes()))
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to sign share link: {e}")))?;
+
+    Ok((link, token))
+}
+
+/// A transcript's share links, newest first.
+pub async fn list(pool: &SqlitePool, transcript_id: &str) -> AppResult<Vec<TranscriptShareLink>> {
+    let links = sqlx::query_as::<_, TranscriptShareLink>(
+        "SELECT * FROM transcript_share_links WHERE transcript_id = ? ORDER BY created_at DESC",
+    )
+    .bind(transcript_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(links)
+}
+
+/// Revoke a share link so its token stops working.
+pub async fn revoke(
+    pool: &SqlitePool,
+    transcript_id: &str,
+    link_id: &str,
+    revoked_by: &str,
+) -> AppResult<TranscriptShareLink> {
+    let link = sqlx::query_as::<_, TranscriptShareLink>(
+        "UPDATE transcript_share_links SET revoked_at = datetime('now'), revoked_by = ? \
+         WHERE id = ? AND transcript_id = ? AND revoked_at IS NULL RETURNING *",
+    )
+    .bind(revoked_by)
+    .bind(link_id)
+    .bind(transcript_id)
+    .fetch_optional(pool)
+    .await?;
+
+    match link {
+        Some(link) => Ok(link),
+        None => {
+            let exists = sqlx::query_scalar::<_, i64>(
+                "SELECT COUNT(*) FROM transcript_share_links WHERE id = ? AND transcript_id = ?",
+            )
+            .bind(link_id)
+            .bind(transcript_id)
+            .fetch_one(pool)
+            .await?;
+
+            Err(match exists {
+                0 => AppError::NotFound(format!("Share link {link_id} not found")),
+                _ => AppError::BadRequest(format!("Share link {link_id} is already revoked")),
+            })
+        }
+    }
+}
+
+/// Resolve a share token to its transcript, counting the view.
+///
+/// Bad signatures, expired or revoked links all give the same 404 so a
+/// token reveals nothing about why it stopped working.
+pub async fn redeem(pool: &SqlitePool, secret: &str, token: &str) -> AppResult<Transcript> {
+    let invalid = || AppError::NotFound("Share link is invalid or has expired".to_string());
+
+    let claims = jsonwebtoken::decode::<ShareClaims>(
+        token,
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &Validation::default(),
+    )
+    .map_err(|_| invalid())?
+    .claims;
+
+    let counted = sqlx::query(
+        "UPDATE transcript_share_links SET view_count = view_count + 1, last_viewed_at = datetime('now') \
+         WHERE id = ? AND transcript_id = ? AND revoked_at IS NULL AND expires_at > datetime('now')",
+    )
+    .bind(&claims.jti)
+    .bind(&claims.sub)
+    .execute(pool)
+    .await?
+    .rows_affected();
+
+    if counted == 0 {
+        return Err(invalid());
+    }
+
+    transcript::get_transcript(pool, &claims.sub).await
+}
@@ -1,7 +1,9 @@
 use sqlx::SqlitePool;
-use std::sync::Arc;
-use tokio::sync::broadcast;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{broadcast, Notify};
 
+use crate::auth::middleware::GuildAccessCache;
 use crate::config::Config;
 
 #[derive(Debug, Clone, serde::Serialize)]
@@ -16,6 +18,16 @@ pub enum WsEvent {
         ticket_id: i64,
         status: String,
     },
+    /// A ticket is close to or past an SLA deadline.
+    SlaAlert {
+        guild_id: String,
+        ticket_id: i64,
+        /// "first_response" or "resolution".
+        target: String,
+        /// "warning" or "breach".
+        level: String,
+        due_at: String,
+    },
     DashboardLog {
         guild_id: String,
         action: String,
@@ -29,6 +41,11 @@ pub struct AppStateInner {
     pub config: Config,
     pub http: reqwest::Client,
     pub ws_tx: broadcast::Sender<WsEvent>,
+    pub guild_access: GuildAccessCache,
+    /// Number of open WebSocket subscriptions per guild ID.
+    pub ws_subscriptions: Mutex<HashMap<String, usize>>,
+    /// Woken when new `pending_actions` are queued, so bot sockets push them.
+    pub bot_actions: Notify,
 }
 
 pub type AppState = Arc<AppStateInner>;
@@ -41,6 +58,32 @@ impl AppStateInner {
             config,
             http: reqwest::Client::new(),
             ws_tx,
+            guild_access: GuildAccessCache::default(),
+            ws_subscriptions: Mutex::new(HashMap::new()),
+            bot_actions: Notify::new(),
         })
     }
+
+    /// Record a new WebSocket subscriber for a guild.
+    pub fn add_ws_subscription(&self, guild_id: &str) {
+        let mut subs = self.ws_subscriptions.lock().unwrap_or_else(|e| e.into_inner());
+        *subs.entry(guild_id.to_string()).or_insert(0) += 1;
+    }
+
+    /// Drop a WebSocket subscriber for a guild, forgetting the guild at zero.
+    pub fn remove_ws_subscription(&self, guild_id: &str) {
+        let mut subs = self.ws_subscriptions.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(count) = subs.get_mut(guild_id) {
+            *count -= 1;
+            if *count == 0 {
+                subs.remove(guild_id);
+            }
+        }
+    }
+
+    /// Guild IDs with at least one live WebSocket subscriber.
+    pub fn subscribed_guilds(&self) -> Vec<String> {
+        let subs = self.ws_subscriptions.lock().unwrap_or_else(|e| e.into_inner());
+        subs.keys().cloned().collect()
+    }
 }
@@ -0,0 +1,62 @@
+//! Helpers for the `YYYY-MM-DD HH:MM:SS` UTC strings SQLite's `datetime('now')`
+//! produces, which every timestamp column in the schema uses.
+
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+
+use crate::error::{AppError, AppResult};
+
+/// `strftime` pattern matching SQLite's `datetime()` output.
+pub const FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Current UTC time as a naive datetime.
+pub fn now() -> NaiveDateTime {
+    Utc::now().naive_utc()
+}
+
+/// Format a datetime the way SQLite stores it.
+pub fn format(dt: NaiveDateTime) -> String {
+    dt.format(FORMAT).to_string()
+}
+
+/// Parse a stored timestamp column.
+pub fn parse(value: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value, FORMAT).ok()
+}
+
+/// Parse user input in SQLite format, RFC 3339, or as a bare `YYYY-MM-DD`
+/// date (which is completed with `default_time`), returning SQLite format.
+pub fn normalize(value: &str, default_time: &str) -> Option<String> {
+    if let Some(dt) = parse(value) {
+        return Some(format(dt));
+    }
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(format(dt.naive_utc()));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Some(format!("{} {default_time}", date.format("%Y-%m-%d")));
+    }
+    None
+}
+
+/// Parse optional `from`/`to` query filters with [`normalize`]. A bare date
+/// covers the whole day: `from` starts at midnight and `to` ends at 23:59:59.
+pub fn parse_range(from: Option<&str>, to: Option<&str>) -> AppResult<(Option<String>, Option<String>)> {
+    let bound = |name: &str, value: &str, default_time: &str| {
+        normalize(value, default_time).ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "Invalid {name} '{value}'. Use YYYY-MM-DD, YYYY-MM-DD HH:MM:SS or RFC 3339"
+            ))
+        })
+    };
+    let from = from.map(|v| bound("from", v, "00:00:00")).transpose()?;
+    let to = to.map(|v| bound("to", v, "23:59:59")).transpose()?;
+    Ok((from, to))
+}
+
+/// Whole minutes between two stored timestamps, or `None` if `end` is unset
+/// or either fails to parse.
+pub fn minutes_since(start: &str, end: Option<&str>) -> Option<i64> {
+    let start = parse(start)?;
+    let end = parse(end?)?;
+    Some((end - start).num_minutes())
+}
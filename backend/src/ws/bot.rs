@@ -0,0 +1,104 @@
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    response::IntoResponse,
+};
+use futures::{SinkExt, StreamExt};
+use tokio::time::{interval, Duration};
+
+use crate::auth::middleware::BotAuth;
+use crate::models::PendingAction;
+use crate::services::pending_action;
+use crate::state::AppState;
+
+/// Upgrade to the bot's outbox WebSocket. Requires the `x-api-key` header.
+///
+/// Pending actions are claimed and pushed as soon as they are queued (and
+/// re-checked every 15 seconds); the bot replies with `ack` or `fail`.
+pub async fn bot_ws_handler(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    _auth: BotAuth,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| handle_socket(socket, state))
+}
+
+async fn handle_socket(socket: WebSocket, state: AppState) {
+    let (mut sender, mut receiver) = socket.split();
+    let mut poll = interval(Duration::from_secs(15));
+
+    loop {
+        let outgoing = tokio::select! {
+            _ = poll.tick() => push_actions(&state).await,
+            _ = state.bot_actions.notified() => push_actions(&state).await,
+            msg = receiver.next() => match msg {
+                Some(Ok(Message::Text(text))) => vec![handle_command(&state, &text).await],
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+        };
+
+        for message in outgoing {
+            let Ok(json) = serde_json::to_string(&message) else {
+                continue;
+            };
+            if sender.send(Message::Text(json.into())).await.is_err() {
+                // Unacked claims are requeued by the scheduler once their lease expires
+                return;
+            }
+        }
+    }
+}
+
+async fn push_actions(state: &AppState) -> Vec<BotWsMessage> {
+    match pending_action::claim(&state.db, 25, pending_action::DEFAULT_LEASE_SECS).await {
+        Ok(actions) => actions.into_iter().map(|action| BotWsMessage::Action { action }).collect(),
+        Err(e) => {
+            tracing::error!("Bot WebSocket: claiming pending actions failed: {e}");
+            Vec::new()
+        }
+    }
+}
+
+async fn handle_command(state: &AppState, text: &str) -> BotWsMessage {
+    let result = match serde_json::from_str::<BotWsCommand>(text) {
+        Ok(BotWsCommand::Ack { id }) => pending_action::ack(&state.db, id).await,
+        Ok(BotWsCommand::Fail { id, error }) => {
+            let error = error.as_deref().unwrap_or("Action failed");
+            pending_action::fail(&state.db, id, error).await
+        }
+        Err(e) => {
+            return BotWsMessage::Error {
+                message: format!("Invalid command: {e}"),
+            };
+        }
+    };
+
+    match result {
+        Ok(action) => BotWsMessage::Updated { action },
+        Err(e) => BotWsMessage::Error {
+            message: e.to_string(),
+        },
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(tag = "type")]
+enum BotWsCommand {
+    #[serde(rename = "ack")]
+    Ack { id: i64 },
+    #[serde(rename = "fail")]
+    Fail { id: i64, error: Option<String> },
+}
+
+#[derive(serde::Serialize)]
+#[serde(tag = "type")]
+enum BotWsMessage {
+    /// A newly claimed action the bot should perform, then ack or fail.
+    Action { action: PendingAction },
+    /// The action's state after an ack or fail.
+    Updated { action: PendingAction },
+    Error { message: String },
+}
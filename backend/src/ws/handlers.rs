@@ -6,63 +6,111 @@ use axum::{
     response::IntoResponse,
 };
 use futures::{SinkExt, StreamExt};
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, mpsc};
 
+use crate::auth::middleware::{can_manage_guild, AuthUser};
 use crate::state::{AppState, WsEvent};
 
+/// Upgrade to a dashboard WebSocket. The `session_id` cookie is checked
+/// before the upgrade, so anonymous clients get a 401 instead of a socket.
 pub async fn ws_handler(
     ws: WebSocketUpgrade,
     State(state): State<AppState>,
+    user: AuthUser,
 ) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| handle_socket(socket, state))
+    ws.on_upgrade(move |socket| handle_socket(socket, state, user))
 }
 
-async fn handle_socket(socket: WebSocket, state: AppState) {
+async fn handle_socket(socket: WebSocket, state: AppState, user: AuthUser) {
     let (mut sender, mut receiver) = socket.split();
     let mut rx = state.ws_tx.subscribe();
 
+    // Replies to client commands are funnelled through the send task
+    let (reply_tx, mut reply_rx) = mpsc::unbounded_channel::<WsReply>();
+
     // Track which guilds this connection is subscribed to
     let subscribed_guilds = std::sync::Arc::new(tokio::sync::Mutex::new(Vec::<String>::new()));
     let guilds_for_broadcast = subscribed_guilds.clone();
 
-    // Forward broadcast events to this WebSocket client
+    // Forward broadcast events and command replies to this WebSocket client
     let mut send_task = tokio::spawn(async move {
-        while let Ok(event) = rx.recv().await {
-            let guild_id = match &event {
-                WsEvent::Stats { guild_id, .. } => guild_id,
-                WsEvent::TicketUpdate { guild_id, .. } => guild_id,
-                WsEvent::DashboardLog { guild_id, .. } => guild_id,
-            };
+        loop {
+            let json = tokio::select! {
+                event = rx.recv() => match event {
+                    Ok(event) => {
+                        let guild_id = match &event {
+                            WsEvent::Stats { guild_id, .. } => guild_id,
+                            WsEvent::TicketUpdate { guild_id, .. } => guild_id,
+                            WsEvent::SlaAlert { guild_id, .. } => guild_id,
+                            WsEvent::DashboardLog { guild_id, .. } => guild_id,
+                        };
 
-            let guilds = guilds_for_broadcast.lock().await;
-            if guilds.contains(guild_id) {
-                if let Ok(json) = serde_json::to_string(&event) {
-                    if sender.send(Message::Text(json.into())).await.is_err() {
-                        break;
+                        if !guilds_for_broadcast.lock().await.contains(guild_id) {
+                            continue;
+                        }
+                        serde_json::to_string(&event)
                     }
-                }
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                reply = reply_rx.recv() => match reply {
+                    Some(reply) => serde_json::to_string(&reply),
+                    None => break,
+                },
+            };
+
+            if let Ok(json) = json
+                && sender.send(Message::Text(json.into())).await.is_err()
+            {
+                break;
             }
         }
     });
 
     // Handle incoming messages (subscribe/unsubscribe)
     let guilds_for_recv = subscribed_guilds.clone();
+    let recv_state = state.clone();
     let mut recv_task = tokio::spawn(async move {
         while let Some(Ok(msg)) = receiver.next().await {
             match msg {
                 Message::Text(text) => {
-                    if let Ok(cmd) = serde_json::from_str::<WsCommand>(&text) {
-                        let mut guilds = guilds_for_recv.lock().await;
-                        match cmd {
-                            WsCommand::Subscribe { guild_id } => {
-                                if !guilds.contains(&guild_id) {
-                                    guilds.push(guild_id);
+                    let reply = match serde_json::from_str::<WsCommand>(&text) {
+                        Ok(WsCommand::Subscribe { guild_id }) => {
+                            match can_manage_guild(&recv_state, &user, &guild_id).await {
+                                Ok(true) => {
+                                    let mut guilds = guilds_for_recv.lock().await;
+                                    if !guilds.contains(&guild_id) {
+                                        recv_state.add_ws_subscription(&guild_id);
+                                        guilds.push(guild_id.clone());
+                                    }
+                                    WsReply::Subscribed { guild_id }
+                                }
+                                Ok(false) => WsReply::Error {
+                                    message: format!("You cannot manage guild {guild_id}"),
+                                },
+                                Err(e) => {
+                                    tracing::error!("WebSocket guild access check failed: {e}");
+                                    WsReply::Error {
+                                        message: "Could not verify guild access".to_string(),
+                                    }
                                 }
                             }
-                            WsCommand::Unsubscribe { guild_id } => {
+                        }
+                        Ok(WsCommand::Unsubscribe { guild_id }) => {
+                            let mut guilds = guilds_for_recv.lock().await;
+                            if guilds.contains(&guild_id) {
+                                recv_state.remove_ws_subscription(&guild_id);
                                 guilds.retain(|g| g != &guild_id);
                             }
+                            WsReply::Unsubscribed { guild_id }
                         }
+                        Err(e) => WsReply::Error {
+                            message: format!("Invalid command: {e}"),
+                        },
+                    };
+
+                    if reply_tx.send(reply).is_err() {
+                        break;
                     }
                 }
                 Message::Close(_) => break,
@@ -76,6 +124,11 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
         _ = &mut send_task => recv_task.abort(),
         _ = &mut recv_task => send_task.abort(),
     }
+
+    // Release this connection's subscriptions so stats stop being computed
+    for guild_id in subscribed_guilds.lock().await.iter() {
+        state.remove_ws_subscription(guild_id);
+    }
 }
 
 #[derive(serde::Deserialize)]
@@ -86,3 +139,12 @@ enum WsCommand {
     #[serde(rename = "unsubscribe")]
     Unsubscribe { guild_id: String },
 }
+
+/// Direct replies to a client's commands (not broadcast to other sockets).
+#[derive(serde::Serialize)]
+#[serde(tag = "type")]
+enum WsReply {
+    Subscribed { guild_id: String },
+    Unsubscribed { guild_id: String },
+    Error { message: String },
+}
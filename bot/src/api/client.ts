@@ -2,20 +2,46 @@ import type {
   GuildConfig,
   Warn,
   Ticket,
+  TicketMessagePayload,
   TicketCategory,
+  TicketDetail,
+  TicketFormQuestion,
+  TicketStaff,
   TicketBlacklist,
+  TicketDenial,
+  TicketOpenCheck,
   Giveaway,
   Suggestion,
   ReactionRole,
   AutoRole,
   EmbedTemplate,
   Reminder,
+  PendingAction,
   BotConfig,
   Transcript,
   User,
   AddXpResult,
 } from "./types.js";
 
+/**
+ * A non-2xx response from the backend. `body` is the parsed JSON error
+ * when there is one.
+ */
+export class BackendError extends Error {
+  constructor(
+    public status: number,
+    public body: { error?: string; denial?: TicketDenial } | null,
+    text: string,
+  ) {
+    super(`Backend API error ${status}: ${text}`);
+  }
+
+  /** The open policy's reason when a ticket creation was refused. */
+  get denial(): TicketDenial | null {
+    return this.body?.denial ?? null;
+  }
+}
+
 /**
  * HTTP client for the Rust backend API.
  * Replaces all direct Prisma calls in the bot.
@@ -48,9 +74,16 @@ export class BackendClient {
 
     if (!response.ok) {
       const error = await response.text();
-      throw new Error(`Backend API error ${response.status}: ${error}`);
+      let body = null;
+      try {
+        body = JSON.parse(error);
+      } catch {
+        // Not every error has a JSON body
+      }
+      throw new BackendError(response.status, body, error);
     }
 
+    if (response.status === 204) return undefined as T;
     return response.json() as Promise<T>;
   }
 
@@ -87,25 +120,53 @@ export class BackendClient {
 
   async createTicket(data: {
     guildId: string;
-    number: number;
     channelId: string;
     userId: string;
     category?: string;
     subject?: string;
-  }): Promise<Ticket> {
+    answers?: { question_id: number; value: string }[];
+    roleIds?: string[];
+  }): Promise<TicketDetail> {
     return this.post(`/api/v1/bot/guilds/${data.guildId}/tickets`, {
-      number: data.number,
       channel_id: data.channelId,
       user_id: data.userId,
       category: data.category,
       subject: data.subject,
+      answers: data.answers ?? [],
+      role_ids: data.roleIds ?? [],
     });
   }
 
-  async getTicket(guildId: string, ticketId: number): Promise<Ticket> {
+  /**
+   * Check the backend's open policy (blacklist, limits, cooldown, required
+   * roles) before showing a form or creating a channel.
+   */
+  async canOpenTicket(
+    guildId: string,
+    userId: string,
+    options: { category?: string; roleIds?: string[] } = {},
+  ): Promise<TicketOpenCheck> {
+    return this.post(`/api/v1/bot/guilds/${guildId}/tickets/can-open`, {
+      user_id: userId,
+      category: options.category,
+      role_ids: options.roleIds ?? [],
+    });
+  }
+
+  async getTicket(guildId: string, ticketId: number): Promise<TicketDetail> {
     return this.get(`/api/v1/guilds/${guildId}/tickets/${ticketId}`);
   }
 
+  /** The ticket opened in a channel, whatever its status, or null. */
+  async getChannelTicket(channelId: string): Promise<Ticket | null> {
+    try {
+      return await this.get(`/api/v1/bot/ticket-channels/${channelId}`);
+    } catch (error) {
+      if (error instanceof BackendError && error.status === 404) return null;
+      throw error;
+    }
+  }
+
   async listTickets(
     guildId: string,
     status?: string,
@@ -114,16 +175,84 @@ export class BackendClient {
     return this.get(`/api/v1/guilds/${guildId}/tickets${params}`);
   }
 
+  /**
+   * Close a ticket. The backend renders the transcript from the messages
+   * streamed with `ingestTicketMessages`.
+   */
   async closeTicket(
     guildId: string,
     ticketId: number,
     closedBy: string,
-  ): Promise<void> {
-    await this.post(`/api/v1/guilds/${guildId}/tickets/${ticketId}/close`, {
+    names: { guildName?: string; userName?: string; closedByName?: string } = {},
+  ): Promise<{ ticket: Ticket; transcript: Transcript }> {
+    return this.post(`/api/v1/bot/guilds/${guildId}/tickets/${ticketId}/close`, {
       closed_by: closedBy,
+      guild_name: names.guildName,
+      user_name: names.userName,
+      closed_by_name: names.closedByName,
+    });
+  }
+
+  /** Opt a ticket in or out of inactivity auto-close. */
+  async setTicketAutoClose(
+    guildId: string,
+    ticketId: number,
+    enabled: boolean,
+    actorId: string,
+  ): Promise<Ticket> {
+    return this.put(`/api/v1/bot/guilds/${guildId}/tickets/${ticketId}/auto-close`, {
+      enabled,
+      actor_id: actorId,
+    });
+  }
+
+  /**
+   * Mark a staff member available or away for ticket routing. Going away
+   * hands their routed tickets to other staff.
+   */
+  async setStaffAvailability(
+    guildId: string,
+    userId: string,
+    available: boolean,
+  ): Promise<{ staff: TicketStaff; reassigned: Ticket[] }> {
+    return this.put(
+      `/api/v1/bot/guilds/${guildId}/ticket-staff/${userId}/availability`,
+      { available },
+    );
+  }
+
+  /** Create a signed link to a transcript that works without a login. */
+  async createTranscriptShareLink(
+    transcriptId: string,
+    createdBy: string,
+    expiresInHours?: number,
+  ): Promise<{ token: string; url: string }> {
+    return this.post(`/api/v1/bot/transcripts/${transcriptId}/share-links`, {
+      created_by: createdBy,
+      expires_in_hours: expiresInHours,
+    });
+  }
+
+  async ingestTicketMessages(
+    channelId: string,
+    messages: TicketMessagePayload[],
+  ): Promise<{ ticket_id: number; written: number } | undefined> {
+    return this.post(`/api/v1/bot/ticket-channels/${channelId}/messages`, {
+      messages,
     });
   }
 
+  async editTicketMessage(
+    messageId: string,
+    data: Pick<TicketMessagePayload, "content" | "attachments" | "embeds" | "edited_at">,
+  ): Promise<void> {
+    await this.put(`/api/v1/bot/ticket-messages/${messageId}`, data);
+  }
+
+  async deleteTicketMessage(messageId: string): Promise<void> {
+    await this.delete(`/api/v1/bot/ticket-messages/${messageId}`);
+  }
+
   async claimTicket(guildId: string, ticketId: number): Promise<void> {
     await this.post(`/api/v1/guilds/${guildId}/tickets/${ticketId}/claim`);
   }
@@ -139,7 +268,17 @@ export class BackendClient {
   }
 
   async getTicketCategories(guildId: string): Promise<TicketCategory[]> {
-    return this.get(`/api/v1/guilds/${guildId}/ticket-categories`);
+    return this.get(`/api/v1/bot/guilds/${guildId}/ticket-categories`);
+  }
+
+  /** Questions to ask in the ticket modal for a category, in order. */
+  async getTicketCategoryForm(
+    guildId: string,
+    categoryId: number,
+  ): Promise<TicketFormQuestion[]> {
+    return this.get(
+      `/api/v1/bot/guilds/${guildId}/ticket-categories/${categoryId}/form`,
+    );
   }
 
   async createTicketCategory(
@@ -160,29 +299,50 @@ export class BackendClient {
     guildId: string,
     userId: string,
   ): Promise<TicketBlacklist | null> {
-    const list: TicketBlacklist[] = await this.get(
-      `/api/v1/guilds/${guildId}/ticket-blacklist`,
+    const { entries } = await this.listTicketBlacklist(guildId, {
+      search: userId,
+      limit: 100,
+    });
+    return entries.find((b) => b.user_id === userId) ?? null;
+  }
+
+  /** A page of the blacklist; `search` matches users, moderators and reasons. */
+  async listTicketBlacklist(
+    guildId: string,
+    options: { search?: string; page?: number; limit?: number } = {},
+  ): Promise<{ entries: TicketBlacklist[]; total: number; page: number; limit: number }> {
+    const params = new URLSearchParams();
+    if (options.search) params.set("search", options.search);
+    if (options.page) params.set("page", String(options.page));
+    if (options.limit) params.set("limit", String(options.limit));
+    const query = params.toString();
+    return this.get(
+      `/api/v1/bot/guilds/${guildId}/ticket-blacklist${query ? `?${query}` : ""}`,
     );
-    return list.find((b) => b.user_id === userId) ?? null;
   }
 
   async addTicketBlacklist(
     guildId: string,
-    data: { userId: string; reason?: string; addedBy: string },
-  ): Promise<void> {
-    await this.post(`/api/v1/guilds/${guildId}/ticket-blacklist`, {
+    data: { userId: string; reason?: string; addedBy: string; expiresAt?: Date },
+  ): Promise<TicketBlacklist> {
+    return this.post(`/api/v1/bot/guilds/${guildId}/ticket-blacklist`, {
       user_id: data.userId,
       reason: data.reason,
       added_by: data.addedBy,
+      expires_at: data.expiresAt?.toISOString(),
     });
   }
 
   async removeTicketBlacklist(
     guildId: string,
     blacklistId: number,
+    removedBy: string,
+    reason?: string,
   ): Promise<void> {
+    const params = new URLSearchParams({ removed_by: removedBy });
+    if (reason) params.set("reason", reason);
     await this.delete(
-      `/api/v1/guilds/${guildId}/ticket-blacklist/${blacklistId}`,
+      `/api/v1/bot/guilds/${guildId}/ticket-blacklist/${blacklistId}?${params}`,
     );
   }
 
@@ -218,8 +378,8 @@ export class BackendClient {
 
   // ─── XP / Leveling ─────────────────────────────────────────────────
 
-  async addXp(userId: string, amount: number): Promise<AddXpResult> {
-    return this.post("/api/v1/bot/xp", { user_id: userId, amount });
+  async addXp(guildId: string, userId: string, amount?: number): Promise<AddXpResult> {
+    return this.post("/api/v1/bot/xp", { guild_id: guildId, user_id: userId, amount });
   }
 
   async getUser(userId: string): Promise<User | null> {
@@ -430,11 +590,53 @@ export class BackendClient {
     });
   }
 
+  /** The user's reminders that haven't been delivered yet. */
   async getReminders(userId: string): Promise<Reminder[]> {
-    return this.get(`/api/v1/bot/reminders/${userId}`);
+    return this.get(`/api/v1/bot/users/${userId}/reminders`);
+  }
+
+  /**
+   * Lease due reminders for delivery. Each must be acked or failed before
+   * the lease runs out, or it becomes due again.
+   */
+  async claimReminders(limit = 25): Promise<Reminder[]> {
+    return this.post("/api/v1/bot/reminders/claim", { limit });
+  }
+
+  async ackReminder(reminderId: number): Promise<Reminder> {
+    return this.post(`/api/v1/bot/reminders/${reminderId}/ack`);
+  }
+
+  async failReminder(reminderId: number, error: string): Promise<Reminder> {
+    return this.post(`/api/v1/bot/reminders/${reminderId}/fail`, { error });
   }
 
   async deleteReminder(reminderId: number): Promise<void> {
     await this.delete(`/api/v1/bot/reminders/${reminderId}`);
   }
+
+  // ─── Action Outbox ────────────────────────────────────────────────
+
+  /**
+   * Open the outbox WebSocket. The backend pushes `{ type: "Action" }`
+   * messages; reply with `{ type: "ack", id }` or `{ type: "fail", id, error }`.
+   */
+  openActionSocket(): WebSocket {
+    const url = `${this.baseUrl.replace(/^http/, "ws")}/api/v1/bot/ws`;
+    // Node's WebSocket (undici) takes headers in its init object
+    return new WebSocket(url, { headers: { "X-API-Key": this.apiKey } });
+  }
+
+  /** Lease pending actions when the WebSocket is unavailable. */
+  async claimActions(limit = 25): Promise<PendingAction[]> {
+    return this.post("/api/v1/bot/actions/claim", { limit });
+  }
+
+  async ackAction(actionId: number): Promise<PendingAction> {
+    return this.post(`/api/v1/bot/actions/${actionId}/ack`);
+  }
+
+  async failAction(actionId: number, error: string): Promise<PendingAction> {
+    return this.post(`/api/v1/bot/actions/${actionId}/fail`, { error });
+  }
 }
@@ -80,7 +80,7 @@ export interface Ticket {
   guild_id: string;
   category: string | null;
   subject: string | null;
-  status: string;
+  status: TicketStatus;
   priority: string;
   claimed_by: string | null;
   closed_by: string | null;
@@ -89,6 +89,37 @@ export interface Ticket {
   review_rating: number | null;
   last_activity: string;
   created_at: string;
+  inactivity_warned_at: string | null;
+  /** Whether inactivity warnings and auto-close apply. */
+  auto_close: boolean;
+}
+
+export type TicketStatus =
+  | "open"
+  | "awaiting_user"
+  | "on_hold"
+  | "review"
+  | "closed";
+
+export interface TicketMessageAttachment {
+  name: string;
+  url: string;
+  content_type: string | null;
+  size: number | null;
+}
+
+/** A ticket channel message as sent to the backend. */
+export interface TicketMessagePayload {
+  message_id: string;
+  author_id: string;
+  author_name: string;
+  author_avatar: string | null;
+  author_bot: boolean;
+  content: string;
+  attachments: TicketMessageAttachment[];
+  embeds: unknown[];
+  created_at: string;
+  edited_at: string | null;
 }
 
 export interface TicketCategory {
@@ -98,6 +129,64 @@ export interface TicketCategory {
   emoji: string | null;
   description: string | null;
   created_at: string;
+  inactivity_warn_hours: number | null;
+  inactivity_close_hours: number | null;
+  routing: TicketRouting | null;
+  max_open: number | null;
+  required_role: string | null;
+}
+
+export type TicketRouting = "manual" | "round_robin" | "least_open";
+
+/** A member of the staff roster tickets are routed to. */
+export interface TicketStaff {
+  guild_id: string;
+  user_id: string;
+  available: boolean;
+  last_assigned_at: string | null;
+  created_at: string;
+  updated_at: string;
+}
+
+/** A question of a category's ticket form. */
+export interface TicketFormQuestion {
+  id: number;
+  category_id: number;
+  position: number;
+  label: string;
+  style: "short" | "paragraph";
+  required: boolean;
+  min_length: number | null;
+  max_length: number | null;
+  placeholder: string | null;
+  created_at: string;
+}
+
+export interface TicketFormAnswer {
+  ticket_id: number;
+  question_id: number | null;
+  position: number;
+  label: string;
+  value: string;
+}
+
+/** A ticket with the form answers given when it was opened. */
+export interface TicketDetail extends Ticket {
+  answers: TicketFormAnswer[];
+}
+
+/** Why the backend refused to open a ticket, tagged by `reason`. */
+export type TicketDenial =
+  | { reason: "blacklisted"; note: string | null }
+  | { reason: "missing_role"; role_id: string }
+  | { reason: "too_many_open"; limit: number }
+  | { reason: "category_limit"; category: string; limit: number }
+  | { reason: "cooldown"; retry_at: string };
+
+export interface TicketOpenCheck {
+  allowed: boolean;
+  denial: TicketDenial | null;
+  message: string | null;
 }
 
 export interface TicketBlacklist {
@@ -107,6 +196,8 @@ export interface TicketBlacklist {
   reason: string | null;
   added_by: string;
   created_at: string;
+  /** When the entry is lifted; `null` is permanent. */
+  expires_at: string | null;
 }
 
 export interface Giveaway {
@@ -176,6 +267,31 @@ export interface Reminder {
   message: string;
   remind_at: string;
   created_at: string;
+  status: "pending" | "due" | "claimed" | "delivered" | "failed";
+  recurrence: "daily" | "weekly" | "cron" | null;
+  cron_expr: string | null;
+  attempts: number;
+  lease_expires_at: string | null;
+  last_error: string | null;
+  delivered_at: string | null;
+}
+
+/** Something the backend queued for the bot to do in Discord. */
+export interface PendingAction {
+  id: number;
+  guild_id: string;
+  user_id: string;
+  action_type: string;
+  temp_punishment_id: number | null;
+  status: "pending" | "claimed" | "done" | "failed";
+  attempts: number;
+  lease_expires_at: string | null;
+  last_error: string | null;
+  created_at: string;
+  completed_at: string | null;
+  ticket_id: number | null;
+  /** JSON-encoded details, depending on `action_type`. */
+  payload: string | null;
 }
 
 export interface BotConfig {
@@ -201,13 +317,18 @@ export interface Transcript {
   message_count: number;
   html: string;
   created_at: string;
+  theme: "dark" | "light";
 }
 
 export interface AddXpResult {
+  guild_id: string;
   user_id: string;
   xp: number;
   level: number;
+  xp_gained: number;
   leveled_up: boolean;
+  roles_to_add: string[];
+  roles_to_remove: string[];
 }
 
 export interface PaginatedResponse<T> {
@@ -16,7 +16,9 @@ import {
   errorMessage,
   createMessage,
   warningMessage,
+  backendTimestamp,
 } from "../../utils/index.js";
+import { syncTicketChannel } from "../../utils/ticketMessages.js";
 
 const data = new SlashCommandBuilder()
   .setName("ticket")
@@ -76,6 +78,28 @@ const data = new SlashCommandBuilder()
           )
       )
   )
+  .addSubcommand((sub) =>
+    sub
+      .setName("autoclose")
+      .setDescription("Turn inactivity auto-close on or off for the current ticket")
+      .addBooleanOption((opt) =>
+        opt
+          .setName("enabled")
+          .setDescription("Whether the ticket closes itself when inactive")
+          .setRequired(true)
+      )
+  )
+  .addSubcommand((sub) =>
+    sub
+      .setName("availability")
+      .setDescription("Set whether you receive automatically assigned tickets")
+      .addBooleanOption((opt) =>
+        opt
+          .setName("available")
+          .setDescription("Whether new tickets can be assigned to you")
+          .setRequired(true)
+      )
+  )
   .addSubcommandGroup((group) =>
     group
       .setName("category")
@@ -136,6 +160,13 @@ const data = new SlashCommandBuilder()
           .addStringOption((opt) =>
             opt.setName("reason").setDescription("Reason for blacklisting")
           )
+          .addIntegerOption((opt) =>
+            opt
+              .setName("days")
+              .setDescription("Lift the blacklist after this many days (default: permanent)")
+              .setMinValue(1)
+              .setMaxValue(365)
+          )
       )
       .addSubcommand((sub) =>
         sub
@@ -147,11 +178,22 @@ const data = new SlashCommandBuilder()
               .setDescription("User to remove from blacklist")
               .setRequired(true)
           )
+          .addStringOption((opt) =>
+            opt.setName("reason").setDescription("Reason for lifting the blacklist")
+          )
       )
       .addSubcommand((sub) =>
         sub
           .setName("list")
-          .setDescription("List all blacklisted users")
+          .setDescription("List blacklisted users")
+          .addStringOption((opt) =>
+            opt
+              .setName("search")
+              .setDescription("Filter by user ID, moderator ID or reason")
+          )
+          .addIntegerOption((opt) =>
+            opt.setName("page").setDescription("Page number").setMinValue(1)
+          )
       )
   )
   .addSubcommand((sub) =>
@@ -275,10 +317,9 @@ async function execute(
     const channel = interaction.channel as TextChannel;
 
     try {
-      const { tickets } = await client.api.listTickets(guildId, "open");
-      const ticket = tickets.find((t) => t.channel_id === channel.id);
+      const ticket = await client.api.getChannelTicket(channel.id);
 
-      if (!ticket) {
+      if (!ticket || ticket.status === "closed") {
         return interaction.reply({
           ...errorMessage({
             description: "This channel is not an open ticket.",
@@ -291,7 +332,15 @@ async function execute(
         warningMessage({ description: "Closing ticket..." })
       );
 
-      await client.api.closeTicket(guildId, ticket.id, interaction.user.id);
+      await syncTicketChannel(client, channel).catch(() => {});
+      const ticketUser = await client.users
+        .fetch(ticket.user_id)
+        .catch(() => null);
+      await client.api.closeTicket(guildId, ticket.id, interaction.user.id, {
+        guildName: interaction.guild?.name,
+        userName: ticketUser?.tag,
+        closedByName: interaction.user.tag,
+      });
 
       // Delete the channel after a short delay
       setTimeout(async () => {
@@ -317,10 +366,9 @@ async function execute(
     const channel = interaction.channel as TextChannel;
 
     try {
-      const { tickets } = await client.api.listTickets(guildId, "open");
-      const ticket = tickets.find((t) => t.channel_id === channel.id);
+      const ticket = await client.api.getChannelTicket(channel.id);
 
-      if (!ticket) {
+      if (!ticket || ticket.status === "closed") {
         return interaction.reply({
           ...errorMessage({
             description: "This channel is not an open ticket.",
@@ -364,10 +412,9 @@ async function execute(
     const priority = interaction.options.getString("level", true);
 
     try {
-      const { tickets } = await client.api.listTickets(guildId, "open");
-      const ticket = tickets.find((t) => t.channel_id === channel.id);
+      const ticket = await client.api.getChannelTicket(channel.id);
 
-      if (!ticket) {
+      if (!ticket || ticket.status === "closed") {
         return interaction.reply({
           ...errorMessage({
             description: "This channel is not an open ticket.",
@@ -411,6 +458,74 @@ async function execute(
     }
   }
 
+  // ─── Auto-close ─────────────────────────────────────────────────────
+  if (subcommand === "autoclose" && !subcommandGroup) {
+    const channel = interaction.channel as TextChannel;
+    const enabled = interaction.options.getBoolean("enabled", true);
+
+    try {
+      const ticket = await client.api.getChannelTicket(channel.id);
+
+      if (!ticket || ticket.status === "closed") {
+        return interaction.reply({
+          ...errorMessage({
+            description: "This channel is not an open ticket.",
+          }),
+          ephemeral: true,
+        });
+      }
+
+      await client.api.setTicketAutoClose(
+        guildId,
+        ticket.id,
+        enabled,
+        interaction.user.id
+      );
+
+      return interaction.reply(
+        successMessage({
+          description: enabled
+            ? "This ticket will be closed automatically when inactive."
+            : "This ticket will no longer be closed for inactivity.",
+        })
+      );
+    } catch (error) {
+      console.error(error);
+      return interaction.reply({
+        ...errorMessage({ description: "Failed to update auto-close." }),
+        ephemeral: true,
+      });
+    }
+  }
+
+  // ─── Availability ───────────────────────────────────────────────────
+  if (subcommand === "availability" && !subcommandGroup) {
+    const available = interaction.options.getBoolean("available", true);
+
+    try {
+      const { reassigned } = await client.api.setStaffAvailability(
+        guildId,
+        interaction.user.id,
+        available
+      );
+
+      return interaction.reply({
+        ...successMessage({
+          description: available
+            ? "You will now receive assigned tickets."
+            : `You are now away.${reassigned.length > 0 ? ` ${reassigned.length} ticket(s) were handed to other staff.` : ""}`,
+        }),
+        ephemeral: true,
+      });
+    } catch (error) {
+      console.error(error);
+      return interaction.reply({
+        ...errorMessage({ description: "Failed to update availability." }),
+        ephemeral: true,
+      });
+    }
+  }
+
   // ─── Category Add ──────────────────────────────────────────────────
   if (subcommandGroup === "category" && subcommand === "add") {
     const name = interaction.options.getString("name", true);
@@ -514,6 +629,10 @@ async function execute(
   if (subcommandGroup === "blacklist" && subcommand === "add") {
     const user = interaction.options.getUser("user", true);
     const reason = interaction.options.getString("reason");
+    const days = interaction.options.getInteger("days");
+    const expiresAt = days
+      ? new Date(Date.now() + days * 24 * 60 * 60 * 1000)
+      : undefined;
 
     try {
       const existing = await client.api.checkTicketBlacklist(guildId, user.id);
@@ -531,11 +650,15 @@ async function execute(
         userId: user.id,
         reason: reason ?? undefined,
         addedBy: interaction.user.id,
+        expiresAt,
       });
 
+      const until = expiresAt
+        ? `\n**Until:** <t:${Math.floor(expiresAt.getTime() / 1000)}:f>`
+        : "";
       return interaction.reply(
         successMessage({
-          description: `${user.toString()} has been blacklisted from creating tickets.${reason ? `\n**Reason:** ${reason}` : ""}`,
+          description: `${user.toString()} has been blacklisted from creating tickets.${reason ? `\n**Reason:** ${reason}` : ""}${until}`,
         })
       );
     } catch (error) {
@@ -550,6 +673,7 @@ async function execute(
   // ─── Blacklist Remove ──────────────────────────────────────────────
   if (subcommandGroup === "blacklist" && subcommand === "remove") {
     const user = interaction.options.getUser("user", true);
+    const reason = interaction.options.getString("reason");
 
     try {
       const blacklistEntry = await client.api.checkTicketBlacklist(
@@ -566,7 +690,12 @@ async function execute(
         });
       }
 
-      await client.api.removeTicketBlacklist(guildId, blacklistEntry.id);
+      await client.api.removeTicketBlacklist(
+        guildId,
+        blacklistEntry.id,
+        interaction.user.id,
+        reason ?? undefined
+      );
 
       return interaction.reply(
         successMessage({
@@ -586,35 +715,37 @@ async function execute(
 
   // ─── Blacklist List ────────────────────────────────────────────────
   if (subcommandGroup === "blacklist" && subcommand === "list") {
+    const search = interaction.options.getString("search");
+    const page = interaction.options.getInteger("page") ?? 1;
+
     try {
-      // The checkTicketBlacklist method internally fetches the full list
-      // from GET /api/v1/guilds/{guildId}/ticket-blacklist.
-      // We access the private get() method to retrieve the complete list.
-      const blacklist = await (client.api as any).get(
-        `/api/v1/guilds/${guildId}/ticket-blacklist`
-      ) as Array<{
-        id: number;
-        user_id: string;
-        reason: string | null;
-        added_by: string;
-        created_at: string;
-      }>;
-
-      if (!blacklist || blacklist.length === 0) {
+      const { entries, total, limit } = await client.api.listTicketBlacklist(
+        guildId,
+        { search: search ?? undefined, page, limit: 15 }
+      );
+
+      if (entries.length === 0) {
         return interaction.reply(
-          warningMessage({ description: "No blacklisted users." })
+          warningMessage({
+            description:
+              total > 0 ? `No entries on page ${page}.` : "No blacklisted users.",
+          })
         );
       }
 
-      const lines = blacklist.map(
-        (entry, i) =>
-          `**${i + 1}.** <@${entry.user_id}>${entry.reason ? ` - ${entry.reason}` : ""} (by <@${entry.added_by}>)`
-      );
+      const lines = entries.map((entry, i) => {
+        const until = entry.expires_at
+          ? ` until <t:${backendTimestamp(entry.expires_at)}:f>`
+          : "";
+        return `**${(page - 1) * limit + i + 1}.** <@${entry.user_id}>${entry.reason ? ` - ${entry.reason}` : ""} (by <@${entry.added_by}>)${until}`;
+      });
+      const pages = Math.ceil(total / limit);
 
       return interaction.reply(
         createMessage({
           title: "Ticket Blacklist",
           description: lines.join("\n"),
+          footer: `Page ${page} of ${pages} - ${total} entr${total === 1 ? "y" : "ies"}`,
           color: "Primary",
         })
       );
@@ -5,6 +5,7 @@ import {
   successMessage,
   errorMessage,
   createMessage,
+  backendTimestamp,
 } from "../../utils/index.js";
 
 /**
@@ -171,10 +172,9 @@ export default {
 
           const reminderList = reminders
             .map((r) => {
-              const timestamp = Math.floor(
-                new Date(r.remind_at).getTime() / 1000,
-              );
-              return `**ID \`${r.id}\`** - <t:${timestamp}:R>\n${r.message}`;
+              const timestamp = backendTimestamp(r.remind_at);
+              const repeats = r.recurrence ? ` (${r.recurrence})` : "";
+              return `**ID \`${r.id}\`** - <t:${timestamp}:R>${repeats}\n${r.message}`;
             })
             .join("\n\n");
 
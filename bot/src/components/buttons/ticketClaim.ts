@@ -12,10 +12,9 @@ export default {
 
     try {
       // Find the ticket for this channel
-      const tickets = await client.api.listTickets(guildId, "open");
-      const ticket = tickets.tickets.find((t) => t.channel_id === channelId);
+      const ticket = await client.api.getChannelTicket(channelId);
 
-      if (!ticket) {
+      if (!ticket || ticket.status === "closed") {
         await interaction.reply({
           content: "No ticket found for this channel.",
           ephemeral: true,
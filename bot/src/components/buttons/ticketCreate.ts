@@ -9,6 +9,7 @@ import {
 import type { ButtonComponent } from "../../types/index.js";
 import { Bot } from "../../client/Bot.js";
 import { logger } from "../../utils/logger.js";
+import { memberRoleIds } from "../../utils/permissions.js";
 
 export default {
   customId: "ticket_create",
@@ -16,22 +17,17 @@ export default {
   async execute(interaction, client: Bot) {
     const guildId = interaction.guildId!;
 
-    // Check if user is blacklisted
+    // Check the open policy (blacklist, limits, cooldown, required role)
     try {
-      const blacklist = await client.api.checkTicketBlacklist(
-        guildId,
-        interaction.user.id,
-      );
+      const check = await client.api.canOpenTicket(guildId, interaction.user.id, {
+        roleIds: memberRoleIds(interaction.member),
+      });
 
-      if (blacklist) {
+      if (!check.allowed) {
         await interaction.reply({
           embeds: [
             new EmbedBuilder()
-              .setDescription(
-                blacklist.reason
-                  ? `You are blacklisted from tickets.\n**Reason:** ${blacklist.reason}`
-                  : "You are blacklisted from tickets.",
-              )
+              .setDescription(check.message ?? "You can't open a ticket right now.")
               .setColor(0xed4245),
           ],
           ephemeral: true,
@@ -39,7 +35,7 @@ export default {
         return;
       }
     } catch (error) {
-      logger.error("Failed to check ticket blacklist:", error);
+      logger.error("Failed to check ticket policy:", error);
     }
 
     // Check if categories exist
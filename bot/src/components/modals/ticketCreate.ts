@@ -9,7 +9,9 @@ import {
 } from "discord.js";
 import type { ModalComponent } from "../../types/index.js";
 import { Bot } from "../../client/Bot.js";
+import { BackendError } from "../../api/client.js";
 import { logger } from "../../utils/logger.js";
+import { memberRoleIds } from "../../utils/permissions.js";
 
 export default {
   customId: /^ticket_modal/,
@@ -19,8 +21,17 @@ export default {
     const guild = interaction.guild!;
     const user = interaction.user;
 
-    // Get subject from modal fields
-    const subject = interaction.fields.getTextInputValue("subject") || null;
+    // Category forms replace the subject field with their own questions
+    const fields = interaction.fields.fields;
+    const subject = fields.has("subject")
+      ? interaction.fields.getTextInputValue("subject") || null
+      : null;
+    const answers = [...fields.keys()]
+      .filter((id) => id.startsWith("answer_"))
+      .map((id) => ({
+        question_id: Number(id.slice("answer_".length)),
+        value: interaction.fields.getTextInputValue(id),
+      }));
 
     // Extract category from customId if present (ticket_modal_CategoryName)
     const parts = interaction.customId.split("_");
@@ -40,9 +51,6 @@ export default {
         return;
       }
 
-      // Get current ticket count and increment
-      const ticketNumber = config.ticket_counter + 1;
-
       // Build permission overwrites
       const permissionOverwrites: Array<{
         id: string;
@@ -91,34 +99,46 @@ export default {
         });
       }
 
-      // Create channel name
-      const channelName = category
-        ? `${category.toLowerCase()}-${ticketNumber.toString().padStart(4, "0")}`
-        : `ticket-${ticketNumber.toString().padStart(4, "0")}`;
-
-      // Create the Discord channel
+      // Create the Discord channel; it is renamed once the backend assigns a number
       const channel = await guild.channels.create({
-        name: channelName,
+        name: category ? `${category.toLowerCase()}-new` : "ticket-new",
         type: ChannelType.GuildText,
         parent: config.ticket_category_id,
         topic: `Ticket by ${user.tag} | ${category ?? "General"} | ${subject ?? "No subject"}`,
         permissionOverwrites: permissionOverwrites as any,
       });
 
-      // Save ticket via the backend
-      await client.api.createTicket({
-        guildId,
-        number: ticketNumber,
-        channelId: channel.id,
-        userId: user.id,
-        category: category ?? undefined,
-        subject: subject ?? undefined,
-      });
+      // Save ticket via the backend, which assigns the ticket number and
+      // enforces the open policy again
+      let ticket;
+      try {
+        ticket = await client.api.createTicket({
+          guildId,
+          channelId: channel.id,
+          userId: user.id,
+          category: category ?? undefined,
+          subject: subject ?? undefined,
+          answers,
+          roleIds: memberRoleIds(interaction.member),
+        });
+      } catch (error) {
+        // Don't leave an empty channel behind
+        await channel.delete().catch(() => {});
+        if (error instanceof BackendError && error.denial) {
+          await interaction.editReply({
+            content: error.body?.error ?? "You can't open a ticket right now.",
+          });
+          return;
+        }
+        throw error;
+      }
+      const ticketNumber = ticket.number;
 
-      // Update ticket counter in guild config
-      await client.api.updateGuildConfig(guildId, {
-        ticket_counter: ticketNumber,
-      });
+      await channel.setName(
+        category
+          ? `${category.toLowerCase()}-${ticketNumber.toString().padStart(4, "0")}`
+          : `ticket-${ticketNumber.toString().padStart(4, "0")}`,
+      );
 
       // Build welcome embed
       const welcomeEmbed = new EmbedBuilder()
@@ -131,7 +151,11 @@ export default {
             "",
             category ? `**Category:** ${category}` : null,
             subject ? `**Subject:** ${subject}` : null,
+            ...ticket.answers.map(
+              (a) => `**${a.label}:** ${a.value.slice(0, 700)}`,
+            ),
             `**Priority:** Normal`,
+            ticket.claimed_by ? `**Assigned to:** <@${ticket.claimed_by}>` : null,
             "",
             "A staff member will respond to you shortly.",
           ]
@@ -159,10 +183,13 @@ export default {
             .setEmoji("🔒"),
         );
 
-      // Send welcome message with ping
-      const pingContent = config.ticket_support_role
-        ? `<@${user.id}> <@&${config.ticket_support_role}>`
-        : `<@${user.id}>`;
+      // Send welcome message with ping; routed tickets ping their assignee
+      // instead of the whole support role
+      const pingContent = ticket.claimed_by
+        ? `<@${user.id}> <@${ticket.claimed_by}>`
+        : config.ticket_support_role
+          ? `<@${user.id}> <@&${config.ticket_support_role}>`
+          : `<@${user.id}>`;
 
       await channel.send({
         content: pingContent,
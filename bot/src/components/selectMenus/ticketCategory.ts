@@ -1,4 +1,5 @@
 import {
+  EmbedBuilder,
   ModalBuilder,
   TextInputBuilder,
   TextInputStyle,
@@ -7,6 +8,7 @@ import {
 import type { SelectMenuComponent } from "../../types/index.js";
 import { Bot } from "../../client/Bot.js";
 import { logger } from "../../utils/logger.js";
+import { memberRoleIds } from "../../utils/permissions.js";
 
 export default {
   customId: "ticket_category_select",
@@ -16,7 +18,62 @@ export default {
     const guildId = interaction.guildId!;
 
     try {
-      // Get modal config from the guild config
+      // Categories can have their own limits and required role
+      const check = await client.api.canOpenTicket(guildId, interaction.user.id, {
+        category,
+        roleIds: memberRoleIds(interaction.member),
+      });
+      if (!check.allowed) {
+        await interaction.reply({
+          embeds: [
+            new EmbedBuilder()
+              .setDescription(check.message ?? "You can't open a ticket right now.")
+              .setColor(0xed4245),
+          ],
+          ephemeral: true,
+        });
+        return;
+      }
+
+      // Show modal with category encoded in customId
+      const modal = new ModalBuilder()
+        .setCustomId(`ticket_modal_${category}`)
+        .setTitle(`Ticket - ${category}`);
+
+      // Use the category's own form when it has one
+      const categories = await client.api.getTicketCategories(guildId);
+      const selected = categories.find((c) => c.name === category);
+      const form = selected
+        ? await client.api.getTicketCategoryForm(guildId, selected.id)
+        : [];
+
+      if (form.length > 0) {
+        for (const question of form) {
+          const input = new TextInputBuilder()
+            .setCustomId(`answer_${question.id}`)
+            .setLabel(question.label)
+            .setStyle(
+              question.style === "paragraph"
+                ? TextInputStyle.Paragraph
+                : TextInputStyle.Short,
+            )
+            .setRequired(question.required);
+          if (question.placeholder) input.setPlaceholder(question.placeholder);
+          if (question.min_length !== null)
+            input.setMinLength(question.min_length);
+          if (question.max_length !== null)
+            input.setMaxLength(question.max_length);
+
+          modal.addComponents(
+            new ActionRowBuilder<TextInputBuilder>().addComponents(input),
+          );
+        }
+
+        await interaction.showModal(modal);
+        return;
+      }
+
+      // Otherwise fall back to the guild's single subject field
       const config = await client.api.getGuildConfig(guildId);
 
       const label = config.ticket_modal_label || "Subject (optional)";
@@ -25,11 +82,6 @@ export default {
         "Briefly describe your issue...";
       const required = config.ticket_modal_required === 1;
 
-      // Show modal with category encoded in customId
-      const modal = new ModalBuilder()
-        .setCustomId(`ticket_modal_${category}`)
-        .setTitle(`Ticket - ${category}`);
-
       const subjectInput = new TextInputBuilder()
         .setCustomId("subject")
         .setLabel(label)
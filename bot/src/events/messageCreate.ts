@@ -2,21 +2,39 @@ import { type Message, type TextChannel } from "discord.js";
 import type { Event } from "../types/index.js";
 import { Bot } from "../client/Bot.js";
 import { logger } from "../utils/logger.js";
+import { isTicketChannel, toTicketMessage } from "../utils/ticketMessages.js";
 
 export default {
   name: "messageCreate",
 
   async execute(client: Bot, message: Message) {
+    // Stream ticket channel messages (the bot's own included) for transcripts.
+    // The backend also bumps the ticket's last activity.
+    if (message.guild && isTicketChannel(message.channel as TextChannel)) {
+      client.api
+        .ingestTicketMessages(message.channel.id, [toTicketMessage(message)])
+        .catch((error) => logger.error("Failed to store ticket message:", error));
+    }
+
     // Ignore bots
     if (message.author.bot) return;
     if (!message.guild) return;
 
     const guildId = message.guild.id;
 
-    // Add XP on message (cooldown handled by the backend)
+    // Add XP on message (amount and cooldown come from the guild's config)
     try {
-      const xpAmount = Math.floor(Math.random() * 10) + 5; // 5-14 XP per message
-      const result = await client.api.addXp(message.author.id, xpAmount);
+      const result = await client.api.addXp(guildId, message.author.id);
+
+      // Apply level reward roles computed by the backend
+      if (message.member && (result.roles_to_add.length || result.roles_to_remove.length)) {
+        try {
+          if (result.roles_to_add.length) await message.member.roles.add(result.roles_to_add);
+          if (result.roles_to_remove.length) await message.member.roles.remove(result.roles_to_remove);
+        } catch (error) {
+          logger.error("Failed to apply level reward roles:", error);
+        }
+      }
 
       // If the user leveled up, send a level-up message
       if (result.leveled_up) {
@@ -44,23 +62,5 @@ export default {
     } catch {
       // XP add failed (likely on cooldown), silently ignore
     }
-
-    // Update ticket last activity if the message is in a ticket channel
-    try {
-      const tickets = await client.api.listTickets(guildId, "open");
-
-      const ticket = tickets.tickets.find(
-        (t) => t.channel_id === message.channel.id,
-      );
-
-      if (ticket) {
-        // The backend will handle updating the last_activity timestamp
-        // We just need to note that this channel has activity
-        // For now, we fetch the ticket to trigger any activity-related logic
-        await client.api.getTicket(guildId, ticket.id);
-      }
-    } catch {
-      // Silently ignore ticket activity update failures
-    }
   },
 } satisfies Event<"messageCreate">;
@@ -0,0 +1,19 @@
+import type { Message, PartialMessage, TextChannel } from "discord.js";
+import type { Event } from "../types/index.js";
+import { Bot } from "../client/Bot.js";
+import { isTicketChannel } from "../utils/ticketMessages.js";
+
+export default {
+  name: "messageDelete",
+
+  async execute(client: Bot, message: Message | PartialMessage) {
+    if (!message.guild) return;
+    if (!isTicketChannel(message.channel as TextChannel)) return;
+
+    try {
+      await client.api.deleteTicketMessage(message.id);
+    } catch {
+      // Messages sent before the ticket was tracked are not stored
+    }
+  },
+} satisfies Event<"messageDelete">;
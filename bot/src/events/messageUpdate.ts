@@ -0,0 +1,23 @@
+import type { Message, PartialMessage, TextChannel } from "discord.js";
+import type { Event } from "../types/index.js";
+import { Bot } from "../client/Bot.js";
+import { isTicketChannel, toTicketMessageEdit } from "../utils/ticketMessages.js";
+
+export default {
+  name: "messageUpdate",
+
+  async execute(
+    client: Bot,
+    _oldMessage: Message | PartialMessage,
+    newMessage: Message | PartialMessage,
+  ) {
+    if (!newMessage.guild) return;
+    if (!isTicketChannel(newMessage.channel as TextChannel)) return;
+
+    try {
+      await client.api.editTicketMessage(newMessage.id, toTicketMessageEdit(newMessage));
+    } catch {
+      // Messages sent before the ticket was tracked are not stored
+    }
+  },
+} satisfies Event<"messageUpdate">;
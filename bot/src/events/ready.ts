@@ -2,6 +2,8 @@ import { ActivityType, REST, Routes } from "discord.js";
 import type { Event } from "../types/index.js";
 import { Bot } from "../client/Bot.js";
 import { logger } from "../utils/logger.js";
+import { startActionWorker } from "../workers/actions.js";
+import { startReminderWorker } from "../workers/reminders.js";
 
 export default {
   name: "clientReady",
@@ -11,6 +13,10 @@ export default {
     logger.info(`Logged in as ${client.user?.tag}`);
     logger.info(`Serving ${client.guilds.cache.size} guild(s)`);
 
+    // Deliver due reminders and run outbox actions queued by the backend
+    startReminderWorker(client);
+    startActionWorker(client);
+
     // Deploy slash commands to Discord API
     try {
       const commands = client.commands.map((cmd) => cmd.data.toJSON());
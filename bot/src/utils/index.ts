@@ -1,3 +1,4 @@
 export * from "./logger.js";
 export * from "./messages.js";
 export * from "./permissions.js";
+export * from "./time.js";
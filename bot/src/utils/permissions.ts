@@ -1,9 +1,22 @@
 import {
+  type APIInteractionGuildMember,
   type GuildMember,
   type PermissionResolvable,
   PermissionFlagsBits,
 } from "discord.js";
 
+/**
+ * Role IDs of an interaction's member, cached or not.
+ */
+export function memberRoleIds(
+  member: GuildMember | APIInteractionGuildMember | null,
+): string[] {
+  if (!member) return [];
+  return Array.isArray(member.roles)
+    ? member.roles
+    : [...member.roles.cache.keys()];
+}
+
 /**
  * Check if a member has all the specified permissions.
  */
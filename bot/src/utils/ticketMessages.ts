@@ -0,0 +1,76 @@
+import type { Message, PartialMessage, TextChannel } from "discord.js";
+import type { Bot } from "../client/Bot.js";
+import type { TicketMessagePayload } from "../api/types.js";
+
+/**
+ * Ticket channels are created as `ticket-new` and renamed `ticket-0001`.
+ * Other channels matching the prefix (e.g. `ticket-logs`) are dropped by
+ * the backend, which answers 204 for channels that never held a ticket.
+ */
+export function isTicketChannel(channel: { name?: string } | null): boolean {
+  return channel?.name?.startsWith("ticket-") ?? false;
+}
+
+/** Convert a Discord message into the backend's ticket message payload. */
+export function toTicketMessage(message: Message): TicketMessagePayload {
+  return {
+    message_id: message.id,
+    author_id: message.author.id,
+    author_name: message.author.tag,
+    author_avatar: message.author.displayAvatarURL({ size: 64 }),
+    author_bot: message.author.bot,
+    content: message.content,
+    attachments: message.attachments.map((att) => ({
+      name: att.name,
+      url: att.url,
+      content_type: att.contentType,
+      size: att.size,
+    })),
+    embeds: message.embeds.map((embed) => embed.toJSON()),
+    created_at: message.createdAt.toISOString(),
+    edited_at: message.editedAt?.toISOString() ?? null,
+  };
+}
+
+/** Convert an edited message into the payload for an edit. */
+export function toTicketMessageEdit(message: Message | PartialMessage) {
+  return {
+    content: message.content ?? "",
+    attachments: message.attachments.map((att) => ({
+      name: att.name,
+      url: att.url,
+      content_type: att.contentType,
+      size: att.size,
+    })),
+    embeds: message.embeds.map((embed) => embed.toJSON()),
+    edited_at: (message.editedAt ?? new Date()).toISOString(),
+  };
+}
+
+/**
+ * Re-send up to 500 of a ticket channel's messages so the backend has any
+ * it missed (e.g. while the bot was offline). Already stored messages are
+ * updated in place.
+ */
+export async function syncTicketChannel(
+  client: Bot,
+  channel: TextChannel,
+): Promise<void> {
+  let lastId: string | undefined;
+  let synced = 0;
+
+  while (synced < 500) {
+    const fetched = await channel.messages.fetch({
+      limit: 100,
+      ...(lastId && { before: lastId }),
+    });
+    if (fetched.size === 0) break;
+
+    await client.api.ingestTicketMessages(
+      channel.id,
+      [...fetched.values()].map(toTicketMessage),
+    );
+    synced += fetched.size;
+    lastId = fetched.last()?.id;
+  }
+}
@@ -0,0 +1,7 @@
+/**
+ * Unix seconds for a backend timestamp. The backend stores UTC as
+ * `YYYY-MM-DD HH:MM:SS`, which `Date` would otherwise read as local time.
+ */
+export function backendTimestamp(value: string): number {
+  return Math.floor(Date.parse(`${value.replace(" ", "T")}Z`) / 1000);
+}
@@ -0,0 +1,286 @@
+import type { SendableChannels } from "discord.js";
+import type { Bot } from "../client/Bot.js";
+import type { PendingAction } from "../api/types.js";
+import {
+  backendTimestamp,
+  errorMessage,
+  infoMessage,
+  warningMessage,
+} from "../utils/index.js";
+import { logger } from "../utils/logger.js";
+
+/**
+ * Performs one outbox action in Discord. Throwing fails the action so the
+ * backend retries it.
+ */
+export type ActionHandler = (
+  client: Bot,
+  action: PendingAction,
+  payload: Record<string, unknown>,
+) => Promise<void>;
+
+/** Discord's "Unknown Ban" error: the user is already unbanned. */
+const UNKNOWN_BAN = 10026;
+
+const handlers: Record<string, ActionHandler> = {
+  async unban(client, action) {
+    const guild = await client.guilds.fetch(action.guild_id);
+    try {
+      await guild.members.unban(action.user_id, "Temporary ban expired");
+    } catch (error) {
+      if ((error as { code?: number }).code !== UNKNOWN_BAN) throw error;
+    }
+  },
+
+  async unmute(client, action) {
+    const guild = await client.guilds.fetch(action.guild_id);
+    // Members who left have no timeout to lift
+    const member = await guild.members.fetch(action.user_id).catch(() => null);
+    await member?.timeout(null, "Temporary mute expired");
+  },
+
+  async sla_warning(client, _action, payload) {
+    await slaNotice(client, payload, false);
+  },
+
+  async sla_breach(client, _action, payload) {
+    await slaNotice(client, payload, true);
+  },
+
+  async inactivity_warning(client, action, payload) {
+    const channel = await ticketChannel(client, payload);
+    if (!channel) return;
+
+    const lastActivity = payload["last_activity"];
+    const closeAt = payload["close_at"];
+    const since =
+      typeof lastActivity === "string"
+        ? ` since <t:${backendTimestamp(lastActivity)}:R>`
+        : "";
+    const outcome =
+      typeof closeAt === "string"
+        ? `It will be closed automatically <t:${backendTimestamp(closeAt)}:R> unless someone replies.`
+        : "Reply if you still need help.";
+
+    await channel.send({
+      ...warningMessage({
+        title: "Inactive Ticket",
+        description: `<@${action.user_id}> this ticket has had no activity${since}. ${outcome}`,
+      }),
+      allowedMentions: { users: [action.user_id] },
+    });
+  },
+
+  async inactivity_close(client, action, payload) {
+    // The backend already closed the ticket and saved its transcript
+    const channel = await ticketChannel(client, payload);
+    const transcriptId = payload["transcript_id"];
+
+    if (typeof transcriptId === "string") {
+      try {
+        const owner = await client.users.fetch(action.user_id);
+        const share = await client.api.createTranscriptShareLink(
+          transcriptId,
+          client.user!.id,
+        );
+        await owner.send(
+          `Your ticket ${ticketLabel(payload)} was closed after a period of inactivity. ` +
+            `You can read the transcript here for the next 3 days: ${share.url}`,
+        );
+      } catch {
+        // DMs closed or link creation failed; the transcript is still saved
+      }
+    }
+
+    if (!channel) return;
+    await channel.send({
+      ...warningMessage({
+        title: "Ticket Closed",
+        description:
+          "This ticket was closed for inactivity. This channel will be deleted in 5 seconds...",
+      }),
+    });
+    await new Promise((resolve) => setTimeout(resolve, 5000));
+    await channel.delete();
+  },
+
+  async ticket_reassigned(client, _action, payload) {
+    const channel = await ticketChannel(client, payload);
+    if (!channel) return;
+
+    const from = payload["from"];
+    const to = payload["to"];
+    const outcome =
+      typeof to === "string"
+        ? `is now assigned to <@${to}>.`
+        : "is back in the unclaimed queue.";
+    const description =
+      typeof from === "string"
+        ? `<@${from}> is unavailable, so this ticket ${outcome}`
+        : `This ticket ${outcome}`;
+
+    await channel.send({
+      ...infoMessage({ title: "Ticket Reassigned", description }),
+      allowedMentions: { users: typeof to === "string" ? [to] : [] },
+    });
+  },
+};
+
+const SLA_TARGETS: Record<string, string> = {
+  first_response: "First response",
+  resolution: "Resolution",
+};
+
+/**
+ * Tell a ticket's channel, and whoever claimed it, that an SLA deadline is
+ * close or has passed.
+ */
+async function slaNotice(
+  client: Bot,
+  payload: Record<string, unknown>,
+  breached: boolean,
+): Promise<void> {
+  const channel = await ticketChannel(client, payload);
+  if (!channel) return; // Nobody left to notify
+
+  const target = String(payload["target"]);
+  const dueAt = payload["due_at"];
+  const claimedBy = payload["claimed_by"];
+  const when =
+    typeof dueAt === "string" && dueAt ? `<t:${backendTimestamp(dueAt)}:R>` : "soon";
+  const mention = typeof claimedBy === "string" ? `<@${claimedBy}> ` : "";
+  const label = SLA_TARGETS[target] ?? target;
+
+  const options = {
+    title: breached ? "SLA Breached" : "SLA Deadline Approaching",
+    description: breached
+      ? `${mention}${label} for ticket ${ticketLabel(payload)} was due ${when}.`
+      : `${mention}${label} for ticket ${ticketLabel(payload)} is due ${when}.`,
+  };
+  await channel.send({
+    ...(breached ? errorMessage(options) : warningMessage(options)),
+    allowedMentions: { users: typeof claimedBy === "string" ? [claimedBy] : [] },
+  });
+}
+
+/** The ticket channel named in an action's payload, if it still exists. */
+async function ticketChannel(
+  client: Bot,
+  payload: Record<string, unknown>,
+): Promise<SendableChannels | null> {
+  const channelId = payload["channel_id"];
+  if (typeof channelId !== "string") return null;
+  const channel = await client.channels.fetch(channelId).catch(() => null);
+  return channel?.isSendable() ? channel : null;
+}
+
+function ticketLabel(payload: Record<string, unknown>): string {
+  return `#${String(payload["ticket_number"]).padStart(4, "0")}`;
+}
+
+/** How often actions are claimed while the WebSocket is down. */
+const POLL_INTERVAL_MS = 15_000;
+/** Delay before reconnecting a dropped WebSocket. */
+const RECONNECT_DELAY_MS = 30_000;
+
+/**
+ * Work through the backend's action outbox (unbans, unmutes, ticket
+ * notices). Actions are pushed over `/api/v1/bot/ws`; while the socket is
+ * down they are claimed over HTTP instead.
+ */
+export function startActionWorker(client: Bot): void {
+  let socket: WebSocket | null = null;
+  let polling = false;
+
+  const poll = async () => {
+    if (socket || polling) return;
+    polling = true;
+    try {
+      const actions = await client.api.claimActions();
+      for (const action of actions) {
+        const error = await perform(client, action);
+        await (error === null
+          ? client.api.ackAction(action.id)
+          : client.api.failAction(action.id, error)
+        ).catch((e) => logger.error(`Failed to report action ${action.id}:`, e));
+      }
+    } catch (error) {
+      logger.error("Failed to claim pending actions:", error);
+    } finally {
+      polling = false;
+    }
+  };
+
+  const connect = () => {
+    let ws: WebSocket;
+    try {
+      ws = client.api.openActionSocket();
+    } catch (error) {
+      logger.error("Failed to open action WebSocket:", error);
+      setTimeout(connect, RECONNECT_DELAY_MS);
+      return;
+    }
+
+    ws.addEventListener("open", () => {
+      socket = ws;
+      logger.info("Action WebSocket connected");
+    });
+    ws.addEventListener("message", async (event) => {
+      let message: { type?: string; action?: PendingAction; message?: string };
+      try {
+        message = JSON.parse(String(event.data));
+      } catch {
+        return;
+      }
+      if (message.type === "Error") {
+        logger.warn(`Action WebSocket: ${message.message}`);
+      }
+      if (message.type !== "Action" || !message.action) return;
+
+      const action = message.action;
+      const error = await perform(client, action);
+      if (ws.readyState !== WebSocket.OPEN) return; // The lease lapses and it is retried
+      ws.send(
+        JSON.stringify(
+          error === null
+            ? { type: "ack", id: action.id }
+            : { type: "fail", id: action.id, error },
+        ),
+      );
+    });
+    ws.addEventListener("close", () => {
+      if (socket === ws) {
+        logger.warn("Action WebSocket closed, polling until it reconnects");
+      }
+      socket = null;
+      setTimeout(connect, RECONNECT_DELAY_MS);
+    });
+    ws.addEventListener("error", () => {
+      // A close event follows and schedules the reconnect
+    });
+  };
+
+  setInterval(poll, POLL_INTERVAL_MS);
+  connect();
+}
+
+/** Run an action's handler, returning the error to report, if any. */
+async function perform(
+  client: Bot,
+  action: PendingAction,
+): Promise<string | null> {
+  const handler = handlers[action.action_type];
+  if (!handler) return `Unsupported action type '${action.action_type}'`;
+
+  try {
+    const payload = action.payload ? JSON.parse(action.payload) : {};
+    await handler(client, action, payload);
+    return null;
+  } catch (error) {
+    logger.error(
+      `Failed to perform ${action.action_type} action ${action.id}:`,
+      error,
+    );
+    return String(error);
+  }
+}
@@ -0,0 +1,62 @@
+import type { Bot } from "../client/Bot.js";
+import type { Reminder } from "../api/types.js";
+import { logger } from "../utils/logger.js";
+
+/** How often due reminders are claimed from the backend. */
+const POLL_INTERVAL_MS = 15_000;
+
+/**
+ * Deliver reminders the backend's scheduler has marked due. Each claimed
+ * reminder is acked once sent, or failed so the backend retries it.
+ */
+export function startReminderWorker(client: Bot): void {
+  let running = false;
+
+  const tick = async () => {
+    if (running) return;
+    running = true;
+    try {
+      const reminders = await client.api.claimReminders();
+      for (const reminder of reminders) {
+        await deliver(client, reminder);
+      }
+    } catch (error) {
+      logger.error("Failed to claim reminders:", error);
+    } finally {
+      running = false;
+    }
+  };
+
+  setInterval(tick, POLL_INTERVAL_MS);
+  void tick();
+}
+
+async function deliver(client: Bot, reminder: Reminder): Promise<void> {
+  const content = `⏰ <@${reminder.user_id}> **Reminder:** ${reminder.message}`;
+
+  try {
+    // Fall back to a DM when the channel is gone or not writable
+    const channel = await client.channels
+      .fetch(reminder.channel_id)
+      .catch(() => null);
+    if (channel?.isSendable()) {
+      await channel.send({
+        content,
+        allowedMentions: { users: [reminder.user_id] },
+      });
+    } else {
+      const user = await client.users.fetch(reminder.user_id);
+      await user.send(content);
+    }
+  } catch (error) {
+    logger.error(`Failed to deliver reminder ${reminder.id}:`, error);
+    await client.api
+      .failReminder(reminder.id, String(error))
+      .catch((e) => logger.error(`Failed to report reminder ${reminder.id}:`, e));
+    return;
+  }
+
+  await client.api
+    .ackReminder(reminder.id)
+    .catch((e) => logger.error(`Failed to ack reminder ${reminder.id}:`, e));
+}